@@ -0,0 +1,308 @@
+extern crate lib_ruby_parser_nodes;
+
+use lib_ruby_parser_nodes::{Field, FieldType, Node};
+use std::cell::RefCell;
+
+// Node metadata is the same as in ../gen/nodes.rs.
+// Generated structs are not used, `epilogue` generates C accessors
+// for the corresponding `lib_ruby_parser::Node` variant.
+
+thread_local! {
+    static STRUCT_NAMES: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+    static DECLARATIONS: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+const HEADER_TEMPLATE: &str = "header/lib-ruby-parser.h.in";
+const HEADER_PATH: &str = "includes/lib-ruby-parser.h";
+
+fn map_field(field_type: &FieldType) -> String {
+    match field_type {
+        FieldType::Node => "Node",
+        FieldType::Nodes => "Vec<Node>",
+        FieldType::MaybeNode => "Option<Node>",
+        FieldType::Range => "Range",
+        FieldType::MaybeRange => "Option<Range>",
+        FieldType::Str => "String",
+        FieldType::MaybeStr => "Option<String>",
+        FieldType::Chars => "Vec<char>",
+        FieldType::StringValue => "StringValue",
+        FieldType::U8 => "u8",
+        FieldType::Usize => "usize",
+        FieldType::RawString => "String",
+        FieldType::RegexOptions => "Option<Node>",
+    }
+    .to_owned()
+}
+
+fn uses(_: &Node) -> Vec<String> {
+    vec![
+        "use crate::helpers::*;".to_owned(),
+        "use lib_ruby_parser::source::Range;".to_owned(),
+        "use lib_ruby_parser::{Node, StringValue};".to_owned(),
+    ]
+}
+
+fn prologue(_: &Node) -> String {
+    "".to_owned()
+}
+
+fn snake_case(s: &str) -> String {
+    let mut result = String::new();
+    for (idx, c) in s.chars().enumerate() {
+        if c.is_uppercase() {
+            if idx != 0 {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+// "int" -> "int ", "char *" -> "char *"
+fn c_type_prefix(c_type: &str) -> String {
+    if c_type.ends_with('*') {
+        c_type.to_owned()
+    } else {
+        format!("{} ", c_type)
+    }
+}
+
+// (Rust code, C declaration) of accessors of a single field
+fn field_accessors(node: &Node, field: &Field) -> Vec<(String, String)> {
+    let fn_name = format!(
+        "lib_ruby_parser_{}_{}",
+        snake_case(&node.struct_name).trim_end_matches('_'),
+        field.field_name
+    );
+    let accessor = |c_return_type: &str, rust_return_type: &str, default: &str, body: &str| {
+        (
+            format!(
+                "/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern \"C\" fn {fn_name}(node: *const Node) -> {rust_return_type} {{
+    match node_ref(node) {{
+        Some(Node::{struct_name}(inner)) => {body},
+        _ => {default},
+    }}
+}}
+",
+                fn_name = fn_name,
+                rust_return_type = rust_return_type,
+                struct_name = node.struct_name,
+                body = body.replace("FIELD", &format!("inner.{}", field.field_name)),
+                default = default
+            ),
+            format!(
+                "{}{}(const LibRubyParserNode *node);",
+                c_type_prefix(c_return_type),
+                fn_name
+            ),
+        )
+    };
+
+    match &field.field_type {
+        FieldType::Node => vec![accessor(
+            "const LibRubyParserNode *",
+            "*const Node",
+            "std::ptr::null()",
+            "&FIELD as *const Node",
+        )],
+        FieldType::MaybeNode | FieldType::RegexOptions => vec![accessor(
+            "const LibRubyParserNode *",
+            "*const Node",
+            "std::ptr::null()",
+            "maybe_node_ptr(&FIELD)",
+        )],
+        FieldType::Nodes => vec![(
+            format!(
+                "/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern \"C\" fn {fn_name}_len(node: *const Node) -> usize {{
+    match node_ref(node) {{
+        Some(Node::{struct_name}(inner)) => inner.{field_name}.len(),
+        _ => 0,
+    }}
+}}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern \"C\" fn {fn_name}_at(node: *const Node, idx: usize) -> *const Node {{
+    match node_ref(node) {{
+        Some(Node::{struct_name}(inner)) => maybe_node_ref_ptr(inner.{field_name}.get(idx)),
+        _ => std::ptr::null(),
+    }}
+}}
+",
+                fn_name = fn_name,
+                struct_name = node.struct_name,
+                field_name = field.field_name
+            ),
+            format!(
+                "size_t {fn_name}_len(const LibRubyParserNode *node);
+const LibRubyParserNode *{fn_name}_at(const LibRubyParserNode *node, size_t idx);",
+                fn_name = fn_name
+            ),
+        )],
+        FieldType::Range => vec![accessor(
+            "LibRubyParserRange",
+            "LibRubyParserRange",
+            "LibRubyParserRange::default()",
+            "LibRubyParserRange::from(&FIELD)",
+        )],
+        FieldType::MaybeRange => vec![accessor(
+            "LibRubyParserMaybeRange",
+            "LibRubyParserMaybeRange",
+            "LibRubyParserMaybeRange::default()",
+            "LibRubyParserMaybeRange::from(&FIELD)",
+        )],
+        FieldType::Str | FieldType::RawString => vec![accessor(
+            "LibRubyParserBytes",
+            "LibRubyParserBytes",
+            "LibRubyParserBytes::default()",
+            "LibRubyParserBytes::from(FIELD.as_bytes())",
+        )],
+        FieldType::MaybeStr => vec![accessor(
+            "LibRubyParserBytes",
+            "LibRubyParserBytes",
+            "LibRubyParserBytes::default()",
+            "LibRubyParserBytes::from_maybe_str(&FIELD)",
+        )],
+        FieldType::Chars => vec![accessor(
+            "LibRubyParserChars",
+            "LibRubyParserChars",
+            "LibRubyParserChars::default()",
+            "LibRubyParserChars::from(&FIELD[..])",
+        )],
+        FieldType::StringValue => vec![accessor(
+            "LibRubyParserBytes",
+            "LibRubyParserBytes",
+            "LibRubyParserBytes::default()",
+            "LibRubyParserBytes::from(&FIELD.bytes[..])",
+        )],
+        FieldType::U8 => vec![accessor("uint8_t", "u8", "0", "FIELD")],
+        FieldType::Usize => vec![accessor("size_t", "usize", "0", "FIELD")],
+    }
+}
+
+fn epilogue(node: &Node) -> String {
+    STRUCT_NAMES.with(|names| names.borrow_mut().push(node.struct_name.to_string()));
+
+    let mut code = vec![];
+    let mut declarations = vec![];
+    for field in node.fields.iter() {
+        for (rust, c) in field_accessors(node, field) {
+            code.push(rust);
+            declarations.push(c);
+        }
+    }
+    DECLARATIONS.with(|d| d.borrow_mut().push(declarations.join("\n")));
+
+    format!("\n{}", code.join("\n"))
+}
+
+// Nodes from gen/custom_nodes.rs are not described by lib-ruby-parser-nodes
+const CUSTOM_STRUCT_NAMES: &[&str] = &[
+    "AnonymousBlockarg",
+    "Error",
+    "ForwardedBlockarg",
+    "ForwardedKwrestarg",
+    "ForwardedRestarg",
+    "Itblock",
+    "MatchPattern",
+];
+
+fn generate_node_type() -> (String, String) {
+    let mut names = STRUCT_NAMES.with(|names| names.borrow().clone());
+    names.extend(CUSTOM_STRUCT_NAMES.iter().map(|name| name.to_string()));
+
+    let variants = names
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| format!("    {} = {},", name, idx))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let branches = names
+        .iter()
+        .map(|name| {
+            format!(
+                "        Node::{name}(_) => LibRubyParserNodeType::{name},",
+                name = name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let rust = format!(
+        "use lib_ruby_parser::Node;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LibRubyParserNodeType {{
+    Null = -1,
+{variants}
+}}
+
+pub(crate) fn node_type(node: &Node) -> LibRubyParserNodeType {{
+    match node {{
+{branches}
+    }}
+}}
+",
+        variants = variants,
+        branches = branches
+    );
+
+    let c_variants = names
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| {
+            format!(
+                "    LIB_RUBY_PARSER_NODE_{} = {},",
+                snake_case(name).trim_end_matches('_').to_uppercase(),
+                idx
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let c = format!(
+        "typedef enum LibRubyParserNodeType {{\n    LIB_RUBY_PARSER_NODE_NULL = -1,\n{}\n}} LibRubyParserNodeType;",
+        c_variants
+    );
+
+    (rust, c)
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", HEADER_TEMPLATE);
+
+    let options = lib_ruby_parser_nodes::Options {
+        target_dir: "src/nodes".to_owned(),
+        map_field: Box::new(map_field),
+        uses: Box::new(uses),
+        prologue: Box::new(prologue),
+        epilogue: Box::new(epilogue),
+    };
+
+    lib_ruby_parser_nodes::generate_nodes(&options).unwrap();
+    lib_ruby_parser_nodes::generate_mod(&options).unwrap();
+
+    let (node_type_rs, node_type_h) = generate_node_type();
+    std::fs::write("src/node_type.rs", node_type_rs).unwrap();
+
+    let declarations = DECLARATIONS.with(|d| d.borrow().join("\n\n"));
+    let header = std::fs::read_to_string(HEADER_TEMPLATE)
+        .unwrap()
+        .replace("/* NODE_TYPE */", &node_type_h)
+        .replace("/* NODE_ACCESSORS */", &declarations);
+    std::fs::create_dir_all("includes").unwrap();
+    std::fs::write(HEADER_PATH, header).unwrap();
+}
@@ -0,0 +1,129 @@
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::Node;
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LibRubyParserRange {
+    pub begin: usize,
+    pub end: usize,
+}
+
+impl From<&Range> for LibRubyParserRange {
+    fn from(range: &Range) -> Self {
+        Self {
+            begin: range.begin_pos,
+            end: range.end_pos,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LibRubyParserMaybeRange {
+    pub present: bool,
+    pub begin: usize,
+    pub end: usize,
+}
+
+impl From<&Option<Range>> for LibRubyParserMaybeRange {
+    fn from(range: &Option<Range>) -> Self {
+        match range {
+            Some(range) => Self {
+                present: true,
+                begin: range.begin_pos,
+                end: range.end_pos,
+            },
+            None => Self::default(),
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LibRubyParserBytes {
+    pub ptr: *const u8,
+    pub len: usize,
+}
+
+impl Default for LibRubyParserBytes {
+    fn default() -> Self {
+        Self {
+            ptr: std::ptr::null(),
+            len: 0,
+        }
+    }
+}
+
+impl From<&[u8]> for LibRubyParserBytes {
+    fn from(bytes: &[u8]) -> Self {
+        Self {
+            ptr: bytes.as_ptr(),
+            len: bytes.len(),
+        }
+    }
+}
+
+impl LibRubyParserBytes {
+    pub(crate) fn from_maybe_str(s: &Option<String>) -> Self {
+        match s {
+            Some(s) => Self::from(s.as_bytes()),
+            None => Self::default(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ptr` must be NULL or point to `len` bytes that are still alive
+    /// (i.e. the result that they were taken from is not released yet)
+    pub unsafe fn as_slice(&self) -> Option<&[u8]> {
+        if self.ptr.is_null() {
+            None
+        } else {
+            Some(std::slice::from_raw_parts(self.ptr, self.len))
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct LibRubyParserChars {
+    pub ptr: *const u32,
+    pub len: usize,
+}
+
+impl Default for LibRubyParserChars {
+    fn default() -> Self {
+        Self {
+            ptr: std::ptr::null(),
+            len: 0,
+        }
+    }
+}
+
+impl From<&[char]> for LibRubyParserChars {
+    fn from(chars: &[char]) -> Self {
+        // char has the same size and alignment as u32
+        Self {
+            ptr: chars.as_ptr() as *const u32,
+            len: chars.len(),
+        }
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a valid pointer
+pub(crate) unsafe fn node_ref<'a>(node: *const Node) -> Option<&'a Node> {
+    node.as_ref()
+}
+
+pub(crate) fn maybe_node_ptr(node: &Option<Node>) -> *const Node {
+    maybe_node_ref_ptr(node.as_ref())
+}
+
+pub(crate) fn maybe_node_ref_ptr(node: Option<&Node>) -> *const Node {
+    match node {
+        Some(node) => node as *const Node,
+        None => std::ptr::null(),
+    }
+}
@@ -0,0 +1,459 @@
+//! C bindings for lib-ruby-parser.
+//!
+//! `includes/lib-ruby-parser.h` is generated by `build.rs`,
+//! node accessors are generated from lib-ruby-parser-nodes metadata.
+//!
+//! # Safety
+//!
+//! Functions take pointers from C code as is, so all of them are `unsafe`.
+//! A `LibRubyParserResult *` must be NULL or returned by `lib_ruby_parser_parse`
+//! and not released yet, a `LibRubyParserNode *` must be NULL or returned by
+//! one of `lib_ruby_parser_*` functions from a result that is not released yet.
+//! NULL pointers are handled the same way as absent values.
+
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+
+use lib_ruby_parser::{ErrorLevel, Node, Parser, ParserOptions, ParserResult};
+
+mod helpers;
+pub use helpers::{
+    LibRubyParserBytes, LibRubyParserChars, LibRubyParserMaybeRange, LibRubyParserRange,
+};
+
+mod node_type;
+pub use node_type::LibRubyParserNodeType;
+
+// Generated structs are unused, only accessors are exported
+#[allow(dead_code, unused_imports, clippy::all)]
+mod nodes;
+
+#[repr(C)]
+pub struct LibRubyParserOptions {
+    pub buffer_name: *const c_char,
+    pub debug: bool,
+    pub error_recovery: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LibRubyParserErrorLevel {
+    Warning = 0,
+    Error = 1,
+    Note = 2,
+    Fatal = 3,
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a valid pointer
+unsafe fn result_ref<'a>(result: *const ParserResult) -> Option<&'a ParserResult> {
+    result.as_ref()
+}
+
+/// # Safety
+///
+/// `input` must point to `len` bytes, `options` must be NULL or a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_parse(
+    input: *const u8,
+    len: usize,
+    options: *const LibRubyParserOptions,
+) -> *mut ParserResult {
+    let input = if input.is_null() {
+        &[]
+    } else {
+        std::slice::from_raw_parts(input, len)
+    };
+
+    let mut parser_options = ParserOptions::default();
+    if let Some(options) = options.as_ref() {
+        if !options.buffer_name.is_null() {
+            parser_options.buffer_name = CStr::from_ptr(options.buffer_name)
+                .to_string_lossy()
+                .into_owned();
+        }
+        parser_options.debug = options.debug;
+        parser_options.error_recovery = options.error_recovery;
+    }
+
+    let result = Parser::new(input, parser_options).do_parse();
+    Box::into_raw(Box::new(result))
+}
+
+/// # Safety
+///
+/// `result` must be returned by `lib_ruby_parser_parse` and must not be used after this call
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_free(result: *mut ParserResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result))
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_ast(result: *const ParserResult) -> *const Node {
+    match result_ref(result).and_then(|result| result.ast.as_ref()) {
+        Some(ast) => ast as *const Node,
+        None => std::ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_input(result: *const ParserResult) -> LibRubyParserBytes {
+    match result_ref(result) {
+        Some(result) => LibRubyParserBytes::from(&result.input.bytes[..]),
+        None => LibRubyParserBytes::default(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_tokens_len(result: *const ParserResult) -> usize {
+    result_ref(result).map_or(0, |result| result.tokens.len())
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_token_type(
+    result: *const ParserResult,
+    idx: usize,
+) -> i32 {
+    match result_ref(result).and_then(|result| result.tokens.get(idx)) {
+        Some(token) => token.token_type,
+        None => -1,
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_token_value(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserBytes {
+    match result_ref(result).and_then(|result| result.tokens.get(idx)) {
+        Some(token) => LibRubyParserBytes::from(token.as_bytes()),
+        None => LibRubyParserBytes::default(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_token_loc(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserRange {
+    match result_ref(result).and_then(|result| result.tokens.get(idx)) {
+        Some(token) => LibRubyParserRange {
+            begin: token.loc.begin,
+            end: token.loc.end,
+        },
+        None => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_diagnostics_len(result: *const ParserResult) -> usize {
+    result_ref(result).map_or(0, |result| result.diagnostics.len())
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_diagnostic_level(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserErrorLevel {
+    match result_ref(result).and_then(|result| result.diagnostics.get(idx)) {
+        Some(diagnostic) => match diagnostic.level {
+            ErrorLevel::Note => LibRubyParserErrorLevel::Note,
+            ErrorLevel::Warning => LibRubyParserErrorLevel::Warning,
+            ErrorLevel::Error => LibRubyParserErrorLevel::Error,
+            ErrorLevel::Fatal => LibRubyParserErrorLevel::Fatal,
+        },
+        None => LibRubyParserErrorLevel::Error,
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_diagnostic_range(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserRange {
+    match result_ref(result).and_then(|result| result.diagnostics.get(idx)) {
+        Some(diagnostic) => LibRubyParserRange::from(&diagnostic.range),
+        None => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_diagnostic_code(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserBytes {
+    match result_ref(result).and_then(|result| result.diagnostics.get(idx)) {
+        Some(diagnostic) => LibRubyParserBytes::from(diagnostic.code().as_bytes()),
+        None => LibRubyParserBytes::default(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_diagnostic_message(
+    result: *const ParserResult,
+    idx: usize,
+) -> *mut c_char {
+    match result_ref(result).and_then(|result| result.diagnostics.get(idx)) {
+        Some(diagnostic) => {
+            let message = diagnostic.render_message().replace('\0', "");
+            CString::new(message)
+                .expect("NUL bytes are removed")
+                .into_raw()
+        }
+        None => std::ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_comments_len(result: *const ParserResult) -> usize {
+    result_ref(result).map_or(0, |result| result.comments.len())
+}
+
+/// # Safety
+///
+/// `result` must be NULL or a result of `lib_ruby_parser_parse` that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_result_comment_range(
+    result: *const ParserResult,
+    idx: usize,
+) -> LibRubyParserRange {
+    match result_ref(result).and_then(|result| result.comments.get(idx)) {
+        Some(comment) => LibRubyParserRange::from(&comment.location),
+        None => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `s` must be returned by one of `lib_ruby_parser_*` functions that return `char *`
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s))
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_type(node: *const Node) -> LibRubyParserNodeType {
+    match helpers::node_ref(node) {
+        Some(node) => node_type::node_type(node),
+        None => LibRubyParserNodeType::Null,
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_node_expression(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(node) => LibRubyParserRange::from(node.expression()),
+        None => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_anonymous_blockarg_expression_l(
+    node: *const Node,
+) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::AnonymousBlockarg(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_error_expression_l(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::Error(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_forwarded_blockarg_expression_l(
+    node: *const Node,
+) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::ForwardedBlockarg(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_forwarded_kwrestarg_expression_l(
+    node: *const Node,
+) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::ForwardedKwrestarg(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_forwarded_restarg_expression_l(
+    node: *const Node,
+) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::ForwardedRestarg(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_itblock_call(node: *const Node) -> *const Node {
+    match helpers::node_ref(node) {
+        Some(Node::Itblock(inner)) => &inner.call as *const Node,
+        _ => std::ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_itblock_body(node: *const Node) -> *const Node {
+    match helpers::node_ref(node) {
+        Some(Node::Itblock(inner)) => &inner.body as *const Node,
+        _ => std::ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_itblock_begin_l(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::Itblock(inner)) => LibRubyParserRange::from(&inner.begin_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_itblock_end_l(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::Itblock(inner)) => LibRubyParserRange::from(&inner.end_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_itblock_expression_l(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::Itblock(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_match_pattern_value(node: *const Node) -> *const Node {
+    match helpers::node_ref(node) {
+        Some(Node::MatchPattern(inner)) => &inner.value as *const Node,
+        _ => std::ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_match_pattern_pattern(node: *const Node) -> *const Node {
+    match helpers::node_ref(node) {
+        Some(Node::MatchPattern(inner)) => &inner.pattern as *const Node,
+        _ => std::ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_match_pattern_operator_l(node: *const Node) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::MatchPattern(inner)) => LibRubyParserRange::from(&inner.operator_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
+
+/// # Safety
+///
+/// `node` must be NULL or a node of a result that is not released yet
+#[no_mangle]
+pub unsafe extern "C" fn lib_ruby_parser_match_pattern_expression_l(
+    node: *const Node,
+) -> LibRubyParserRange {
+    match helpers::node_ref(node) {
+        Some(Node::MatchPattern(inner)) => LibRubyParserRange::from(&inner.expression_l),
+        _ => LibRubyParserRange::default(),
+    }
+}
@@ -0,0 +1,171 @@
+// Uses the library the same way C code does, through declarations from the header
+extern crate lib_ruby_parser_capi;
+
+use lib_ruby_parser_capi::{
+    LibRubyParserBytes, LibRubyParserMaybeRange, LibRubyParserNodeType, LibRubyParserRange,
+};
+use std::ffi::CStr;
+use std::os::raw::c_char;
+
+#[repr(C)]
+struct LibRubyParserResult {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct LibRubyParserNode {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+struct LibRubyParserOptions {
+    buffer_name: *const c_char,
+    debug: bool,
+    error_recovery: bool,
+}
+
+extern "C" {
+    fn lib_ruby_parser_parse(
+        input: *const u8,
+        len: usize,
+        options: *const LibRubyParserOptions,
+    ) -> *mut LibRubyParserResult;
+    fn lib_ruby_parser_result_free(result: *mut LibRubyParserResult);
+    fn lib_ruby_parser_result_ast(result: *const LibRubyParserResult) -> *const LibRubyParserNode;
+    fn lib_ruby_parser_result_tokens_len(result: *const LibRubyParserResult) -> usize;
+    fn lib_ruby_parser_result_token_value(
+        result: *const LibRubyParserResult,
+        idx: usize,
+    ) -> LibRubyParserBytes;
+    fn lib_ruby_parser_result_diagnostics_len(result: *const LibRubyParserResult) -> usize;
+    fn lib_ruby_parser_result_diagnostic_message(
+        result: *const LibRubyParserResult,
+        idx: usize,
+    ) -> *mut c_char;
+    fn lib_ruby_parser_string_free(s: *mut c_char);
+
+    fn lib_ruby_parser_node_type(node: *const LibRubyParserNode) -> LibRubyParserNodeType;
+    fn lib_ruby_parser_node_expression(node: *const LibRubyParserNode) -> LibRubyParserRange;
+
+    fn lib_ruby_parser_send_recv(node: *const LibRubyParserNode) -> *const LibRubyParserNode;
+    fn lib_ruby_parser_send_method_name(node: *const LibRubyParserNode) -> LibRubyParserBytes;
+    fn lib_ruby_parser_send_args_len(node: *const LibRubyParserNode) -> usize;
+    fn lib_ruby_parser_send_args_at(
+        node: *const LibRubyParserNode,
+        idx: usize,
+    ) -> *const LibRubyParserNode;
+    fn lib_ruby_parser_send_dot_l(node: *const LibRubyParserNode) -> LibRubyParserMaybeRange;
+    fn lib_ruby_parser_send_selector_l(node: *const LibRubyParserNode) -> LibRubyParserMaybeRange;
+    fn lib_ruby_parser_int_value(node: *const LibRubyParserNode) -> LibRubyParserBytes;
+}
+
+fn parse(src: &str) -> *mut LibRubyParserResult {
+    let options = LibRubyParserOptions {
+        buffer_name: b"(capi_test)\0".as_ptr() as *const c_char,
+        debug: false,
+        error_recovery: false,
+    };
+    unsafe { lib_ruby_parser_parse(src.as_ptr(), src.len(), &options) }
+}
+
+#[test]
+fn test_ast() {
+    let result = parse("foo(42)");
+
+    unsafe {
+        let ast = lib_ruby_parser_result_ast(result);
+        assert!(!ast.is_null());
+        assert_eq!(lib_ruby_parser_node_type(ast), LibRubyParserNodeType::Send);
+        assert_eq!(
+            lib_ruby_parser_node_expression(ast),
+            LibRubyParserRange { begin: 0, end: 7 }
+        );
+
+        assert!(lib_ruby_parser_send_recv(ast).is_null());
+        assert_eq!(
+            lib_ruby_parser_send_method_name(ast).as_slice(),
+            Some(&b"foo"[..])
+        );
+        assert_eq!(
+            lib_ruby_parser_send_dot_l(ast),
+            LibRubyParserMaybeRange::default()
+        );
+        assert_eq!(
+            lib_ruby_parser_send_selector_l(ast),
+            LibRubyParserMaybeRange {
+                present: true,
+                begin: 0,
+                end: 3
+            }
+        );
+
+        assert_eq!(lib_ruby_parser_send_args_len(ast), 1);
+        let arg = lib_ruby_parser_send_args_at(ast, 0);
+        assert_eq!(lib_ruby_parser_node_type(arg), LibRubyParserNodeType::Int);
+        assert_eq!(lib_ruby_parser_int_value(arg).as_slice(), Some(&b"42"[..]));
+        assert!(lib_ruby_parser_send_args_at(ast, 1).is_null());
+
+        lib_ruby_parser_result_free(result);
+    }
+}
+
+#[test]
+fn test_accessor_of_a_different_node() {
+    let result = parse("42");
+
+    unsafe {
+        let ast = lib_ruby_parser_result_ast(result);
+        assert_eq!(lib_ruby_parser_node_type(ast), LibRubyParserNodeType::Int);
+        assert_eq!(lib_ruby_parser_send_method_name(ast).as_slice(), None);
+        assert_eq!(lib_ruby_parser_send_args_len(ast), 0);
+
+        lib_ruby_parser_result_free(result);
+    }
+}
+
+#[test]
+fn test_null_node() {
+    unsafe {
+        let node = std::ptr::null();
+        assert_eq!(lib_ruby_parser_node_type(node), LibRubyParserNodeType::Null);
+        assert_eq!(
+            lib_ruby_parser_node_expression(node),
+            LibRubyParserRange::default()
+        );
+        assert!(lib_ruby_parser_send_recv(node).is_null());
+    }
+}
+
+#[test]
+fn test_tokens() {
+    let result = parse("foo");
+
+    unsafe {
+        // tIDENTIFIER, EOF
+        assert_eq!(lib_ruby_parser_result_tokens_len(result), 2);
+        assert_eq!(
+            lib_ruby_parser_result_token_value(result, 0).as_slice(),
+            Some(&b"foo"[..])
+        );
+
+        lib_ruby_parser_result_free(result);
+    }
+}
+
+#[test]
+fn test_diagnostics() {
+    let result = parse("foo(");
+
+    unsafe {
+        assert!(lib_ruby_parser_result_ast(result).is_null());
+        assert_eq!(lib_ruby_parser_result_diagnostics_len(result), 1);
+
+        let message = lib_ruby_parser_result_diagnostic_message(result, 0);
+        assert!(!CStr::from_ptr(message).to_bytes().is_empty());
+        lib_ruby_parser_string_free(message);
+
+        assert!(lib_ruby_parser_result_diagnostic_message(result, 1).is_null());
+
+        lib_ruby_parser_result_free(result);
+    }
+}
@@ -0,0 +1,184 @@
+use lib_ruby_parser_nodes::{Field, FieldType, Node};
+
+// Nodes that are not (yet) described by lib-ruby-parser-nodes
+
+fn node(struct_name: &str, str_type: &str, comment: &str, fields: Vec<Field>) -> Node {
+    Node {
+        struct_name: struct_name.to_owned(),
+        str_type: str_type.to_owned(),
+        filename: str_type.to_owned(),
+        fields,
+        comment: Some(comment.to_owned()),
+    }
+}
+
+fn field(field_name: &str, field_type: FieldType, comment: &str) -> Field {
+    Field {
+        field_name: field_name.to_owned(),
+        field_type,
+        always_print: false,
+        comment: Some(comment.to_owned()),
+    }
+}
+
+pub fn custom_nodes() -> Vec<Node> {
+    vec![
+        node(
+            "AnonymousBlockarg",
+            "anonymous_blockarg",
+            "Anonymous block argument, `&` in `def m(&); end`.
+
+Can only be passed further as `&` (`ForwardedBlockarg`).",
+            vec![field(
+                "expression_l",
+                FieldType::Range,
+                "Location of the `&` operator
+
+```text
+def m(&); end
+      ~
+```",
+            )],
+        ),
+        node(
+            "Error",
+            "error",
+            "Placeholder for code that couldn't be parsed.
+
+Emitted only when `ParserOptions::error_recovery` is set,
+replaces all statements of the enclosing list before the statement
+that parsing continues from.",
+            vec![field(
+                "expression_l",
+                FieldType::Range,
+                "Location from the invalid token to the statement that parsing continues from
+
+```text
+foo; bar(1, ; baz
+            ~~
+```",
+            )],
+        ),
+        node(
+            "ForwardedBlockarg",
+            "forwarded_blockarg",
+            "Forwarded anonymous block argument, `&` in `def m(&) = n(&)`.",
+            vec![field(
+                "expression_l",
+                FieldType::Range,
+                "Location of the `&` operator
+
+```text
+def m(&) = n(&)
+             ~
+```",
+            )],
+        ),
+        node(
+            "ForwardedKwrestarg",
+            "forwarded_kwrestarg",
+            "Forwarded anonymous keyword rest argument, `**` in `def m(**) = n(**)`.",
+            vec![field(
+                "expression_l",
+                FieldType::Range,
+                "Location of the `**` operator
+
+```text
+def m(**) = n(**)
+              ~~
+```",
+            )],
+        ),
+        node(
+            "ForwardedRestarg",
+            "forwarded_restarg",
+            "Forwarded anonymous rest argument, `*` in `def m(*) = n(*)`.",
+            vec![field(
+                "expression_l",
+                FieldType::Range,
+                "Location of the `*` operator
+
+```text
+def m(*) = n(*)
+             ~
+```",
+            )],
+        ),
+        node(
+            "Itblock",
+            "itblock",
+            "Block that uses the implicit `it` parameter, `proc { it }`.
+
+Its counterpart with numbered parameters is `Numblock`.",
+            vec![
+                field("call", FieldType::Node, "Method call that takes a block"),
+                field("body", FieldType::Node, "Block body"),
+                field(
+                    "begin_l",
+                    FieldType::Range,
+                    "Location of the open brace or `do`
+
+```text
+proc { it }
+     ~
+```",
+                ),
+                field(
+                    "end_l",
+                    FieldType::Range,
+                    "Location of the close brace or `end`
+
+```text
+proc { it }
+          ~
+```",
+                ),
+                field(
+                    "expression_l",
+                    FieldType::Range,
+                    "Location of the full expression
+
+```text
+proc { it }
+~~~~~~~~~~~
+```",
+                ),
+            ],
+        ),
+        node(
+            "MatchPattern",
+            "match_pattern",
+            "One-line pattern matching that raises on mismatch, `value => pattern`.
+
+Its boolean counterpart `value in pattern` is `InMatch`.",
+            vec![
+                field("value", FieldType::Node, "Value that is used for matching"),
+                field(
+                    "pattern",
+                    FieldType::Node,
+                    "Pattern that is used for matching",
+                ),
+                field(
+                    "operator_l",
+                    FieldType::Range,
+                    "Location of the `=>` operator
+
+```text
+foo => pat
+    ~~
+```",
+                ),
+                field(
+                    "expression_l",
+                    FieldType::Range,
+                    "Location of the full expression
+
+```text
+foo => pat
+~~~~~~~~~~
+```",
+                ),
+            ],
+        ),
+    ]
+}
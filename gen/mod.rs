@@ -1,4 +1,6 @@
 #[cfg(feature = "lib-ruby-parser-nodes")]
+mod custom_nodes;
+#[cfg(feature = "lib-ruby-parser-nodes")]
 mod nodes;
 #[cfg(feature = "lib-ruby-parser-nodes")]
 pub use nodes::generate_nodes;
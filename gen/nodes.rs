@@ -2,6 +2,8 @@ extern crate lib_ruby_parser_nodes;
 
 use lib_ruby_parser_nodes::{Field, FieldType, Node};
 
+use super::custom_nodes::custom_nodes;
+
 fn map_field(field_type: &FieldType) -> String {
     match field_type {
         FieldType::Node => "Node",
@@ -143,6 +145,51 @@ fn print_field_with_locs(field: &Field) -> Option<String> {
     }
 }
 
+fn for_each_loc_mut_field(field: &Field) -> Option<String> {
+    let offset = "        ";
+
+    match &field.field_type {
+        FieldType::Node => Some(format!(
+            "{offset}self.{field_name}.inner_mut().for_each_loc_mut(f);",
+            offset = offset,
+            field_name = field.field_name
+        )),
+        FieldType::Nodes => Some(format!(
+            "{offset}for node in self.{field_name}.iter_mut() {{
+{offset}    node.inner_mut().for_each_loc_mut(f);
+{offset}}}",
+            offset = offset,
+            field_name = field.field_name
+        )),
+        FieldType::MaybeNode | FieldType::RegexOptions => Some(format!(
+            "{offset}if let Some(node) = &mut self.{field_name} {{
+{offset}    node.inner_mut().for_each_loc_mut(f);
+{offset}}}",
+            offset = offset,
+            field_name = field.field_name
+        )),
+        FieldType::Range => Some(format!(
+            "{offset}f(&mut self.{field_name});",
+            offset = offset,
+            field_name = field.field_name
+        )),
+        FieldType::MaybeRange => Some(format!(
+            "{offset}if let Some(range) = &mut self.{field_name} {{
+{offset}    f(range);
+{offset}}}",
+            offset = offset,
+            field_name = field.field_name
+        )),
+        FieldType::Str
+        | FieldType::MaybeStr
+        | FieldType::Chars
+        | FieldType::StringValue
+        | FieldType::U8
+        | FieldType::Usize
+        | FieldType::RawString => None,
+    }
+}
+
 fn uses(node: &Node) -> Vec<String> {
     let mut uses = vec![];
     uses.push("use crate::nodes::InnerNode;".to_owned());
@@ -153,14 +200,17 @@ fn uses(node: &Node) -> Vec<String> {
     {
         uses.push("use crate::nodes::InspectVec;".to_owned());
     }
-    uses.push("use crate::source::Range;".to_owned());
+    // `Node` has its own `structurally_eq` and `hash_structure`
     if node
         .fields
         .iter()
-        .any(|f| f.field_type.has_reference_to_node())
+        .any(|f| !f.field_type.has_reference_to_range() && f.field_type != FieldType::Node)
     {
-        uses.push("use crate::Node;".to_owned());
+        uses.push("use crate::nodes::Structural;".to_owned());
     }
+    uses.push("use crate::source::Range;".to_owned());
+    // `children` returns `Vec<&Node>` even if there are no child nodes
+    uses.push("use crate::Node;".to_owned());
     if node
         .fields
         .iter()
@@ -213,8 +263,207 @@ fn print_with_locs_fn_declaration(node: &Node) -> String {
     )
 }
 
+fn for_each_loc_mut_fn_declaration(node: &Node) -> String {
+    let stmts = node
+        .fields
+        .iter()
+        .filter_map(for_each_loc_mut_field)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "fn for_each_loc_mut(&mut self, f: &mut dyn FnMut(&mut Range)) {{
+{}
+    }}",
+        stmts
+    )
+}
+
+fn children_fn_declaration(node: &Node) -> String {
+    let fields = node
+        .fields
+        .iter()
+        .filter(|f| {
+            matches!(
+                f.field_type,
+                FieldType::Node | FieldType::Nodes | FieldType::MaybeNode | FieldType::RegexOptions
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Leading single nodes go to `vec![...]`, pushing them right after `vec![]` is a clippy warning
+    let leading = fields
+        .iter()
+        .take_while(|f| f.field_type == FieldType::Node)
+        .map(|f| format!("&self.{}", f.field_name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let stmts = fields
+        .iter()
+        .skip_while(|f| f.field_type == FieldType::Node)
+        .map(|field| match field.field_type {
+            FieldType::Node => format!("        children.push(&self.{});", field.field_name),
+            FieldType::Nodes => {
+                format!("        children.extend(self.{}.iter());", field.field_name)
+            }
+            _ => format!(
+                "        children.extend(self.{}.as_ref());",
+                field.field_name
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    if stmts.is_empty() {
+        return format!(
+            "fn children(&self) -> Vec<&Node> {{
+        vec![{}]
+    }}",
+            leading
+        );
+    }
+
+    format!(
+        "fn children(&self) -> Vec<&Node> {{
+        let mut children = vec![{}];
+{}
+        children
+    }}",
+        leading,
+        stmts.join("\n")
+    )
+}
+
+// Name of the field in `traverse::Find` patterns
+fn pattern_item(field: &Field) -> String {
+    match (&field.field_type, field.field_name.as_str()) {
+        (FieldType::Nodes, "args")
+        | (FieldType::Nodes, "names")
+        | (FieldType::Nodes, "patterns") => "arg",
+        (FieldType::Nodes, "elements") => "element",
+        (FieldType::Nodes, "in_bodies") => "in_body",
+        (FieldType::Nodes, "indexes") => "index",
+        (FieldType::Nodes, "items") => "item",
+        (FieldType::Nodes, "pairs") => "pair",
+        (FieldType::Nodes, "parts") => "part",
+        (FieldType::Nodes, "rescue_bodies") => "rescue_body",
+        (FieldType::Nodes, "statements") => "stmt",
+        (FieldType::Nodes, "when_bodies") => "when_body",
+        (FieldType::Nodes, other) => panic!("no pattern item for list field {}", other),
+        (_, other) => other.trim_end_matches('_'),
+    }
+    .to_owned()
+}
+
+fn structural_fields(node: &Node) -> Vec<&Field> {
+    node.fields
+        .iter()
+        .filter(|f| !f.field_type.has_reference_to_range())
+        .collect()
+}
+
+fn structurally_eq_fn_declaration(node: &Node) -> String {
+    let fields = structural_fields(node);
+    if fields.is_empty() {
+        return "pub(crate) fn structurally_eq(&self, _other: &Self) -> bool {
+        true
+    }"
+        .to_owned();
+    }
+
+    let stmts = fields
+        .iter()
+        .map(|f| {
+            format!(
+                "self.{field_name}.structurally_eq(&other.{field_name})",
+                field_name = f.field_name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n            && ");
+
+    format!(
+        "pub(crate) fn structurally_eq(&self, other: &Self) -> bool {{
+        {}
+    }}",
+        stmts
+    )
+}
+
+fn hash_structure_fn_declaration(node: &Node) -> String {
+    let fields = structural_fields(node);
+    if fields.is_empty() {
+        return "pub(crate) fn hash_structure<H: std::hash::Hasher>(&self, _state: &mut H) {}"
+            .to_owned();
+    }
+
+    let stmts = fields
+        .iter()
+        .map(|f| format!("        self.{}.hash_structure(state);", f.field_name))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "pub(crate) fn hash_structure<H: std::hash::Hasher>(&self, state: &mut H) {{
+{}
+    }}",
+        stmts
+    )
+}
+
+fn structural_diff_fn_declaration(node: &Node) -> String {
+    let fields = structural_fields(node);
+    if fields.is_empty() {
+        return "pub(crate) fn structural_diff(&self, _other: &Self) -> Option<Vec<String>> {
+        None
+    }"
+        .to_owned();
+    }
+
+    let stmts = fields
+        .iter()
+        .map(|f| {
+            let diff = match f.field_type {
+                FieldType::Node => format!(
+                    "crate::nodes::diff_node(&self.{field_name}, &other.{field_name}, \"{item}\")",
+                    field_name = f.field_name,
+                    item = pattern_item(f)
+                ),
+                FieldType::MaybeNode | FieldType::RegexOptions => format!(
+                    "crate::nodes::diff_maybe_node(&self.{field_name}, &other.{field_name}, \"{item}\")",
+                    field_name = f.field_name,
+                    item = pattern_item(f)
+                ),
+                FieldType::Nodes => format!(
+                    "crate::nodes::diff_nodes(&self.{field_name}, &other.{field_name}, \"{item}\")",
+                    field_name = f.field_name,
+                    item = pattern_item(f)
+                ),
+                _ => format!(
+                    "crate::nodes::diff_value(&self.{field_name}, &other.{field_name})",
+                    field_name = f.field_name
+                ),
+            };
+            format!(
+                "        if let Some(path) = {} {{
+            return Some(path);
+        }}",
+                diff
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    format!(
+        "pub(crate) fn structural_diff(&self, other: &Self) -> Option<Vec<String>> {{
+{}
+        None
+    }}",
+        stmts
+    )
+}
+
 fn prologue(_: &Node) -> String {
-    "".to_owned()
+    "#[cfg_attr(feature = \"serde\", derive(serde::Serialize, serde::Deserialize))]".to_owned()
 }
 
 fn epilogue(node: &Node) -> String {
@@ -232,12 +481,29 @@ impl InnerNode for {struct_name} {{
     }}
 
     {print_with_locs}
+
+    {for_each_loc_mut}
+
+    {children}
+}}
+
+impl {struct_name} {{
+    {structurally_eq}
+
+    {hash_structure}
+
+    {structural_diff}
 }}
 ",
         struct_name = node.struct_name,
         inspected_children = inspected_children_fn_declaration(node),
         str_type = node.str_type,
-        print_with_locs = print_with_locs_fn_declaration(node)
+        print_with_locs = print_with_locs_fn_declaration(node),
+        for_each_loc_mut = for_each_loc_mut_fn_declaration(node),
+        children = children_fn_declaration(node),
+        structurally_eq = structurally_eq_fn_declaration(node),
+        hash_structure = hash_structure_fn_declaration(node),
+        structural_diff = structural_diff_fn_declaration(node)
     )
 }
 
@@ -251,5 +517,33 @@ pub fn generate_nodes() {
     };
 
     lib_ruby_parser_nodes::generate_nodes(&options).unwrap();
-    lib_ruby_parser_nodes::generate_mod(&options).unwrap();
+
+    let custom_nodes = custom_nodes();
+    for node in custom_nodes.iter() {
+        std::fs::write(
+            &format!("{}/{}.rs", options.target_dir, node.filename),
+            node.code(&options),
+        )
+        .unwrap();
+    }
+
+    let mut nodes = lib_ruby_parser_nodes::nodes().unwrap();
+    nodes.extend(custom_nodes);
+    generate_mod(&options.target_dir, &nodes);
+}
+
+fn generate_mod(target_dir: &str, nodes: &[Node]) {
+    let mod_content = nodes
+        .iter()
+        .map(|node| {
+            format!(
+                "mod {mod_name};\npub use {mod_name}::{struct_name};\n",
+                mod_name = node.filename,
+                struct_name = node.struct_name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    std::fs::write(&format!("{}/mod.rs", target_dir), &mod_content).unwrap();
 }
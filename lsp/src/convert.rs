@@ -0,0 +1,34 @@
+use lib_ruby_parser::source::buffer::Input;
+use lib_ruby_parser::source::Range;
+use serde_json::{json, Value};
+
+// LSP positions are (line, UTF-16 code unit)
+pub(crate) fn position(input: &Input, pos: usize) -> Value {
+    let (line, character) = input
+        .line_utf16_col_for_pos(pos)
+        .or_else(|| input.line_col_for_pos(pos))
+        .unwrap_or((0, 0));
+    json!({ "line": line, "character": character })
+}
+
+pub(crate) fn range(input: &Input, range: &Range) -> Value {
+    json!({
+        "start": position(input, range.begin_pos),
+        "end": position(input, range.end_pos),
+    })
+}
+
+// Byte offset of an LSP position, characters after the end of line point to the end of line
+pub(crate) fn offset(input: &Input, position: &Value) -> Option<usize> {
+    let line = position.get("line")?.as_u64()? as usize;
+    let character = position.get("character")?.as_u64()? as usize;
+
+    input
+        .pos_for_line_utf16_col(line, character)
+        .or_else(|| input.lines.get(line).map(|line| line.line_end()))
+}
+
+// 0-based line of a given position
+pub(crate) fn line(input: &Input, pos: usize) -> usize {
+    input.line_for_pos(pos).unwrap_or(0)
+}
@@ -0,0 +1,119 @@
+use crate::convert;
+use lib_ruby_parser::source::buffer::Input;
+use lib_ruby_parser::source::{Comment, CommentType};
+use lib_ruby_parser::traverse::Visitor;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+/// Returns `FoldingRange`s of multi-line constructions and comments
+pub(crate) fn folding_ranges(
+    ast: Option<&Node>,
+    comments: &[Comment],
+    input: &Input,
+) -> Vec<Value> {
+    let mut collector = FoldingCollector {
+        input,
+        ranges: vec![],
+    };
+    if let Some(ast) = ast {
+        collector.visit(ast);
+    }
+    collector.ranges.extend(comment_ranges(comments, input));
+    collector.ranges
+}
+
+struct FoldingCollector<'a> {
+    input: &'a Input,
+    ranges: Vec<Value>,
+}
+
+impl Visitor for FoldingCollector<'_> {
+    fn visit(&mut self, node: &Node) {
+        if is_foldable(node) {
+            let expression_l = node.expression();
+            let start_line = convert::line(self.input, expression_l.begin_pos);
+            // the last line (with `end` or `}`) stays visible
+            let end_line = convert::line(self.input, expression_l.end_pos).saturating_sub(1);
+            if end_line > start_line {
+                self.ranges.push(json!({
+                    "startLine": start_line,
+                    "endLine": end_line,
+                    "kind": "region",
+                }))
+            }
+        }
+        self.walk(node)
+    }
+}
+
+fn is_foldable(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Class(_)
+            | Node::Module(_)
+            | Node::SClass(_)
+            | Node::Def(_)
+            | Node::Defs(_)
+            | Node::Block(_)
+            | Node::Numblock(_)
+            | Node::KwBegin(_)
+            | Node::If(_)
+            | Node::Case(_)
+            | Node::CaseMatch(_)
+            | Node::While(_)
+            | Node::Until(_)
+            | Node::For(_)
+            | Node::Array(_)
+            | Node::Hash(_)
+    )
+}
+
+// Consecutive line comments and `=begin`/`=end` comments
+fn comment_ranges(comments: &[Comment], input: &Input) -> Vec<Value> {
+    let mut ranges = vec![];
+    let mut current: Option<(usize, usize)> = None;
+
+    let lines = |comment: &Comment| {
+        let location = &comment.location;
+        (
+            convert::line(input, location.begin_pos),
+            convert::line(input, location.end_pos.saturating_sub(1)),
+        )
+    };
+
+    let mut push = |(start_line, end_line): (usize, usize)| {
+        if end_line > start_line {
+            ranges.push(json!({
+                "startLine": start_line,
+                "endLine": end_line,
+                "kind": "comment",
+            }))
+        }
+    };
+
+    for comment in comments {
+        let (start_line, end_line) = lines(comment);
+        match (&comment.kind, current) {
+            (CommentType::Inline, Some((first, last))) if start_line == last + 1 => {
+                current = Some((first, end_line))
+            }
+            (CommentType::Inline, _) => {
+                if let Some(range) = current.take() {
+                    push(range)
+                }
+                current = Some((start_line, end_line))
+            }
+            _ => {
+                if let Some(range) = current.take() {
+                    push(range)
+                }
+                push((start_line, end_line))
+            }
+        }
+    }
+    if let Some(range) = current {
+        push(range)
+    }
+
+    ranges
+}
@@ -0,0 +1,34 @@
+//! Language server for Ruby based on lib-ruby-parser.
+//!
+//! Speaks LSP over stdio and provides diagnostics, document symbols,
+//! folding ranges and selection ranges.
+
+use std::io::{self, BufRead, Write};
+
+pub mod transport;
+
+mod server;
+pub use server::Server;
+
+mod convert;
+mod folding;
+mod selection;
+mod symbols;
+
+/// Reads messages from `input` and writes responses to `output`
+/// until `exit` notification or EOF, returns an exit code of the server
+pub fn run<R: BufRead, W: Write>(input: &mut R, output: &mut W) -> io::Result<i32> {
+    let mut server = Server::new();
+
+    while let Some(message) = transport::read_message(input)? {
+        for response in server.handle(&message) {
+            transport::write_message(output, &response)?;
+        }
+        if let Some(exit_code) = server.exit_code() {
+            return Ok(exit_code);
+        }
+    }
+
+    // client has gone without `exit` notification
+    Ok(1)
+}
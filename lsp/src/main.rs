@@ -0,0 +1,14 @@
+use std::io;
+
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    match lib_ruby_parser_lsp::run(&mut stdin.lock(), &mut stdout.lock()) {
+        Ok(exit_code) => std::process::exit(exit_code),
+        Err(err) => {
+            eprintln!("lib-ruby-parser-lsp: {}", err);
+            std::process::exit(1)
+        }
+    }
+}
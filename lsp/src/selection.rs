@@ -0,0 +1,64 @@
+use crate::convert;
+use lib_ruby_parser::source::buffer::Input;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::traverse::Visitor;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+/// Returns a `SelectionRange` for a given position:
+/// the innermost node that contains it with its ancestors as parents
+pub(crate) fn selection_range(ast: Option<&Node>, input: &Input, pos: usize) -> Value {
+    let mut collector = RangeCollector {
+        pos,
+        ranges: vec![],
+    };
+    if let Some(ast) = ast {
+        collector.visit(ast);
+    }
+
+    // the whole document is the outermost range
+    collector.ranges.push(Range::new(0, input.len()));
+
+    let mut ranges = collector.ranges;
+    ranges.sort_by_key(|range| std::cmp::Reverse(range.size()));
+
+    // outermost -> innermost, every range contains the next one
+    let mut chain: Vec<Range> = vec![];
+    for range in ranges {
+        match chain.last() {
+            Some(last) if *last == range => {}
+            Some(last) if !contains(last, &range) => {}
+            _ => chain.push(range),
+        }
+    }
+
+    let mut result: Option<Value> = None;
+    for range in chain {
+        let mut selection_range = json!({ "range": convert::range(input, &range) });
+        if let Some(parent) = result {
+            selection_range["parent"] = parent;
+        }
+        result = Some(selection_range);
+    }
+
+    result.unwrap_or_else(|| json!({ "range": convert::range(input, &Range::new(pos, pos)) }))
+}
+
+fn contains(outer: &Range, inner: &Range) -> bool {
+    outer.begin_pos <= inner.begin_pos && inner.end_pos <= outer.end_pos
+}
+
+struct RangeCollector {
+    pos: usize,
+    ranges: Vec<Range>,
+}
+
+impl Visitor for RangeCollector {
+    fn visit(&mut self, node: &Node) {
+        let expression_l = node.expression();
+        if expression_l.begin_pos <= self.pos && self.pos <= expression_l.end_pos {
+            self.ranges.push(expression_l.clone())
+        }
+        self.walk(node)
+    }
+}
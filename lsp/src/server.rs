@@ -0,0 +1,231 @@
+use crate::{convert, folding, selection, symbols};
+use lib_ruby_parser::{Diagnostic, ErrorLevel, Parser, ParserOptions, ParserResult};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+// JSON-RPC error codes
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_REQUEST: i64 = -32600;
+
+// DiagnosticSeverity from the spec
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFORMATION: u8 = 3;
+
+// TextDocumentSyncKind.Full
+const SYNC_FULL: u8 = 1;
+
+/// State of the language server: open documents and their parse results
+#[derive(Debug, Default)]
+pub struct Server {
+    documents: HashMap<String, ParserResult>,
+    shutdown_requested: bool,
+    exit_code: Option<i32>,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Exit code if `exit` notification has been received
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+
+    /// Handles a single request or notification,
+    /// returns messages that must be sent to the client (responses and notifications)
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message.get("method").and_then(|method| method.as_str());
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        match (message.get("id"), method) {
+            (Some(id), Some(method)) => vec![self.handle_request(id, method, &params)],
+            (None, Some(method)) => self.handle_notification(method, &params),
+            // responses from the client are not expected
+            (Some(_), None) => vec![],
+            (None, None) => vec![error_response(
+                &Value::Null,
+                INVALID_REQUEST,
+                "invalid request",
+            )],
+        }
+    }
+
+    fn handle_request(&mut self, id: &Value, method: &str, params: &Value) -> Value {
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": SYNC_FULL,
+                    "documentSymbolProvider": true,
+                    "foldingRangeProvider": true,
+                    "selectionRangeProvider": true,
+                },
+                "serverInfo": {
+                    "name": "lib-ruby-parser-lsp",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/documentSymbol" => self.document_symbol(params),
+            "textDocument/foldingRange" => self.folding_range(params),
+            "textDocument/selectionRange" => self.selection_range(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method {}", method))),
+        };
+
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        match method {
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => vec![self.open(uri, text)],
+                    _ => vec![],
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str();
+                // full sync, the last change contains the whole text
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                match (uri, text) {
+                    (Some(uri), Some(text)) => vec![self.open(uri, text)],
+                    _ => vec![],
+                }
+            }
+            "textDocument/didClose" => match params["textDocument"]["uri"].as_str() {
+                Some(uri) => {
+                    self.documents.remove(uri);
+                    vec![publish_diagnostics(uri, vec![])]
+                }
+                None => vec![],
+            },
+            "exit" => {
+                self.exit_code = Some(if self.shutdown_requested { 0 } else { 1 });
+                vec![]
+            }
+            // "initialized", "$/cancelRequest", etc.
+            _ => vec![],
+        }
+    }
+
+    // Parses a document and returns `textDocument/publishDiagnostics` notification
+    fn open(&mut self, uri: &str, text: &str) -> Value {
+        let options = ParserOptions {
+            buffer_name: uri.to_owned(),
+            error_recovery: true,
+            ..Default::default()
+        };
+        let result = Parser::new(text.as_bytes(), options).do_parse();
+
+        let diagnostics = result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| lsp_diagnostic(uri, diagnostic, &result))
+            .collect();
+        self.documents.insert(uri.to_owned(), result);
+
+        publish_diagnostics(uri, diagnostics)
+    }
+
+    fn document(&self, params: &Value) -> Result<&ParserResult, (i64, String)> {
+        let uri = params["textDocument"]["uri"]
+            .as_str()
+            .ok_or_else(|| (INVALID_PARAMS, "textDocument.uri is missing".to_owned()))?;
+        self.documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("unknown document {}", uri)))
+    }
+
+    fn document_symbol(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        let symbols = match &document.ast {
+            Some(ast) => symbols::document_symbols(ast, &document.input),
+            None => vec![],
+        };
+        Ok(Value::Array(symbols))
+    }
+
+    fn folding_range(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        Ok(Value::Array(folding::folding_ranges(
+            document.ast.as_ref(),
+            &document.comments,
+            &document.input,
+        )))
+    }
+
+    fn selection_range(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let document = self.document(params)?;
+        let positions = params["positions"]
+            .as_array()
+            .ok_or_else(|| (INVALID_PARAMS, "positions are missing".to_owned()))?;
+
+        let mut result = vec![];
+        for position in positions {
+            let pos = convert::offset(&document.input, position)
+                .ok_or_else(|| (INVALID_PARAMS, format!("invalid position {}", position)))?;
+            result.push(selection::selection_range(
+                document.ast.as_ref(),
+                &document.input,
+                pos,
+            ));
+        }
+        Ok(Value::Array(result))
+    }
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn lsp_diagnostic(uri: &str, diagnostic: &Diagnostic, result: &ParserResult) -> Value {
+    let input = &result.input;
+    let severity = match diagnostic.level {
+        ErrorLevel::Error | ErrorLevel::Fatal => SEVERITY_ERROR,
+        ErrorLevel::Warning => SEVERITY_WARNING,
+        ErrorLevel::Note => SEVERITY_INFORMATION,
+    };
+    let related_information = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            json!({
+                "location": { "uri": uri, "range": convert::range(input, &label.range) },
+                "message": label.message,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "range": convert::range(input, &diagnostic.range),
+        "severity": severity,
+        "code": diagnostic.code(),
+        "source": "lib-ruby-parser",
+        "message": diagnostic.render_message(),
+        "relatedInformation": related_information,
+    })
+}
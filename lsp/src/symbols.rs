@@ -0,0 +1,125 @@
+use crate::convert;
+use lib_ruby_parser::nodes::{Casgn, Class, Def, Defs, Module};
+use lib_ruby_parser::source::buffer::Input;
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::traverse::Visitor;
+use lib_ruby_parser::Node;
+use serde_json::{json, Value};
+
+// SymbolKind from the spec
+const SYMBOL_KIND_MODULE: u8 = 2;
+const SYMBOL_KIND_CLASS: u8 = 5;
+const SYMBOL_KIND_METHOD: u8 = 6;
+const SYMBOL_KIND_CONSTANT: u8 = 14;
+
+/// Returns a tree of `DocumentSymbol`s
+pub(crate) fn document_symbols(ast: &Node, input: &Input) -> Vec<Value> {
+    let mut collector = SymbolCollector {
+        input,
+        stack: vec![vec![]],
+    };
+    collector.visit(ast);
+    collector.stack.pop().unwrap_or_default()
+}
+
+struct SymbolCollector<'a> {
+    input: &'a Input,
+    // children of symbols that are being collected
+    stack: Vec<Vec<Value>>,
+}
+
+impl SymbolCollector<'_> {
+    fn source(&self, range: &Range) -> String {
+        range.source(self.input).unwrap_or_default()
+    }
+
+    fn add_symbol<F>(&mut self, name: String, kind: u8, range: &Range, name_l: &Range, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        self.stack.push(vec![]);
+        f(self);
+        let children = self.stack.pop().unwrap_or_default();
+
+        let symbol = json!({
+            "name": name,
+            "kind": kind,
+            "range": convert::range(self.input, range),
+            "selectionRange": convert::range(self.input, name_l),
+            "children": children,
+        });
+        if let Some(siblings) = self.stack.last_mut() {
+            siblings.push(symbol)
+        }
+    }
+}
+
+impl Visitor for SymbolCollector<'_> {
+    fn on_class(&mut self, node: &Class) {
+        let name = self.source(node.name.expression());
+        self.add_symbol(
+            name,
+            SYMBOL_KIND_CLASS,
+            &node.expression_l,
+            node.name.expression(),
+            |this| {
+                this.maybe_visit(&node.superclass);
+                this.maybe_visit(&node.body);
+            },
+        )
+    }
+
+    fn on_module(&mut self, node: &Module) {
+        let name = self.source(node.name.expression());
+        self.add_symbol(
+            name,
+            SYMBOL_KIND_MODULE,
+            &node.expression_l,
+            node.name.expression(),
+            |this| {
+                this.maybe_visit(&node.body);
+            },
+        )
+    }
+
+    fn on_def(&mut self, node: &Def) {
+        self.add_symbol(
+            node.name.clone(),
+            SYMBOL_KIND_METHOD,
+            &node.expression_l,
+            &node.name_l,
+            |this| {
+                this.maybe_visit(&node.args);
+                this.maybe_visit(&node.body);
+            },
+        )
+    }
+
+    fn on_defs(&mut self, node: &Defs) {
+        let name = format!("{}.{}", self.source(node.definee.expression()), node.name);
+        self.add_symbol(
+            name,
+            SYMBOL_KIND_METHOD,
+            &node.expression_l,
+            &node.name_l,
+            |this| {
+                this.visit(&node.definee);
+                this.maybe_visit(&node.args);
+                this.maybe_visit(&node.body);
+            },
+        )
+    }
+
+    fn on_casgn(&mut self, node: &Casgn) {
+        self.add_symbol(
+            node.name.clone(),
+            SYMBOL_KIND_CONSTANT,
+            &node.expression_l,
+            &node.name_l,
+            |this| {
+                this.maybe_visit(&node.scope);
+                this.maybe_visit(&node.value);
+            },
+        )
+    }
+}
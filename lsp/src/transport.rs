@@ -0,0 +1,43 @@
+//! JSON-RPC messages with `Content-Length` headers (base protocol of LSP)
+
+use serde_json::Value;
+use std::io::{self, BufRead, Write};
+
+fn invalid_data<E: ToString>(err: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// Reads a single message, returns `None` on EOF
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            content_length = Some(value.trim().parse().map_err(invalid_data)?);
+        }
+    }
+
+    let content_length =
+        content_length.ok_or_else(|| invalid_data("missing Content-Length header"))?;
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(invalid_data)
+}
+
+/// Writes a single message
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
@@ -0,0 +1,254 @@
+// Runs the server binary and talks to it over stdio like an editor does
+use lib_ruby_parser_lsp::transport::{read_message, write_message};
+use serde_json::{json, Value};
+use std::io::BufReader;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const URI: &str = "file:///test.rb";
+
+const SOURCE: &str = "module Foo
+  class Bar < Baz
+    X = 1
+
+    def foo
+      bar -1
+    end
+
+    def self.baz; end
+  end
+end
+";
+
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_lib-ruby-parser-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start lib-ruby-parser-lsp");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 1,
+        }
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        let response = self.receive();
+        assert_eq!(response["id"], json!(id));
+        response
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn send(&mut self, message: Value) {
+        write_message(&mut self.stdin, &message).unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        read_message(&mut self.stdout)
+            .unwrap()
+            .expect("server has closed stdout")
+    }
+}
+
+fn range(start: (u64, u64), end: (u64, u64)) -> Value {
+    json!({
+        "start": { "line": start.0, "character": start.1 },
+        "end": { "line": end.0, "character": end.1 },
+    })
+}
+
+fn initialize(client: &mut Client) {
+    let response = client.request("initialize", json!({ "capabilities": {} }));
+    let capabilities = &response["result"]["capabilities"];
+    assert_eq!(capabilities["documentSymbolProvider"], json!(true));
+    assert_eq!(capabilities["foldingRangeProvider"], json!(true));
+    assert_eq!(capabilities["selectionRangeProvider"], json!(true));
+    client.notify("initialized", json!({}));
+}
+
+fn open(client: &mut Client, text: &str) -> Value {
+    client.notify(
+        "textDocument/didOpen",
+        json!({
+            "textDocument": { "uri": URI, "languageId": "ruby", "version": 1, "text": text }
+        }),
+    );
+    client.receive()
+}
+
+fn shutdown(mut client: Client) {
+    let response = client.request("shutdown", Value::Null);
+    assert_eq!(response["result"], Value::Null);
+    client.notify("exit", Value::Null);
+
+    let status = client.child.wait().unwrap();
+    assert_eq!(status.code(), Some(0));
+}
+
+#[test]
+fn test_diagnostics() {
+    let mut client = Client::start();
+    initialize(&mut client);
+
+    let notification = open(&mut client, SOURCE);
+    assert_eq!(notification["method"], "textDocument/publishDiagnostics");
+    assert_eq!(notification["params"]["uri"], URI);
+    let diagnostics = notification["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], json!(2));
+    assert_eq!(diagnostics[0]["code"], json!("W0007"));
+    assert_eq!(diagnostics[0]["source"], json!("lib-ruby-parser"));
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "foo(" }]
+        }),
+    );
+    let notification = client.receive();
+    let diagnostics = notification["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], json!(1));
+
+    client.notify(
+        "textDocument/didClose",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let notification = client.receive();
+    assert_eq!(notification["params"]["diagnostics"], json!([]));
+
+    shutdown(client);
+}
+
+#[test]
+fn test_document_symbols() {
+    let mut client = Client::start();
+    initialize(&mut client);
+    open(&mut client, SOURCE);
+
+    let response = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    let symbols = response["result"].as_array().unwrap();
+    assert_eq!(symbols.len(), 1);
+
+    let module = &symbols[0];
+    assert_eq!(module["name"], "Foo");
+    assert_eq!(module["kind"], json!(2));
+    assert_eq!(module["range"], range((0, 0), (10, 3)));
+    assert_eq!(module["selectionRange"], range((0, 7), (0, 10)));
+
+    let class = &module["children"][0];
+    assert_eq!(class["name"], "Bar");
+    assert_eq!(class["kind"], json!(5));
+
+    let names = class["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| {
+            (
+                symbol["name"].as_str().unwrap(),
+                symbol["kind"].as_u64().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec![("X", 14), ("foo", 6), ("self.baz", 6)]);
+
+    shutdown(client);
+}
+
+fn folding_ranges(client: &mut Client) -> Vec<(u64, u64, String)> {
+    let response = client.request(
+        "textDocument/foldingRange",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|range| {
+            (
+                range["startLine"].as_u64().unwrap(),
+                range["endLine"].as_u64().unwrap(),
+                range["kind"].as_str().unwrap().to_owned(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_folding_ranges() {
+    let mut client = Client::start();
+    initialize(&mut client);
+
+    open(&mut client, SOURCE);
+    assert_eq!(
+        folding_ranges(&mut client),
+        vec![
+            (0, 9, "region".to_owned()),
+            (1, 8, "region".to_owned()),
+            (4, 5, "region".to_owned())
+        ]
+    );
+
+    open(&mut client, "# a\n# b\nfoo\n# c\n");
+    assert_eq!(
+        folding_ranges(&mut client),
+        vec![(0, 1, "comment".to_owned())]
+    );
+
+    shutdown(client);
+}
+
+#[test]
+fn test_selection_ranges() {
+    let mut client = Client::start();
+    initialize(&mut client);
+    open(&mut client, SOURCE);
+
+    // "bar" in "bar -1"
+    let response = client.request(
+        "textDocument/selectionRange",
+        json!({
+            "textDocument": { "uri": URI },
+            "positions": [{ "line": 5, "character": 7 }]
+        }),
+    );
+    let selection = &response["result"][0];
+    assert_eq!(selection["range"], range((5, 6), (5, 12)));
+    assert_eq!(selection["parent"]["range"], range((4, 4), (6, 7)));
+
+    shutdown(client);
+}
+
+#[test]
+fn test_unknown_method() {
+    let mut client = Client::start();
+    initialize(&mut client);
+
+    let response = client.request("textDocument/hover", json!({}));
+    assert_eq!(response["error"]["code"], json!(-32601));
+
+    shutdown(client);
+}
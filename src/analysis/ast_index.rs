@@ -0,0 +1,164 @@
+use crate::source::Range;
+use crate::Node;
+
+/// Id of a node in `AstIndex`, nodes are numbered in pre-order starting from the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// Indexed view of the AST with parent links.
+///
+/// Ids are assigned in pre-order (the same order as `Visitor` uses),
+/// so they are stable for the same AST and `id` of a parent is always
+/// less than `id` of its children.
+#[derive(Debug, Clone)]
+pub struct AstIndex<'a> {
+    nodes: Vec<&'a Node>,
+    parents: Vec<Option<NodeId>>,
+    children: Vec<Vec<NodeId>>,
+    depths: Vec<usize>,
+    // (begin, end) covered by a node and all of its descendants,
+    // heredoc bodies are located outside of their parent nodes
+    spans: Vec<(usize, usize)>,
+    // ids sorted by begin of the expression
+    by_begin: Vec<(usize, NodeId)>,
+}
+
+impl<'a> AstIndex<'a> {
+    pub fn build(ast: &'a Node) -> Self {
+        let mut index = Self {
+            nodes: vec![],
+            parents: vec![],
+            children: vec![],
+            depths: vec![],
+            spans: vec![],
+            by_begin: vec![],
+        };
+
+        // (node, parent), children are pushed in reverse to pop them in order
+        let mut stack: Vec<(&'a Node, Option<NodeId>)> = vec![(ast, None)];
+        while let Some((node, parent)) = stack.pop() {
+            let id = NodeId(index.nodes.len());
+            index.nodes.push(node);
+            index.parents.push(parent);
+            index.children.push(vec![]);
+            match parent {
+                Some(parent) => {
+                    index.children[parent.0].push(id);
+                    index.depths.push(index.depths[parent.0] + 1);
+                }
+                None => index.depths.push(0),
+            }
+            for child in node.children().into_iter().rev() {
+                stack.push((child, Some(id)))
+            }
+        }
+
+        index.spans = index
+            .nodes
+            .iter()
+            .map(|node| (node.expression().begin_pos, node.expression().end_pos))
+            .collect();
+        // children have greater ids than their parents
+        for idx in (1..index.nodes.len()).rev() {
+            let (begin, end) = index.spans[idx];
+            let parent = index.parents[idx].expect("only root has no parent");
+            let span = &mut index.spans[parent.0];
+            span.0 = span.0.min(begin);
+            span.1 = span.1.max(end);
+        }
+
+        index.by_begin = index
+            .nodes()
+            .map(|(id, node)| (node.expression().begin_pos, id))
+            .collect();
+        index.by_begin.sort_unstable();
+
+        index
+    }
+
+    /// Returns id of the root node
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &'a Node {
+        self.nodes[id.0]
+    }
+
+    /// Returns all nodes in pre-order
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &'a Node)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (NodeId(idx), *node))
+    }
+
+    /// Returns id of the given node, `node` must be a reference into the indexed AST
+    pub fn id_of(&self, node: &Node) -> Option<NodeId> {
+        let begin_pos = node.expression().begin_pos;
+        let first = self
+            .by_begin
+            .partition_point(|(begin, _)| *begin < begin_pos);
+        self.by_begin[first..]
+            .iter()
+            .take_while(|(begin, _)| *begin == begin_pos)
+            .map(|(_, id)| *id)
+            .find(|id| std::ptr::eq(self.nodes[id.0], node))
+    }
+
+    /// Returns parent of the node, `None` for the root
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.parents[id.0]
+    }
+
+    /// Returns direct children of the node in order of traversal
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.children[id.0]
+    }
+
+    /// Returns depth of the node, root has depth 0
+    pub fn depth(&self, id: NodeId) -> usize {
+        self.depths[id.0]
+    }
+
+    /// Returns all ancestors of the node from its parent up to the root
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        std::iter::successors(self.parent(id), move |id| self.parent(*id))
+    }
+
+    /// Returns the innermost node whose expression contains the given byte
+    pub fn node_at_offset(&self, pos: usize) -> Option<NodeId> {
+        // pre-order traversal that skips subtrees that don't cover `pos`
+        let mut matching = vec![];
+        let mut stack = vec![self.root()];
+        while let Some(id) = stack.pop() {
+            let (begin, end) = self.spans[id.0];
+            if pos < begin || end <= pos {
+                continue;
+            }
+            let expression_l = self.node(id).expression();
+            if expression_l.begin_pos <= pos && pos < expression_l.end_pos {
+                matching.push(id);
+            }
+            stack.extend(self.children(id).iter().rev());
+        }
+
+        matching.into_iter().max_by_key(|id| {
+            (
+                self.depth(*id),
+                std::cmp::Reverse(self.node(*id).expression().size()),
+            )
+        })
+    }
+
+    /// Returns all nodes whose expression lies within the given range, in pre-order
+    pub fn nodes_in_range(&self, range: &Range) -> Vec<NodeId> {
+        self.nodes()
+            .filter(|(_, node)| {
+                let expression_l = node.expression();
+                range.begin_pos <= expression_l.begin_pos && expression_l.end_pos <= range.end_pos
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
@@ -0,0 +1,7 @@
+//! Static analysis passes over a parsed AST
+
+mod ast_index;
+mod scopes;
+
+pub use ast_index::{AstIndex, NodeId};
+pub use scopes::{Scope, ScopeGraph, ScopeId, ScopeKind, Variable, VariableId, VariableKind};
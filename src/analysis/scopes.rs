@@ -0,0 +1,441 @@
+use crate::builder::static_string;
+use crate::nodes::*;
+use crate::source::Range;
+use crate::traverse::Visitor;
+use crate::{Node, RegexpParser};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScopeId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VariableId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeKind {
+    Program,
+    Def,
+    Defs,
+    Class,
+    Module,
+    SClass,
+    Block,
+    Numblock,
+    Itblock,
+    Lambda,
+}
+
+impl ScopeKind {
+    /// Returns true if local variables of the parent scope are visible in this scope
+    pub fn is_dynamic(&self) -> bool {
+        matches!(
+            self,
+            Self::Block | Self::Numblock | Self::Itblock | Self::Lambda
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    /// `a = 1`, `rescue => a`, `for a in b`, named regex captures, block-local `|;a|`
+    Local,
+    /// Method, block and lambda arguments
+    Argument,
+    /// `_1`..`_9` in a block without explicit arguments
+    NumberedParam,
+    /// `it` in a block without explicit arguments
+    ItParam,
+    /// `in a`, `in [*a]`, `in { a: }`, `in Integer => a`
+    PatternVariable,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// Location of the node that opens the scope
+    pub range: Range,
+    pub parent: Option<ScopeId>,
+    pub children: Vec<ScopeId>,
+    /// Variables declared in this scope, in order of declaration
+    pub variables: Vec<VariableId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Variable {
+    pub name: String,
+    pub kind: VariableKind,
+    pub scope: ScopeId,
+    /// Locations of names of the declaration and all assignments,
+    /// empty for numbered params
+    pub definitions: Vec<Range>,
+    /// Locations of all `Lvar` nodes referring to this variable
+    pub references: Vec<Range>,
+}
+
+/// Scopes and local variables of the AST.
+///
+/// Follows the same rules as `StaticEnvironment` during parsing:
+/// `def`/`class`/`module`/`class << self` start a new set of locals,
+/// blocks and lambdas can see locals of the enclosing scope.
+#[derive(Debug, Clone)]
+pub struct ScopeGraph {
+    scopes: Vec<Scope>,
+    variables: Vec<Variable>,
+    locations: HashMap<Range, VariableId>,
+    unresolved: Vec<Range>,
+}
+
+impl ScopeGraph {
+    pub fn build(ast: &Node) -> Self {
+        let mut builder = ScopeGraphBuilder {
+            graph: Self {
+                scopes: vec![],
+                variables: vec![],
+                locations: HashMap::new(),
+                unresolved: vec![],
+            },
+            current: ScopeId(0),
+        };
+        builder.push_scope(ScopeKind::Program, ast.expression());
+        builder.visit(ast);
+        builder.graph
+    }
+
+    /// Returns top-level scope
+    pub fn root(&self) -> ScopeId {
+        ScopeId(0)
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0]
+    }
+
+    pub fn scopes(&self) -> impl Iterator<Item = (ScopeId, &Scope)> {
+        self.scopes
+            .iter()
+            .enumerate()
+            .map(|(idx, scope)| (ScopeId(idx), scope))
+    }
+
+    pub fn variable(&self, id: VariableId) -> &Variable {
+        &self.variables[id.0]
+    }
+
+    pub fn variables(&self) -> impl Iterator<Item = (VariableId, &Variable)> {
+        self.variables
+            .iter()
+            .enumerate()
+            .map(|(idx, variable)| (VariableId(idx), variable))
+    }
+
+    /// Returns a variable that is defined or referenced at the given location
+    pub fn variable_at(&self, range: &Range) -> Option<VariableId> {
+        self.locations.get(range).copied()
+    }
+
+    /// Returns definition sites of a variable referenced at the given location
+    pub fn definitions_of(&self, reference: &Range) -> Option<&[Range]> {
+        let id = self.variable_at(reference)?;
+        Some(&self.variable(id).definitions)
+    }
+
+    /// Returns the innermost scope that contains the given position
+    pub fn scope_at(&self, pos: usize) -> ScopeId {
+        let mut id = self.root();
+        'outer: loop {
+            for child in self.scope(id).children.iter() {
+                let range = &self.scope(*child).range;
+                if range.begin_pos <= pos && pos < range.end_pos {
+                    id = *child;
+                    continue 'outer;
+                }
+            }
+            return id;
+        }
+    }
+
+    /// Returns locations of `Lvar` nodes that don't refer to any known variable
+    /// (e.g. if the code was parsed with some locals declared in advance)
+    pub fn unresolved(&self) -> &[Range] {
+        &self.unresolved
+    }
+}
+
+struct ScopeGraphBuilder {
+    graph: ScopeGraph,
+    current: ScopeId,
+}
+
+impl ScopeGraphBuilder {
+    fn push_scope(&mut self, kind: ScopeKind, range: &Range) -> ScopeId {
+        let id = ScopeId(self.graph.scopes.len());
+        let parent = if self.graph.scopes.is_empty() {
+            None
+        } else {
+            self.graph.scopes[self.current.0].children.push(id);
+            Some(self.current)
+        };
+        self.graph.scopes.push(Scope {
+            kind,
+            range: range.clone(),
+            parent,
+            children: vec![],
+            variables: vec![],
+        });
+        id
+    }
+
+    fn with_scope<F>(&mut self, kind: ScopeKind, range: &Range, f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let outer = self.current;
+        self.current = self.push_scope(kind, range);
+        f(self);
+        self.current = outer;
+    }
+
+    fn lookup(&self, name: &str) -> Option<VariableId> {
+        let mut scope_id = self.current;
+        loop {
+            let scope = &self.graph.scopes[scope_id.0];
+            for id in scope.variables.iter() {
+                if self.graph.variables[id.0].name == name {
+                    return Some(*id);
+                }
+            }
+            match scope.parent {
+                Some(parent) if scope.kind.is_dynamic() => scope_id = parent,
+                _ => return None,
+            }
+        }
+    }
+
+    fn declare(&mut self, name: &str, kind: VariableKind, range: Option<&Range>) -> VariableId {
+        let id = VariableId(self.graph.variables.len());
+        self.graph.variables.push(Variable {
+            name: name.to_owned(),
+            kind,
+            scope: self.current,
+            definitions: vec![],
+            references: vec![],
+        });
+        self.graph.scopes[self.current.0].variables.push(id);
+        if let Some(range) = range {
+            self.add_definition(id, range);
+        }
+        id
+    }
+
+    fn add_definition(&mut self, id: VariableId, range: &Range) {
+        self.graph.variables[id.0].definitions.push(range.clone());
+        self.graph.locations.insert(range.clone(), id);
+    }
+
+    // Assignment to an existing variable or a declaration of a new one
+    fn assign(&mut self, name: &str, kind: VariableKind, range: &Range) {
+        match self.lookup(name) {
+            Some(id) => self.add_definition(id, range),
+            None => {
+                self.declare(name, kind, Some(range));
+            }
+        }
+    }
+
+    fn declare_arg(&mut self, name: &str, range: &Range) {
+        self.declare(name, VariableKind::Argument, Some(range));
+    }
+}
+
+impl Visitor for ScopeGraphBuilder {
+    fn on_def(&mut self, node: &Def) {
+        self.with_scope(ScopeKind::Def, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.args);
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_defs(&mut self, node: &Defs) {
+        self.visit(&node.definee);
+        self.with_scope(ScopeKind::Defs, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.args);
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_class(&mut self, node: &Class) {
+        self.visit(&node.name);
+        self.maybe_visit(&node.superclass);
+        self.with_scope(ScopeKind::Class, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_module(&mut self, node: &Module) {
+        self.visit(&node.name);
+        self.with_scope(ScopeKind::Module, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_sclass(&mut self, node: &SClass) {
+        self.visit(&node.expr);
+        self.with_scope(ScopeKind::SClass, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_block(&mut self, node: &Block) {
+        self.visit(&node.call);
+        let kind = match node.call {
+            Node::Lambda(_) => ScopeKind::Lambda,
+            _ => ScopeKind::Block,
+        };
+        self.with_scope(kind, &node.expression_l, |builder| {
+            builder.maybe_visit(&node.args);
+            builder.maybe_visit(&node.body);
+        })
+    }
+
+    fn on_numblock(&mut self, node: &Numblock) {
+        self.visit(&node.call);
+        self.with_scope(ScopeKind::Numblock, &node.expression_l, |builder| {
+            for n in 1..=node.numargs {
+                builder.declare(&format!("_{}", n), VariableKind::NumberedParam, None);
+            }
+            builder.visit(&node.body);
+        })
+    }
+
+    fn on_itblock(&mut self, node: &Itblock) {
+        self.visit(&node.call);
+        self.with_scope(ScopeKind::Itblock, &node.expression_l, |builder| {
+            builder.declare("it", VariableKind::ItParam, None);
+            builder.visit(&node.body);
+        })
+    }
+
+    fn on_arg(&mut self, node: &Arg) {
+        self.declare_arg(&node.name, &node.expression_l)
+    }
+
+    fn on_optarg(&mut self, node: &Optarg) {
+        self.declare_arg(&node.name, &node.name_l);
+        self.visit(&node.default)
+    }
+
+    fn on_restarg(&mut self, node: &Restarg) {
+        if let (Some(name), Some(name_l)) = (&node.name, &node.name_l) {
+            self.declare_arg(name, name_l)
+        }
+    }
+
+    fn on_kwarg(&mut self, node: &Kwarg) {
+        self.declare_arg(&node.name, &node.name_l)
+    }
+
+    fn on_kwoptarg(&mut self, node: &Kwoptarg) {
+        self.declare_arg(&node.name, &node.name_l);
+        self.visit(&node.default)
+    }
+
+    fn on_kwrestarg(&mut self, node: &Kwrestarg) {
+        if let (Some(name), Some(name_l)) = (&node.name, &node.name_l) {
+            self.declare_arg(name, name_l)
+        }
+    }
+
+    fn on_blockarg(&mut self, node: &Blockarg) {
+        self.declare_arg(&node.name, &node.name_l)
+    }
+
+    fn on_shadowarg(&mut self, node: &Shadowarg) {
+        self.declare(&node.name, VariableKind::Local, Some(&node.expression_l));
+    }
+
+    fn on_lvasgn(&mut self, node: &Lvasgn) {
+        self.assign(&node.name, VariableKind::Local, &node.name_l);
+        self.maybe_visit(&node.value)
+    }
+
+    fn on_match_var(&mut self, node: &MatchVar) {
+        self.assign(&node.name, VariableKind::PatternVariable, &node.name_l)
+    }
+
+    fn on_match_with_lvasgn(&mut self, node: &MatchWithLvasgn) {
+        self.visit(&node.re);
+        self.visit(&node.value);
+        for (name, range) in named_captures(&node.re) {
+            self.assign(&name, VariableKind::Local, &range);
+        }
+    }
+
+    fn on_lvar(&mut self, node: &Lvar) {
+        match self.lookup(&node.name) {
+            Some(id) => {
+                self.graph.variables[id.0]
+                    .references
+                    .push(node.expression_l.clone());
+                self.graph.locations.insert(node.expression_l.clone(), id);
+            }
+            None => self.graph.unresolved.push(node.expression_l.clone()),
+        }
+    }
+}
+
+// Named captures of a static regex literal like `/(?<name>.)/`
+// with locations of their names (or locations of the parts
+// that contain them if the source of the part has escape sequences)
+fn named_captures(re: &Node) -> Vec<(String, Range)> {
+    let (parts, options) = match re {
+        Node::Regexp(inner) => (&inner.parts, &inner.options),
+        _ => return vec![],
+    };
+    let options: &[char] = match options {
+        Some(Node::RegOpt(inner)) => &inner.options,
+        _ => &[],
+    };
+
+    // (offset in the source, part)
+    let mut source = String::new();
+    let mut segments = vec![];
+    for part in parts {
+        segments.push((source.len(), part));
+        match static_string(std::slice::from_ref(part)) {
+            Some(value) => source.push_str(&value),
+            None => return vec![],
+        }
+    }
+
+    let names = match RegexpParser::named_groups(&source, options) {
+        Ok(names) => names,
+        Err(_) => return vec![],
+    };
+
+    names
+        .into_iter()
+        .filter(|(name, _)| is_local_variable_name(name))
+        .map(|(name, offset)| {
+            let idx = segments.partition_point(|(begin, _)| *begin <= offset) - 1;
+            let (begin, part) = segments[idx];
+            let name_l = match part {
+                Node::Str(inner)
+                    if std::str::from_utf8(&inner.value.bytes).is_ok()
+                        && inner.value.bytes.len() == inner.expression_l.size() =>
+                {
+                    let name_begin = inner.expression_l.begin_pos + offset - begin;
+                    Range::new(name_begin, name_begin + name.len())
+                }
+                other => other.expression().clone(),
+            };
+            (name, name_l)
+        })
+        .collect()
+}
+
+fn is_local_variable_name(name: &str) -> bool {
+    match name.chars().next() {
+        Some(c) => c == '_' || c.is_lowercase() || !c.is_ascii(),
+        None => false,
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(not(feature = "onig"))]
+use crate::RegexpParser;
 #[cfg(feature = "onig")]
 use onig::{Regex, RegexOptions};
 use std::collections::HashMap;
@@ -8,8 +10,8 @@ use crate::nodes::*;
 use crate::source::Range;
 use crate::StringValue;
 use crate::{
-    Context, CurrentArgStack, Lexer, Loc, MaxNumparamStack, Node, StaticEnvironment, Token,
-    VariablesStack,
+    Context, CurrentArgStack, Lexer, Loc, MaxNumparamStack, Node, RubyVersion, StaticEnvironment,
+    Token, VariablesStack,
 };
 use crate::{Diagnostic, DiagnosticMessage, ErrorLevel};
 
@@ -53,6 +55,7 @@ pub(crate) enum PKwLabel {
 pub(crate) enum ArgsType {
     Args(Option<Node>),
     Numargs(u8),
+    It,
 }
 
 #[derive(Debug)]
@@ -64,9 +67,11 @@ pub(crate) struct Builder {
     pattern_variables: VariablesStack,
     pattern_hash_keys: VariablesStack,
     diagnostics: Diagnostics,
+    ruby_version: RubyVersion,
 }
 
 impl Builder {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         static_env: StaticEnvironment,
         context: Context,
@@ -75,6 +80,7 @@ impl Builder {
         pattern_variables: VariablesStack,
         pattern_hash_keys: VariablesStack,
         diagnostics: Diagnostics,
+        ruby_version: RubyVersion,
     ) -> Self {
         Self {
             static_env,
@@ -84,6 +90,7 @@ impl Builder {
             pattern_variables,
             pattern_hash_keys,
             diagnostics,
+            ruby_version,
         }
     }
 
@@ -633,6 +640,29 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn pair_label(&self, key_t: Token) -> Node {
+        let key_l = self.loc(&key_t).adjust_end(-1);
+        let name = clone_value(&key_t);
+
+        // `{X:}` is `{X: X}`, `{x:}` is either a local variable or a method call
+        let value = if name.starts_with(char::is_uppercase) {
+            Node::Const(Box::new(Const {
+                scope: None,
+                name,
+                double_colon_l: None,
+                name_l: key_l.clone(),
+                expression_l: key_l,
+            }))
+        } else {
+            self.accessible(Node::Lvar(Box::new(Lvar {
+                name,
+                expression_l: key_l,
+            })))
+        };
+
+        self.pair_keyword(key_t, value)
+    }
+
     pub(crate) fn pair_quoted(
         &self,
         begin_t: Token,
@@ -1198,14 +1228,6 @@ impl Builder {
         }))
     }
 
-    pub(crate) fn rassign(&self, lhs: Node, eql_t: Token, rhs: Node) -> Node {
-        self.assign(rhs, eql_t, lhs)
-    }
-
-    pub(crate) fn multi_rassign(&self, lhs: Node, eql_t: Token, rhs: Node) -> Node {
-        self.multi_assign(rhs, eql_t, lhs)
-    }
-
     //
     // Class and module definition
     //
@@ -1475,6 +1497,12 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn anonymous_blockarg(&self, amper_t: Token) -> Node {
+        Node::AnonymousBlockarg(Box::new(AnonymousBlockarg {
+            expression_l: self.loc(&amper_t),
+        }))
+    }
+
     pub(crate) fn arg(&self, name_t: Token) -> Result<Node, ()> {
         let name_l = self.loc(&name_t);
         let name = value(name_t);
@@ -1663,6 +1691,24 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn forwarded_blockarg(&self, amper_t: Token) -> Node {
+        Node::ForwardedBlockarg(Box::new(ForwardedBlockarg {
+            expression_l: self.loc(&amper_t),
+        }))
+    }
+
+    pub(crate) fn forwarded_restarg(&self, star_t: Token) -> Node {
+        Node::ForwardedRestarg(Box::new(ForwardedRestarg {
+            expression_l: self.loc(&star_t),
+        }))
+    }
+
+    pub(crate) fn forwarded_kwrestarg(&self, dstar_t: Token) -> Node {
+        Node::ForwardedKwrestarg(Box::new(ForwardedKwrestarg {
+            expression_l: self.loc(&dstar_t),
+        }))
+    }
+
     pub(crate) fn call_method(
         &self,
         receiver: Option<Node>,
@@ -1736,7 +1782,7 @@ impl Builder {
         let validate_block_and_block_arg = |args: &Vec<Node>| {
             if let Some(last_arg) = args.last() {
                 match last_arg {
-                    Node::BlockPass(_) | Node::ForwardedArgs(_) => {
+                    Node::BlockPass(_) | Node::ForwardedBlockarg(_) | Node::ForwardedArgs(_) => {
                         self.error(
                             DiagnosticMessage::BlockAndBlockArgGiven,
                             last_arg.inner_ref().expression().clone(),
@@ -1796,6 +1842,13 @@ impl Builder {
                     end_l,
                     expression_l,
                 })),
+                ArgsType::It => Node::Itblock(Box::new(Itblock {
+                    call: actual_send,
+                    body: block_body.expect("itblock always has body"),
+                    begin_l,
+                    end_l,
+                    expression_l,
+                })),
             };
 
             let expr_l = keyword_expression_l.join(block.expression());
@@ -1832,6 +1885,13 @@ impl Builder {
                         end_l,
                         expression_l,
                     })),
+                    ArgsType::It => Node::Itblock(Box::new(Itblock {
+                        call: method_call,
+                        body: block_body.expect("itblock always has body"),
+                        begin_l,
+                        end_l,
+                        expression_l,
+                    })),
                 };
                 return Ok(result);
             }
@@ -2406,7 +2466,7 @@ impl Builder {
         let keyword_l = self.loc(&keyword_t);
 
         if type_ == KeywordCmd::Yield && !args.is_empty() {
-            if let Some(Node::BlockPass(_)) = args.last() {
+            if let Some(Node::BlockPass(_)) | Some(Node::ForwardedBlockarg(_)) = args.last() {
                 self.error(DiagnosticMessage::BlockGivenToYield, keyword_l);
                 return Err(());
             }
@@ -2781,6 +2841,18 @@ impl Builder {
         }))
     }
 
+    pub(crate) fn match_pattern(&self, value: Node, assoc_t: Token, pattern: Node) -> Node {
+        let operator_l = self.loc(&assoc_t);
+        let expression_l = join_exprs(&value, &pattern);
+
+        Node::MatchPattern(Box::new(MatchPattern {
+            value,
+            pattern,
+            operator_l,
+            expression_l,
+        }))
+    }
+
     pub(crate) fn in_match(&self, value: Node, in_t: Token, pattern: Node) -> Node {
         let keyword_l = self.loc(&in_t);
         let expression_l = join_exprs(&value, &pattern);
@@ -3102,7 +3174,7 @@ impl Builder {
             PKwLabel::QuotedLabel((begin_t, parts, end_t)) => {
                 let label_loc = self.loc(&begin_t).join(&self.loc(&end_t));
 
-                match self.static_string(&parts) {
+                match static_string(&parts) {
                     Some(var_name) => self.check_duplicate_pattern_key(&var_name, &label_loc)?,
                     _ => {
                         self.error(DiagnosticMessage::SymbolLiteralWithInterpolation, label_loc);
@@ -3125,6 +3197,17 @@ impl Builder {
         }
     }
 
+    //
+    // Error recovery
+    //
+
+    // Covers code from the invalid token to the statement that parsing continues from
+    pub(crate) fn error_node(&self, begin_pos: usize, end_pos: usize) -> Node {
+        Node::Error(Box::new(Error {
+            expression_l: Range::new(begin_pos, end_pos),
+        }))
+    }
+
     //
     // Verification
     //
@@ -3221,7 +3304,7 @@ impl Builder {
                 Node::Procarg0(inner) => {
                     self.check_duplicate_args(&inner.args, map);
                 }
-                Node::ForwardArg(_) | Node::Kwnilarg(_) => {}
+                Node::ForwardArg(_) | Node::AnonymousBlockarg(_) | Node::Kwnilarg(_) => {}
                 _ => unreachable!("unsupported arg type {:?}", arg),
             }
         }
@@ -3306,6 +3389,11 @@ impl Builder {
     }
 
     pub(crate) fn check_reserved_for_numparam(&self, name: &str, loc: &Range) -> Result<(), ()> {
+        // `_1` is an ordinary identifier before numbered parameters
+        if self.ruby_version < RubyVersion::new(2, 7) {
+            return Ok(());
+        }
+
         match name {
             "_1" | "_2" | "_3" | "_4" | "_5" | "_6" | "_7" | "_8" | "_9" => {
                 self.error(
@@ -3374,29 +3462,6 @@ impl Builder {
     // Helpers
     //
 
-    pub(crate) fn static_string(&self, nodes: &[Node]) -> Option<String> {
-        let mut result = String::from("");
-
-        for node in nodes {
-            match node {
-                Node::Str(inner) => {
-                    let value = inner.value.to_string_lossy();
-                    result.push_str(&value)
-                }
-                Node::Begin(inner) => {
-                    if let Some(s) = self.static_string(&inner.statements) {
-                        result.push_str(&s)
-                    } else {
-                        return None;
-                    }
-                }
-                _ => return None,
-            }
-        }
-
-        Some(result)
-    }
-
     #[cfg(feature = "onig")]
     pub(crate) fn build_static_regexp(
         &self,
@@ -3404,7 +3469,7 @@ impl Builder {
         options: &[char],
         range: &Range,
     ) -> Option<Regex> {
-        let source = self.static_string(&parts)?;
+        let source = static_string(&parts)?;
         let mut reg_options = RegexOptions::REGEX_OPTION_NONE;
         reg_options |= RegexOptions::REGEX_OPTION_CAPTURE_GROUP;
         if options.contains(&'x') {
@@ -3424,18 +3489,26 @@ impl Builder {
         }
     }
 
-    #[cfg(feature = "onig")]
-    pub(crate) fn validate_static_regexp(&self, parts: &[Node], options: &[char], range: &Range) {
-        self.build_static_regexp(parts, options, range);
-    }
-
     #[cfg(not(feature = "onig"))]
-    pub(crate) fn validate_static_regexp(
+    pub(crate) fn build_static_regexp(
         &self,
-        _parts: &[Node],
-        _options: &[char],
-        _range: &Range,
-    ) {
+        parts: &[Node],
+        options: &[char],
+        range: &Range,
+    ) -> Option<Vec<String>> {
+        let source = static_string(parts)?;
+
+        match RegexpParser::parse(&source, options) {
+            Ok(names) => Some(names),
+            Err(message) => {
+                self.error(DiagnosticMessage::RegexError(message), range.clone());
+                None
+            }
+        }
+    }
+
+    pub(crate) fn validate_static_regexp(&self, parts: &[Node], options: &[char], range: &Range) {
+        self.build_static_regexp(parts, options, range);
     }
 
     #[cfg(feature = "onig")]
@@ -3469,7 +3542,21 @@ impl Builder {
     }
 
     #[cfg(not(feature = "onig"))]
-    pub(crate) fn static_regexp_captures(&self, _node: &Node) -> Option<Vec<String>> {
+    pub(crate) fn static_regexp_captures(&self, node: &Node) -> Option<Vec<String>> {
+        if let Node::Regexp(inner) = node {
+            let Regexp {
+                parts,
+                options,
+                expression_l,
+                ..
+            } = &**inner;
+
+            let mut re_options: &[char] = &[];
+            if let Some(Node::RegOpt(inner)) = options {
+                re_options = &inner.options;
+            };
+            return self.build_static_regexp(parts, re_options, expression_l);
+        }
         None
     }
 
@@ -3574,6 +3661,7 @@ impl Builder {
                 Some(node)
             }
             Node::InMatch(inner) => self.void_value(&inner.value),
+            Node::MatchPattern(inner) => self.void_value(&inner.value),
             Node::Begin(inner) => check_stmts(&inner.statements),
             Node::KwBegin(inner) => check_stmts(&inner.statements),
             Node::If(inner) => check_maybe_condition(&inner.if_true, &inner.if_false),
@@ -3586,6 +3674,30 @@ impl Builder {
     }
 }
 
+// Value of a string literal without dynamic interpolation
+pub(crate) fn static_string(nodes: &[Node]) -> Option<String> {
+    let mut result = String::from("");
+
+    for node in nodes {
+        match node {
+            Node::Str(inner) => {
+                let value = inner.value.to_string_lossy();
+                result.push_str(&value)
+            }
+            Node::Begin(inner) => {
+                if let Some(s) = static_string(&inner.statements) {
+                    result.push_str(&s)
+                } else {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(result)
+}
+
 pub(crate) fn maybe_node_expr(node: &Option<&Node>) -> Option<Range> {
     node.map(|node| node.expression().clone())
 }
@@ -1,14 +1,22 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// Kind of a construct that encloses parsed code, see `ParserOptions::context`
 #[derive(Debug, Clone, Eq, PartialEq)]
-pub(crate) enum ContextItem {
+pub enum ContextItem {
+    /// `class Foo; ...; end`
     Class,
+    /// `module Foo; ...; end`
     Module,
+    /// `class << self; ...; end`
     Sclass,
+    /// `def foo; ...; end`
     Def,
+    /// `def self.foo; ...; end`
     Defs,
+    /// `foo { ... }` or `foo do ... end`
     Block,
+    /// `-> { ... }`
     Lambda,
 }
 
@@ -24,7 +32,7 @@ impl Context {
         }
     }
 
-    fn push(&self, item: ContextItem) {
+    pub(crate) fn push(&self, item: ContextItem) {
         self.stack.borrow_mut().push(item);
     }
 
@@ -132,6 +140,21 @@ impl Context {
         self.is_class_definition_allowed()
     }
 
+    // Blocks and lambdas are transparent, so `foo { yield }` in a class body is still invalid.
+    // Top-level code is not a class body, it can be evaluated in a method.
+    pub(crate) fn is_in_class_or_module_body(&self) -> bool {
+        let stack = self.stack.borrow();
+        let scope = stack
+            .iter()
+            .rev()
+            .find(|i| **i != ContextItem::Block && **i != ContextItem::Lambda);
+
+        matches!(
+            scope,
+            Some(ContextItem::Class) | Some(ContextItem::Module) | Some(ContextItem::Sclass)
+        )
+    }
+
     pub(crate) fn is_in_dynamic_block(&self) -> bool {
         self.is_in(ContextItem::Block) || self.is_in(ContextItem::Lambda)
     }
@@ -3,10 +3,34 @@ use crate::{DiagnosticMessage, ErrorLevel};
 
 /// Diagnostic message that comes from the parser when there's an error or warning
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Diagnostic {
     pub level: ErrorLevel,
     pub message: DiagnosticMessage,
     pub range: Range,
+    /// Secondary ranges with explanations (like "heredoc starts here")
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub labels: Vec<Label>,
+}
+
+/// Secondary range of the diagnostic with a short explanation
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Label {
+    pub range: Range,
+    pub message: String,
+}
+
+impl Label {
+    pub fn new(range: Range, message: &str) -> Self {
+        Self {
+            range,
+            message: message.to_owned(),
+        }
+    }
 }
 
 impl Diagnostic {
@@ -15,13 +39,22 @@ impl Diagnostic {
             level,
             message,
             range,
+            labels: vec![],
         }
     }
 
+    /// Adds a secondary labeled range
+    pub fn with_label(mut self, range: Range, message: &str) -> Self {
+        self.labels.push(Label::new(range, message));
+        self
+    }
+
     pub fn render_message(&self) -> String {
         self.message.render()
     }
 
+    /// Renders the first line of the range with a single-line message,
+    /// use `DiagnosticRenderer` for multi-line output with labels
     pub fn render(&self, input: &Input) -> Option<String> {
         let (line_no, line_loc) = self.range.expand_to_line(input)?;
         let line = line_loc.source(input)?;
@@ -55,12 +88,31 @@ impl Diagnostic {
         )
     }
 
+    pub fn is_note(&self) -> bool {
+        matches!(self.level, ErrorLevel::Note)
+    }
+
     pub fn is_warning(&self) -> bool {
         matches!(self.level, ErrorLevel::Warning)
     }
 
+    /// Returns true for both `Error` and `Fatal` levels
     pub fn is_error(&self) -> bool {
-        matches!(self.level, ErrorLevel::Error)
+        matches!(self.level, ErrorLevel::Error | ErrorLevel::Fatal)
+    }
+
+    pub fn is_fatal(&self) -> bool {
+        matches!(self.level, ErrorLevel::Fatal)
+    }
+
+    /// Returns stable machine-readable code of the message, like `E0015`
+    pub fn code(&self) -> &'static str {
+        self.message.code()
+    }
+
+    /// Returns stable machine-readable name of the message, like `unterminated_heredoc`
+    pub fn name(&self) -> &'static str {
+        self.message.name()
     }
 }
 
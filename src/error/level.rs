@@ -1,15 +1,22 @@
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Error level of the diagnostic message
 pub enum ErrorLevel {
+    /// Additional information, never affects the result of parsing
+    Note,
     Warning,
     Error,
+    /// Error that stops parsing of the whole input (e.g. unsupported encoding)
+    Fatal,
 }
 
 impl std::fmt::Debug for ErrorLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::Note => f.write_str("note"),
             Self::Warning => f.write_str("warning"),
             Self::Error => f.write_str("error"),
+            Self::Fatal => f.write_str("fatal"),
         }
     }
 }
@@ -1,4 +1,5 @@
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DiagnosticMessage {
     // Lexer errors
     FractionAfterNumeric,
@@ -27,8 +28,8 @@ pub enum DiagnosticMessage {
         operator: u8,
     },
     AmbiguousOperator {
-        operator: &'static str,
-        interpreted_as: &'static str,
+        operator: String,
+        interpreted_as: String,
     },
     InvalidCharacterSyntax {
         suggestion: String,
@@ -72,6 +73,21 @@ pub enum DiagnosticMessage {
     OrdinaryParamDefined,
     NumparamUsed,
     TokAtEolWithoutExpression(String),
+    NoAnonymousBlockarg,
+    NoAnonymousRestarg,
+    NoAnonymousKwrestarg,
+    AnonymousArgUsedInBlock(String),
+    ItNotAllowedWithNumparam,
+    NumparamNotAllowedWithIt,
+    SyntaxNotAvailable {
+        syntax: String,
+        ruby_version: String,
+    },
+    UnexpectedTokenExpected {
+        got: String,
+        expected: Vec<String>,
+    },
+    InvalidYield,
 
     // Parser warnings
     EndInMethod,
@@ -105,6 +121,212 @@ pub enum DiagnosticMessage {
 }
 
 impl DiagnosticMessage {
+    /// Stable machine-readable code of the message,
+    /// `E` prefix is used for errors and `W` for warnings.
+    ///
+    /// Codes are never reused, new messages get new codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::FractionAfterNumeric => "E0001",
+            Self::NoDigitsAfterDot => "E0002",
+            Self::UnknownTypeOfPercentString => "E0003",
+            Self::NumericLiteralWithoutDigits => "E0004",
+            Self::UnterminatedList => "E0005",
+            Self::UnterminatedRegexp => "E0006",
+            Self::UnterminatedString => "E0007",
+            Self::UnterminatedQuotedString => "E0008",
+            Self::InvalidUnicodeEscape => "E0009",
+            Self::TooLargeUnicodeCodepoint => "E0010",
+            Self::InvalidUnicodeCodepoint => "E0011",
+            Self::MultipleCodepointAtSingleChar => "E0012",
+            Self::InvalidEscapeCharacter => "E0013",
+            Self::InvalidHexEscape => "E0014",
+            Self::UnterminatedHeredoc(_) => "E0015",
+            Self::UnterminatedHeredocId => "E0016",
+            Self::SlashRAtMiddleOfLine => "W0001",
+            Self::DStarInterpretedAsArgPrefix => "W0002",
+            Self::StarInterpretedAsArgPrefix => "W0003",
+            Self::AmpersandInterpretedAsArgPrefix => "W0004",
+            Self::TripleDotAtEol => "W0005",
+            Self::ParenthesesIterpretedAsArglist => "W0006",
+            Self::AmbiguousFirstArgument { .. } => "W0007",
+            Self::AmbiguousOperator { .. } => "W0008",
+            Self::InvalidCharacterSyntax { .. } => "W0009",
+            Self::InvalidOctalDigit => "E0017",
+            Self::TrailingCharInNumber { .. } => "E0018",
+            Self::EmbeddedDocumentMeetsEof => "E0019",
+            Self::InvalidChar(_) => "E0020",
+            Self::IncompleteCharacterSyntax => "E0021",
+            Self::GvarWithoutId => "E0022",
+            Self::InvalidGvarName(_) => "E0023",
+            Self::IvarWithoutId => "E0024",
+            Self::InvalidIvarName(_) => "E0025",
+            Self::CvarWithoutId => "E0026",
+            Self::InvalidCvarName(_) => "E0027",
+            Self::UnknownRegexOptions(_) => "E0028",
+            Self::UnterminatedUnicodeEscape => "E0029",
+            Self::EncodingError(_) => "E0030",
+            Self::AmbiguousTernaryOperator(_) => "W0010",
+            Self::ElseWithoutRescue => "E0031",
+            Self::BeginNotAtTopLevel => "E0032",
+            Self::AliasNthRef => "E0033",
+            Self::CsendInsideMasgn => "E0034",
+            Self::ClassOrModuleNameMustBeConstant => "E0035",
+            Self::EndlessSetterDefinition => "E0036",
+            Self::UnexpectedToken(_) => "E0037",
+            Self::ClassDefinitionInMethodBody => "E0038",
+            Self::ModuleDefinitionInMethodBody => "E0039",
+            Self::InvalidReturnInClassOrModuleBody => "E0040",
+            Self::ConstArgument => "E0041",
+            Self::IvarArgument => "E0042",
+            Self::GvarArgument => "E0043",
+            Self::CvarArgument => "E0044",
+            Self::NoSuchLocalVariable(_) => "E0045",
+            Self::OrdinaryParamDefined => "E0046",
+            Self::NumparamUsed => "E0047",
+            Self::TokAtEolWithoutExpression(_) => "W0011",
+            Self::NoAnonymousBlockarg => "E0071",
+            Self::NoAnonymousRestarg => "E0072",
+            Self::NoAnonymousKwrestarg => "E0073",
+            Self::AnonymousArgUsedInBlock(_) => "E0074",
+            Self::ItNotAllowedWithNumparam => "E0075",
+            Self::NumparamNotAllowedWithIt => "E0076",
+            Self::SyntaxNotAvailable { .. } => "E0077",
+            Self::UnexpectedTokenExpected { .. } => "E0078",
+            Self::InvalidYield => "E0079",
+            Self::EndInMethod => "W0012",
+            Self::ComparisonAfterComparison(_) => "W0013",
+            Self::CircularArgumentReference(_) => "E0048",
+            Self::DynamicConstantAssignment => "E0049",
+            Self::CantAssignToSelf => "E0050",
+            Self::CantAssignToNil => "E0051",
+            Self::CantAssignToTrue => "E0052",
+            Self::CantAssignToFalse => "E0053",
+            Self::CantAssignToFile => "E0054",
+            Self::CantAssignToLine => "E0055",
+            Self::CantAssignToEncoding => "E0056",
+            Self::CantAssignToNumparam(_) => "E0057",
+            Self::CantSetVariable(_) => "E0058",
+            Self::BlockGivenToYield => "E0059",
+            Self::BlockAndBlockArgGiven => "E0060",
+            Self::SymbolLiteralWithInterpolation => "E0061",
+            Self::ReservedForNumparam(_) => "E0062",
+            Self::KeyMustBeValidAsLocalVariable => "E0063",
+            Self::DuplicateVariableName => "E0064",
+            Self::DuplicateKeyName => "E0065",
+            Self::SingletonLiteral => "E0066",
+            Self::NthRefIsTooBig(_) => "W0014",
+            Self::DuplicatedArgumentName => "E0067",
+            Self::RegexError(_) => "E0068",
+            Self::InvalidSymbol(_) => "E0069",
+            Self::VoidValueExpression => "E0070",
+        }
+    }
+
+    /// Stable machine-readable name of the message, like `unterminated_heredoc`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::FractionAfterNumeric => "fraction_after_numeric",
+            Self::NoDigitsAfterDot => "no_digits_after_dot",
+            Self::UnknownTypeOfPercentString => "unknown_type_of_percent_string",
+            Self::NumericLiteralWithoutDigits => "numeric_literal_without_digits",
+            Self::UnterminatedList => "unterminated_list",
+            Self::UnterminatedRegexp => "unterminated_regexp",
+            Self::UnterminatedString => "unterminated_string",
+            Self::UnterminatedQuotedString => "unterminated_quoted_string",
+            Self::InvalidUnicodeEscape => "invalid_unicode_escape",
+            Self::TooLargeUnicodeCodepoint => "too_large_unicode_codepoint",
+            Self::InvalidUnicodeCodepoint => "invalid_unicode_codepoint",
+            Self::MultipleCodepointAtSingleChar => "multiple_codepoint_at_single_char",
+            Self::InvalidEscapeCharacter => "invalid_escape_character",
+            Self::InvalidHexEscape => "invalid_hex_escape",
+            Self::UnterminatedHeredoc(_) => "unterminated_heredoc",
+            Self::UnterminatedHeredocId => "unterminated_heredoc_id",
+            Self::SlashRAtMiddleOfLine => "slash_r_at_middle_of_line",
+            Self::DStarInterpretedAsArgPrefix => "dstar_interpreted_as_arg_prefix",
+            Self::StarInterpretedAsArgPrefix => "star_interpreted_as_arg_prefix",
+            Self::AmpersandInterpretedAsArgPrefix => "ampersand_interpreted_as_arg_prefix",
+            Self::TripleDotAtEol => "triple_dot_at_eol",
+            Self::ParenthesesIterpretedAsArglist => "parentheses_interpreted_as_arglist",
+            Self::AmbiguousFirstArgument { .. } => "ambiguous_first_argument",
+            Self::AmbiguousOperator { .. } => "ambiguous_operator",
+            Self::InvalidCharacterSyntax { .. } => "invalid_character_syntax",
+            Self::InvalidOctalDigit => "invalid_octal_digit",
+            Self::TrailingCharInNumber { .. } => "trailing_char_in_number",
+            Self::EmbeddedDocumentMeetsEof => "embedded_document_meets_eof",
+            Self::InvalidChar(_) => "invalid_char",
+            Self::IncompleteCharacterSyntax => "incomplete_character_syntax",
+            Self::GvarWithoutId => "gvar_without_id",
+            Self::InvalidGvarName(_) => "invalid_gvar_name",
+            Self::IvarWithoutId => "ivar_without_id",
+            Self::InvalidIvarName(_) => "invalid_ivar_name",
+            Self::CvarWithoutId => "cvar_without_id",
+            Self::InvalidCvarName(_) => "invalid_cvar_name",
+            Self::UnknownRegexOptions(_) => "unknown_regex_options",
+            Self::UnterminatedUnicodeEscape => "unterminated_unicode_escape",
+            Self::EncodingError(_) => "encoding_error",
+            Self::AmbiguousTernaryOperator(_) => "ambiguous_ternary_operator",
+            Self::ElseWithoutRescue => "else_without_rescue",
+            Self::BeginNotAtTopLevel => "begin_not_at_top_level",
+            Self::AliasNthRef => "alias_nth_ref",
+            Self::CsendInsideMasgn => "csend_inside_masgn",
+            Self::ClassOrModuleNameMustBeConstant => "class_or_module_name_must_be_constant",
+            Self::EndlessSetterDefinition => "endless_setter_definition",
+            Self::UnexpectedToken(_) => "unexpected_token",
+            Self::ClassDefinitionInMethodBody => "class_definition_in_method_body",
+            Self::ModuleDefinitionInMethodBody => "module_definition_in_method_body",
+            Self::InvalidReturnInClassOrModuleBody => "invalid_return_in_class_or_module_body",
+            Self::ConstArgument => "const_argument",
+            Self::IvarArgument => "ivar_argument",
+            Self::GvarArgument => "gvar_argument",
+            Self::CvarArgument => "cvar_argument",
+            Self::NoSuchLocalVariable(_) => "no_such_local_variable",
+            Self::OrdinaryParamDefined => "ordinary_param_defined",
+            Self::NumparamUsed => "numparam_used",
+            Self::TokAtEolWithoutExpression(_) => "tok_at_eol_without_expression",
+            Self::NoAnonymousBlockarg => "no_anonymous_blockarg",
+            Self::NoAnonymousRestarg => "no_anonymous_restarg",
+            Self::NoAnonymousKwrestarg => "no_anonymous_kwrestarg",
+            Self::AnonymousArgUsedInBlock(_) => "anonymous_arg_used_in_block",
+            Self::ItNotAllowedWithNumparam => "it_not_allowed_with_numparam",
+            Self::NumparamNotAllowedWithIt => "numparam_not_allowed_with_it",
+            Self::SyntaxNotAvailable { .. } => "syntax_not_available",
+            Self::UnexpectedTokenExpected { .. } => "unexpected_token_expected",
+            Self::InvalidYield => "invalid_yield",
+            Self::EndInMethod => "end_in_method",
+            Self::ComparisonAfterComparison(_) => "comparison_after_comparison",
+            Self::CircularArgumentReference(_) => "circular_argument_reference",
+            Self::DynamicConstantAssignment => "dynamic_constant_assignment",
+            Self::CantAssignToSelf => "cant_assign_to_self",
+            Self::CantAssignToNil => "cant_assign_to_nil",
+            Self::CantAssignToTrue => "cant_assign_to_true",
+            Self::CantAssignToFalse => "cant_assign_to_false",
+            Self::CantAssignToFile => "cant_assign_to_file",
+            Self::CantAssignToLine => "cant_assign_to_line",
+            Self::CantAssignToEncoding => "cant_assign_to_encoding",
+            Self::CantAssignToNumparam(_) => "cant_assign_to_numparam",
+            Self::CantSetVariable(_) => "cant_set_variable",
+            Self::BlockGivenToYield => "block_given_to_yield",
+            Self::BlockAndBlockArgGiven => "block_and_block_arg_given",
+            Self::SymbolLiteralWithInterpolation => "symbol_literal_with_interpolation",
+            Self::ReservedForNumparam(_) => "reserved_for_numparam",
+            Self::KeyMustBeValidAsLocalVariable => "key_must_be_valid_as_local_variable",
+            Self::DuplicateVariableName => "duplicate_variable_name",
+            Self::DuplicateKeyName => "duplicate_key_name",
+            Self::SingletonLiteral => "singleton_literal",
+            Self::NthRefIsTooBig(_) => "nth_ref_is_too_big",
+            Self::DuplicatedArgumentName => "duplicated_argument_name",
+            Self::RegexError(_) => "regex_error",
+            Self::InvalidSymbol(_) => "invalid_symbol",
+            Self::VoidValueExpression => "void_value_expression",
+        }
+    }
+
+    /// Returns true if given string is a code or a name of the message
+    pub fn is_identified_by(&self, code_or_name: &str) -> bool {
+        self.code() == code_or_name || self.name() == code_or_name
+    }
+
     pub fn render(&self) -> String {
         match self {
             // Lexer errors
@@ -179,6 +401,15 @@ impl DiagnosticMessage {
             Self::OrdinaryParamDefined => "ordinary parameter is defined".to_owned(),
             Self::NumparamUsed => "numbered parameter is already used".to_owned(),
             Self::TokAtEolWithoutExpression(tok) => format!("`{}' at the end of line without an expression", tok),
+            Self::NoAnonymousBlockarg => "no anonymous block parameter".to_owned(),
+            Self::NoAnonymousRestarg => "no anonymous rest parameter".to_owned(),
+            Self::NoAnonymousKwrestarg => "no anonymous keyword rest parameter".to_owned(),
+            Self::AnonymousArgUsedInBlock(kind) => format!("anonymous {} parameter is also used within block", kind),
+            Self::ItNotAllowedWithNumparam => "`it' is not allowed when a numbered parameter is already used".to_owned(),
+            Self::NumparamNotAllowedWithIt => "numbered parameters are not allowed when `it' is already used".to_owned(),
+            Self::SyntaxNotAvailable { syntax, ruby_version } => format!("{} is not available in Ruby {}", syntax, ruby_version),
+            Self::UnexpectedTokenExpected { got, expected } => format!("unexpected {}, expected {}", describe_token(got), describe_tokens(expected)),
+            Self::InvalidYield => "Invalid yield".to_owned(),
 
             // Parser warnings
             Self::EndInMethod => "END in method; use at_exit".to_owned(),
@@ -212,3 +443,136 @@ impl DiagnosticMessage {
         }
     }
 }
+
+// Human-friendly name of the token, like "`end'" for kEND
+fn describe_token(name: &str) -> &str {
+    match name {
+        "END_OF_INPUT" => "end-of-input",
+
+        "kCLASS" => "`class'",
+        "kMODULE" => "`module'",
+        "kDEF" => "`def'",
+        "kUNDEF" => "`undef'",
+        "kBEGIN" => "`begin'",
+        "kRESCUE" | "kRESCUE_MOD" => "`rescue'",
+        "kENSURE" => "`ensure'",
+        "kEND" => "`end'",
+        "kIF" | "kIF_MOD" => "`if'",
+        "kUNLESS" | "kUNLESS_MOD" => "`unless'",
+        "kTHEN" => "`then'",
+        "kELSIF" => "`elsif'",
+        "kELSE" => "`else'",
+        "kCASE" => "`case'",
+        "kWHEN" => "`when'",
+        "kWHILE" | "kWHILE_MOD" => "`while'",
+        "kUNTIL" | "kUNTIL_MOD" => "`until'",
+        "kFOR" => "`for'",
+        "kBREAK" => "`break'",
+        "kNEXT" => "`next'",
+        "kREDO" => "`redo'",
+        "kRETRY" => "`retry'",
+        "kIN" => "`in'",
+        "kDO" | "kDO_COND" | "kDO_BLOCK" | "kDO_LAMBDA" => "`do'",
+        "kRETURN" => "`return'",
+        "kYIELD" => "`yield'",
+        "kSUPER" => "`super'",
+        "kSELF" => "`self'",
+        "kNIL" => "`nil'",
+        "kTRUE" => "`true'",
+        "kFALSE" => "`false'",
+        "kAND" => "`and'",
+        "kOR" => "`or'",
+        "kNOT" => "`not'",
+        "kALIAS" => "`alias'",
+        "kDEFINED" => "`defined?'",
+        "klBEGIN" => "`BEGIN'",
+        "klEND" => "`END'",
+        "k__LINE__" => "`__LINE__'",
+        "k__FILE__" => "`__FILE__'",
+        "k__ENCODING__" => "`__ENCODING__'",
+
+        "tIDENTIFIER" => "local variable or method",
+        "tFID" => "method",
+        "tGVAR" => "global variable",
+        "tIVAR" => "instance variable",
+        "tCONSTANT" => "constant",
+        "tCVAR" => "class variable",
+        "tLABEL" => "label",
+        "tINTEGER" => "integer literal",
+        "tFLOAT" => "float literal",
+        "tRATIONAL" => "rational literal",
+        "tIMAGINARY" => "imaginary literal",
+        "tCHAR" => "char literal",
+        "tNTH_REF" => "numbered reference",
+        "tBACK_REF" => "back reference",
+        "tSYMBEG" => "symbol literal",
+        "tSTRING_BEG" => "string literal",
+        "tXSTRING_BEG" => "backtick literal",
+        "tREGEXP_BEG" => "regexp literal",
+        "tWORDS_BEG" | "tQWORDS_BEG" => "word list",
+        "tSYMBOLS_BEG" | "tQSYMBOLS_BEG" => "symbol list",
+        "tSTRING_END" => "string end",
+        "tNL" => "newline",
+
+        "tLPAREN" | "tLPAREN_ARG" | "tLPAREN2" => "`('",
+        "tRPAREN" => "`)'",
+        "tLBRACK" | "tLBRACK2" => "`['",
+        "tRBRACK" => "`]'",
+        "tLBRACE" | "tLBRACE_ARG" | "tLCURLY" | "tLAMBEG" => "`{'",
+        "tRCURLY" | "tSTRING_DEND" => "`}'",
+        "tCOMMA" => "`,'",
+        "tSEMI" => "`;'",
+        "tDOT" => "`.'",
+        "tANDDOT" => "`&.'",
+        "tCOLON2" | "tCOLON3" => "`::'",
+        "tDOT2" | "tBDOT2" => "`..'",
+        "tDOT3" | "tBDOT3" => "`...'",
+        "tEQL" => "`='",
+        "tASSOC" => "`=>'",
+        "tPIPE" => "`|'",
+        "tLAMBDA" => "`->'",
+        "tSTAR" | "tSTAR2" => "`*'",
+        "tDSTAR" | "tPOW" => "`**'",
+        "tAMPER" | "tAMPER2" => "`&'",
+        "tPLUS" | "tUPLUS" => "`+'",
+        "tMINUS" | "tUMINUS" => "`-'",
+        "tDIVIDE" => "`/'",
+        "tPERCENT" => "`%'",
+        "tBANG" => "`!'",
+        "tTILDE" => "`~'",
+        "tCARET" => "`^'",
+        "tGT" => "`>'",
+        "tLT" => "`<'",
+        "tGEQ" => "`>='",
+        "tLEQ" => "`<='",
+        "tEQ" => "`=='",
+        "tEQQ" => "`==='",
+        "tNEQ" => "`!='",
+        "tCMP" => "`<=>'",
+        "tMATCH" => "`=~'",
+        "tNMATCH" => "`!~'",
+        "tANDOP" => "`&&'",
+        "tOROP" => "`||'",
+        "tLSHFT" => "`<<'",
+        "tRSHFT" => "`>>'",
+
+        other => other,
+    }
+}
+
+// "`end', `rescue' or `ensure'"
+fn describe_tokens(names: &[String]) -> String {
+    let mut descriptions: Vec<&str> = vec![];
+    for name in names {
+        let description = describe_token(name);
+        if !descriptions.contains(&description) {
+            descriptions.push(description);
+        }
+    }
+
+    match descriptions.split_last() {
+        Some((last, [])) => (*last).to_owned(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+        None => "nothing".to_owned(),
+    }
+}
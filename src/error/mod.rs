@@ -1,8 +1,10 @@
 mod diagnostic;
 mod level;
 mod message;
+mod renderer;
 
-pub use diagnostic::Diagnostic;
+pub use diagnostic::{Diagnostic, Label};
 pub(crate) use diagnostic::Diagnostics;
 pub use level::ErrorLevel;
 pub use message::DiagnosticMessage;
+pub use renderer::DiagnosticRenderer;
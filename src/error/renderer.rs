@@ -0,0 +1,413 @@
+use crate::source::buffer::Input;
+use crate::source::Range;
+use crate::{Diagnostic, ErrorLevel};
+use std::collections::BTreeSet;
+
+/// Renders diagnostics in a multi-line format similar to `rustc`:
+///
+/// ```text
+/// error[E0015]: can't find string "HERE" anywhere before EOF
+///  --> (eval):1:5
+///   |
+/// 1 | foo(<<HERE)
+///   |     ^^^^^^
+/// 2 | bar
+///   |    - end of file is reached here
+/// ```
+///
+/// Supports ranges that span multiple lines and secondary labels
+/// (`Diagnostic::labels`), expands tabs and uses display width of UTF-8 characters
+/// to compute columns.
+#[derive(Debug, Clone)]
+pub struct DiagnosticRenderer {
+    /// Controls whether output is highlighted with ANSI escape codes
+    pub colors: bool,
+
+    /// Number of columns between tab stops
+    pub tab_width: usize,
+}
+
+impl Default for DiagnosticRenderer {
+    fn default() -> Self {
+        Self {
+            colors: false,
+            tab_width: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Style {
+    Plain,
+    Gutter,
+    Primary,
+    Secondary,
+}
+
+// Location of a single range, columns are display columns
+struct Span<'a> {
+    begin_line: usize,
+    begin_col: usize,
+    end_line: usize,
+    // exclusive
+    end_col: usize,
+    style: Style,
+    message: &'a str,
+}
+
+impl Span<'_> {
+    fn is_multiline(&self) -> bool {
+        self.begin_line != self.end_line
+    }
+}
+
+type Row = Vec<(char, Style)>;
+
+impl DiagnosticRenderer {
+    /// Renders given `diagnostic`, returns `None` if any of its ranges is synthetic or outside of `input`
+    pub fn render(&self, diagnostic: &Diagnostic, input: &Input) -> Option<String> {
+        let mut spans = vec![self.span(&diagnostic.range, Style::Primary, "", input)?];
+        for label in diagnostic.labels.iter() {
+            spans.push(self.span(&label.range, Style::Secondary, &label.message, input)?);
+        }
+
+        let mut lines = BTreeSet::new();
+        for span in spans.iter() {
+            lines.insert(span.begin_line);
+            lines.insert(span.end_line);
+            if span.is_multiline() {
+                lines.insert(span.begin_line + 1);
+                lines.insert(span.end_line - 1);
+            }
+        }
+        let multiline = spans
+            .iter()
+            .filter(|span| span.is_multiline())
+            .collect::<Vec<_>>();
+
+        let number_width = (lines.iter().last()? + 1).to_string().len();
+        let mut output = vec![];
+
+        let primary = &spans[0];
+        output.push(self.paint(
+            &diagnostic.level,
+            &[
+                text(
+                    &format!("{:?}[{}]", diagnostic.level, diagnostic.code()),
+                    Style::Primary,
+                ),
+                text(&format!(": {}", diagnostic.render_message()), Style::Plain),
+            ],
+        ));
+        output.push(self.paint(
+            &diagnostic.level,
+            &[
+                text(&format!("{}--> ", " ".repeat(number_width)), Style::Gutter),
+                text(
+                    &format!(
+                        "{}:{}:{}",
+                        input.name,
+                        primary.begin_line + 1,
+                        primary.begin_col + 1
+                    ),
+                    Style::Plain,
+                ),
+            ],
+        ));
+        output.push(self.paint(
+            &diagnostic.level,
+            &[text(
+                &format!("{} |", " ".repeat(number_width)),
+                Style::Gutter,
+            )],
+        ));
+
+        let mut prev_line: Option<usize> = None;
+        for line in lines.iter().copied() {
+            if let Some(prev_line) = prev_line {
+                if line > prev_line + 1 {
+                    output.push(self.paint(&diagnostic.level, &[text("...", Style::Gutter)]));
+                }
+            }
+            prev_line = Some(line);
+
+            // source line
+            let mut gutter = Row::new();
+            for span in multiline.iter() {
+                let c = if span.begin_line == line {
+                    if span.begin_col == 0 {
+                        '/'
+                    } else {
+                        ' '
+                    }
+                } else if span.begin_line < line && line <= span.end_line {
+                    '|'
+                } else {
+                    ' '
+                };
+                gutter.push((c, span.style));
+                gutter.push((' ', Style::Plain));
+            }
+            output.push(self.paint(
+                &diagnostic.level,
+                &[
+                    text(
+                        &format!("{:>w$} | ", line + 1, w = number_width),
+                        Style::Gutter,
+                    ),
+                    gutter,
+                    text(&self.expand_tabs(line_text(input, line)), Style::Plain),
+                ],
+            ));
+
+            let prefix = text(&format!("{} | ", " ".repeat(number_width)), Style::Gutter);
+            let active_gutter = |started: &dyn Fn(&Span) -> bool| {
+                let mut gutter = Row::new();
+                for span in multiline.iter() {
+                    let c = if started(span) && line < span.end_line {
+                        '|'
+                    } else {
+                        ' '
+                    };
+                    gutter.push((c, span.style));
+                    gutter.push((' ', Style::Plain));
+                }
+                gutter
+            };
+
+            // labels of single-line spans
+            let single = spans
+                .iter()
+                .filter(|span| !span.is_multiline() && span.begin_line == line)
+                .collect::<Vec<_>>();
+            if !single.is_empty() {
+                let gutter = active_gutter(&|span| {
+                    span.begin_line < line || (span.begin_line == line && span.begin_col == 0)
+                });
+                for row in self.label_rows(&single) {
+                    output.push(
+                        self.paint(&diagnostic.level, &[prefix.clone(), gutter.clone(), row]),
+                    );
+                }
+            }
+
+            // beginnings and ends of multi-line spans
+            for (idx, span) in multiline.iter().enumerate() {
+                if span.begin_line == line && span.begin_col != 0 {
+                    let mut row = active_gutter(&|other| {
+                        other.begin_line < line
+                            || (other.begin_line == line && other.begin_col == 0)
+                    });
+                    row.truncate(idx * 2 + 1);
+                    row.resize(multiline.len() * 2 + span.begin_col, ('_', span.style));
+                    row.push((self.marker(span.style), span.style));
+                    output.push(self.paint(&diagnostic.level, &[prefix.clone(), row]));
+                }
+            }
+            for (idx, span) in multiline.iter().enumerate() {
+                if span.end_line == line {
+                    let mut row = active_gutter(&|other| other.begin_line <= line);
+                    row.truncate(idx * 2);
+                    row.push(('|', span.style));
+                    row.resize(
+                        multiline.len() * 2 + span.end_col.saturating_sub(1),
+                        ('_', span.style),
+                    );
+                    row.push((self.marker(span.style), span.style));
+                    if !span.message.is_empty() {
+                        row.extend(text(&format!(" {}", span.message), span.style));
+                    }
+                    output.push(self.paint(&diagnostic.level, &[prefix.clone(), row]));
+                }
+            }
+        }
+
+        Some(output.join("\n"))
+    }
+
+    fn span<'a>(
+        &self,
+        range: &Range,
+        style: Style,
+        message: &'a str,
+        input: &Input,
+    ) -> Option<Span<'a>> {
+        if range.is_synthetic() {
+            return None;
+        }
+        let (begin_line, begin_offset) = input.line_col_for_pos(range.begin_pos)?;
+        let (end_line, end_offset) = if range.size() == 0 {
+            (begin_line, begin_offset)
+        } else {
+            // location of the last byte, ranges can end with a newline
+            let (line, offset) = input.line_col_for_pos(range.end_pos - 1)?;
+            (line, offset + 1)
+        };
+
+        let begin_col = self.display_col(line_text(input, begin_line), begin_offset);
+        let mut end_col = self.display_col(line_text(input, end_line), end_offset);
+        if begin_line == end_line {
+            end_col = std::cmp::max(end_col, begin_col + 1);
+        }
+
+        Some(Span {
+            begin_line,
+            begin_col,
+            end_line,
+            end_col,
+            style,
+            message,
+        })
+    }
+
+    // Markers under a line and messages of labels like
+    //
+    //     ^^^^^   --- secondary
+    //     |
+    //     primary
+    fn label_rows(&self, spans: &[&Span]) -> Vec<Row> {
+        let width = spans.iter().map(|span| span.end_col).max().unwrap_or(0);
+        let mut markers = vec![(' ', Style::Plain); width];
+        // secondary first, primary markers override them
+        for span in spans.iter().rev() {
+            for cell in markers[span.begin_col..span.end_col].iter_mut() {
+                *cell = (self.marker(span.style), span.style);
+            }
+        }
+
+        let mut pending = spans
+            .iter()
+            .filter(|span| !span.message.is_empty())
+            .collect::<Vec<_>>();
+        pending.sort_by_key(|span| span.begin_col);
+
+        if let Some(last) = pending.last() {
+            // message can be printed right after markers
+            // only if there are no other markers on the right
+            if last.end_col == width {
+                markers.extend(text(&format!(" {}", last.message), last.style));
+                pending.pop();
+            }
+        }
+
+        let mut rows = vec![markers];
+        while let Some(last) = pending.last() {
+            let mut connectors = Row::new();
+            for span in pending.iter() {
+                connectors.resize(span.begin_col, (' ', Style::Plain));
+                connectors.push(('|', span.style));
+            }
+            rows.push(connectors.clone());
+
+            connectors.truncate(last.begin_col);
+            connectors.extend(text(last.message, last.style));
+            rows.push(connectors);
+            pending.pop();
+        }
+        rows
+    }
+
+    fn marker(&self, style: Style) -> char {
+        if style == Style::Primary {
+            '^'
+        } else {
+            '-'
+        }
+    }
+
+    fn paint(&self, level: &ErrorLevel, parts: &[Row]) -> String {
+        let mut result = String::new();
+        let mut current = Style::Plain;
+        for (c, style) in parts.iter().flatten().copied() {
+            let style = if c == ' ' { Style::Plain } else { style };
+            if self.colors && style != current {
+                result.push_str("\x1b[0m");
+                result.push_str(match style {
+                    Style::Plain => "",
+                    Style::Gutter => "\x1b[1;34m",
+                    Style::Primary => match level {
+                        ErrorLevel::Note => "\x1b[1;32m",
+                        ErrorLevel::Warning => "\x1b[1;33m",
+                        ErrorLevel::Error | ErrorLevel::Fatal => "\x1b[1;31m",
+                    },
+                    Style::Secondary => "\x1b[1;36m",
+                });
+                current = style;
+            }
+            result.push(c);
+        }
+        if self.colors && current != Style::Plain {
+            result.push_str("\x1b[0m");
+        }
+        result.trim_end().to_owned()
+    }
+
+    fn display_col(&self, line: &[u8], byte_offset: usize) -> usize {
+        let prefix = &line[..std::cmp::min(byte_offset, line.len())];
+        let mut col = 0;
+        for c in String::from_utf8_lossy(prefix).chars() {
+            col += self.char_width(c, col);
+        }
+        col
+    }
+
+    fn expand_tabs(&self, line: &[u8]) -> String {
+        let mut result = String::new();
+        let mut col = 0;
+        for c in String::from_utf8_lossy(line).chars() {
+            let width = self.char_width(c, col);
+            if c == '\t' {
+                result.push_str(&" ".repeat(width));
+            } else {
+                result.push(c);
+            }
+            col += width;
+        }
+        result
+    }
+
+    fn char_width(&self, c: char, col: usize) -> usize {
+        if c == '\t' {
+            let tab_width = std::cmp::max(self.tab_width, 1);
+            return tab_width - col % tab_width;
+        }
+        display_width(c)
+    }
+}
+
+fn text(s: &str, style: Style) -> Row {
+    s.chars().map(|c| (c, style)).collect()
+}
+
+// Bytes of the line without trailing newline
+fn line_text(input: &Input, line: usize) -> &[u8] {
+    let line = &input.lines[line];
+    let text = &input.bytes[line.start..line.line_end()];
+    text.strip_suffix(b"\r").unwrap_or(text)
+}
+
+// Number of columns taken by the char in a terminal:
+// 0 for combining and zero-width chars, 2 for East Asian wide chars and emojis
+fn display_width(c: char) -> usize {
+    let c = c as u32;
+    match c {
+        0x0300..=0x036F | 0x200B..=0x200F | 0x20D0..=0x20FF | 0xFE00..=0xFE0F | 0xFE20..=0xFE2F => {
+            0
+        }
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
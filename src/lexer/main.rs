@@ -1075,8 +1075,8 @@ impl Lexer {
         {
             self.warn(
                 DiagnosticMessage::AmbiguousOperator {
-                    operator: op,
-                    interpreted_as: syn,
+                    operator: op.to_owned(),
+                    interpreted_as: syn.to_owned(),
                 },
                 self.current_range(),
             );
@@ -1092,6 +1092,14 @@ impl Lexer {
         self.diagnostics.emit(diagnostic);
     }
 
+    pub(crate) fn fatal(&mut self, message: DiagnosticMessage, range: Range) {
+        if self.debug {
+            println!("Fatal error: {}", message.render())
+        }
+        let diagnostic = Diagnostic::new(ErrorLevel::Fatal, message, range);
+        self.diagnostics.emit(diagnostic);
+    }
+
     pub(crate) fn new_strterm(
         &self,
         func: usize,
@@ -33,3 +33,6 @@ pub(crate) use parse_gvar::ParseGvar;
 
 mod parse_atmark;
 pub(crate) use parse_atmark::ParseAtMark;
+
+mod trivia;
+pub(crate) use trivia::Trivia;
@@ -4,7 +4,7 @@ use crate::source::buffer::*;
 use crate::str_term::{str_types::*, HeredocEnd, HeredocLiteral, StrTerm};
 use crate::Lexer;
 use crate::TokenBuf;
-use crate::{lex_states::*, DiagnosticMessage};
+use crate::{lex_states::*, Diagnostic, DiagnosticMessage, ErrorLevel};
 
 pub(crate) trait ParseHeredoc {
     fn heredoc_identifier(&mut self) -> Option<i32>;
@@ -332,17 +332,21 @@ impl ParseHeredoc for Lexer {
 
     fn here_document_error(&mut self, here: &HeredocLiteral, eos: usize, len: usize) -> i32 {
         self.heredoc_restore(&here);
-        self.compile_error(
-            DiagnosticMessage::UnterminatedHeredoc(
-                String::from_utf8_lossy(
-                    self.buffer
-                        .substr_at(eos, eos + len)
-                        .expect("failed to get heredoc id for comparison"),
-                )
-                .into_owned(),
-            ),
-            self.current_range(),
+        let message = DiagnosticMessage::UnterminatedHeredoc(
+            String::from_utf8_lossy(
+                self.buffer
+                    .substr_at(eos, eos + len)
+                    .expect("failed to get heredoc id for comparison"),
+            )
+            .into_owned(),
         );
+        if self.debug {
+            println!("Compile error: {}", message.render())
+        }
+        let eof = self.buffer.input.len();
+        let diagnostic = Diagnostic::new(ErrorLevel::Error, message, self.current_range())
+            .with_label(self.range(eof, eof), "end of file is reached here");
+        self.diagnostics.emit(diagnostic);
         self.token_flush();
         self.strterm = None;
         self.lex_state.set(EXPR_END);
@@ -326,7 +326,7 @@ impl ParseMagicComment for Lexer {
                         match self.buffer.set_encoding(&encoding) {
                             Ok(_) => {}
                             Err(err) => {
-                                self.yyerror1(
+                                self.fatal(
                                     DiagnosticMessage::EncodingError(err.to_string()),
                                     self.range(vbeg, vend),
                                 );
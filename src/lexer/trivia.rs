@@ -0,0 +1,123 @@
+use crate::lexer::*;
+use crate::parser::{Loc, Token, TokenValue};
+use crate::source::{Comment, CommentType};
+
+impl Lexer {
+    /// Returns true if given token type is one of trivia tokens
+    /// (that are recorded only in lossless mode and never reach the parser)
+    pub fn is_trivia(token_type: i32) -> bool {
+        token_type == Self::tWHITESPACE
+            || token_type == Self::tIGNORED_NL
+            || token_type == Self::tLINE_CONTINUATION
+            || token_type == Self::tCOMMENT
+            || token_type == Self::tEMBDOC
+            || token_type == Self::tEND_DATA
+            || token_type == Self::tSKIPPED
+    }
+}
+
+pub(crate) trait Trivia {
+    fn add_trivia(&self, tokens: Vec<Token>) -> Vec<Token>;
+    fn push_trivia(&self, tokens: &mut Vec<Token>, begin: usize, end: usize);
+    fn trivia_token(&self, token_type: i32, begin: usize, end: usize) -> Token;
+}
+
+impl Trivia for Lexer {
+    // Sorts tokens by location and fills gaps between them with trivia tokens,
+    // so that all tokens together cover the whole input
+    fn add_trivia(&self, mut tokens: Vec<Token>) -> Vec<Token> {
+        // heredoc bodies are lexed before the rest of the line
+        tokens.sort_by_key(|token| token.loc.begin);
+
+        let mut result = Vec::with_capacity(tokens.len() * 2);
+        let mut end_of_input = None;
+        let mut pos = 0;
+
+        for token in tokens {
+            if token.token_type == Self::END_OF_INPUT {
+                end_of_input = Some(token);
+                continue;
+            }
+            if token.loc.begin > pos {
+                self.push_trivia(&mut result, pos, token.loc.begin);
+            }
+            pos = std::cmp::max(pos, token.loc.end);
+            result.push(token);
+        }
+
+        let len = self.buffer.input.len();
+        if len > pos {
+            self.push_trivia(&mut result, pos, len);
+        }
+        if let Some(mut token) = end_of_input {
+            // `__END__` returns end-of-input at the beginning of the data section
+            token.loc = Loc {
+                begin: len,
+                end: len,
+            };
+            result.push(token);
+        }
+
+        result
+    }
+
+    fn push_trivia(&self, tokens: &mut Vec<Token>, begin: usize, end: usize) {
+        let bytes = &self.buffer.input.bytes;
+        let comment_at = |pos: usize| -> Option<&Comment> {
+            self.comments
+                .binary_search_by_key(&pos, |comment| comment.location.begin_pos)
+                .ok()
+                .map(|idx| &self.comments[idx])
+        };
+        let is_space = |byte: u8| matches!(byte, b' ' | b'\t' | b'\r' | 0x0b | 0x0c);
+        let is_bol = |pos: usize| pos == 0 || bytes[pos - 1] == b'\n';
+
+        let mut pos = begin;
+        while pos < end {
+            let rest = &bytes[pos..end];
+
+            let (token_type, len) = if let Some(comment) = comment_at(pos) {
+                let token_type = match comment.kind {
+                    CommentType::Document => Self::tEMBDOC,
+                    _ => Self::tCOMMENT,
+                };
+                let comment_end = std::cmp::min(comment.location.end_pos, end);
+                (token_type, std::cmp::max(comment_end - pos, 1))
+            } else if rest.starts_with(b"\\\n") {
+                (Self::tLINE_CONTINUATION, 2)
+            } else if rest.starts_with(b"\\\r\n") {
+                (Self::tLINE_CONTINUATION, 3)
+            } else if rest[0] == b'\n' {
+                (Self::tIGNORED_NL, 1)
+            } else if is_space(rest[0]) {
+                let len = rest.iter().take_while(|byte| is_space(**byte)).count();
+                (Self::tWHITESPACE, len)
+            } else if is_bol(pos) && rest.starts_with(b"__END__") {
+                (Self::tEND_DATA, rest.len())
+            } else {
+                // invalid chars, or input after a fatal error
+                let len = rest
+                    .iter()
+                    .position(|byte| matches!(byte, b'\n' | b'\\') || is_space(*byte))
+                    .unwrap_or(rest.len());
+                (Self::tSKIPPED, std::cmp::max(len, 1))
+            };
+
+            tokens.push(self.trivia_token(token_type, pos, pos + len));
+            pos += len;
+        }
+    }
+
+    fn trivia_token(&self, token_type: i32, begin: usize, end: usize) -> Token {
+        let bytes = &self.buffer.input.bytes[begin..end];
+        let token_value = match String::from_utf8(bytes.to_vec()) {
+            Ok(value) => TokenValue::String(value),
+            Err(_) => TokenValue::InvalidString(bytes.to_vec()),
+        };
+        Token {
+            token_type,
+            token_value,
+            loc: Loc { begin, end },
+        }
+    }
+}
@@ -13,13 +13,21 @@ pub(crate) mod parse_value;
 mod parser_options;
 pub use parser_options::ParserOptions;
 
+mod ruby_version;
+pub use ruby_version::RubyVersion;
+
 mod parser_result;
 pub use parser_result::ParserResult;
 
+mod reparse;
+
 mod parser;
 pub(crate) use parser::Loc;
 pub use parser::{token_name, Parser, Token};
 
+#[cfg(feature = "serde")]
+mod token_serde;
+
 mod builder;
 pub(crate) use builder::Builder;
 
@@ -33,7 +41,7 @@ mod variables_stack;
 pub(crate) use variables_stack::VariablesStack;
 
 mod error;
-pub use error::{Diagnostic, DiagnosticMessage, ErrorLevel};
+pub use error::{Diagnostic, DiagnosticMessage, DiagnosticRenderer, ErrorLevel, Label};
 
 pub(crate) mod maybe_byte;
 
@@ -44,6 +52,9 @@ pub(crate) use lex_state::LexState;
 mod token_buf;
 pub(crate) use token_buf::TokenBuf;
 
+mod regexp_parser;
+pub(crate) use regexp_parser::RegexpParser;
+
 mod reserved_words;
 pub(crate) use reserved_words::reserved_word;
 
@@ -53,12 +64,17 @@ pub(crate) use stack_state::StackState;
 pub(crate) mod str_term;
 
 mod context;
-pub(crate) use context::{Context, ContextItem};
+pub(crate) use context::Context;
+pub use context::ContextItem;
 
 pub mod nodes;
 pub use nodes::Node;
 
 pub mod traverse;
 
+pub mod analysis;
+
+pub mod unparse;
+
 mod string_value;
 pub use string_value::StringValue;
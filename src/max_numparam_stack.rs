@@ -4,12 +4,14 @@ use std::rc::Rc;
 #[derive(Debug, Clone, Default)]
 pub(crate) struct MaxNumparamStack {
     stack: Rc<RefCell<Vec<i32>>>,
+    uses_it: Rc<RefCell<Vec<bool>>>,
 }
 
 impl MaxNumparamStack {
     pub(crate) fn new() -> Self {
         Self {
             stack: Rc::new(RefCell::new(vec![])),
+            uses_it: Rc::new(RefCell::new(vec![])),
         }
     }
 
@@ -29,16 +31,28 @@ impl MaxNumparamStack {
         self.set(std::cmp::max(self.top(), numparam))
     }
 
+    pub(crate) fn set_uses_it(&self) {
+        let mut uses_it = self.uses_it.borrow_mut();
+        uses_it.pop();
+        uses_it.push(true)
+    }
+
+    pub(crate) fn uses_it(&self) -> bool {
+        *self.uses_it.borrow().last().unwrap_or(&false)
+    }
+
     pub(crate) fn top(&self) -> i32 {
         *self.stack.borrow().last().unwrap_or(&std::i32::MIN)
     }
 
     pub(crate) fn push(&self) {
-        self.stack.borrow_mut().push(0)
+        self.stack.borrow_mut().push(0);
+        self.uses_it.borrow_mut().push(false)
     }
 
     pub(crate) fn pop(&self) {
         self.stack.borrow_mut().pop();
+        self.uses_it.borrow_mut().pop();
     }
 
     fn set(&self, value: i32) {
@@ -0,0 +1,986 @@
+use crate::nodes::*;
+use crate::source::Range;
+use crate::Node;
+use crate::StringValue;
+
+/// Constructors for building AST nodes without source code.
+///
+/// All location fields of created nodes are set to the same range,
+/// `Range::synthetic()` by default, so generated nodes can be told apart
+/// from parsed ones with `Node::is_synthetic()`.
+///
+/// Nodes are shaped the same way as the parser would produce them
+/// for the equivalent code (e.g. a single block argument becomes `procarg0`,
+/// parts of interpolated strings are wrapped into `begin`), so they can be
+/// mixed with parsed nodes and printed with `unparse`.
+///
+/// ```rust
+/// use lib_ruby_parser::nodes::NodeFactory;
+/// use lib_ruby_parser::unparse::unparse;
+///
+/// let f = NodeFactory::new();
+/// let call = f.send(Some(f.lvar("foo")), "bar", vec![f.int(1)]);
+/// assert!(call.is_synthetic());
+/// assert_eq!(unparse(&call), "foo.bar(1)");
+/// ```
+///
+/// Constructors take statements as `Vec<Node>`: an empty list is no body,
+/// a list of multiple statements is wrapped into an implicit `begin`.
+/// Nodes that don't have a constructor here can be created as plain structs
+/// with `factory.loc()` as location.
+#[derive(Debug, Clone)]
+pub struct NodeFactory {
+    loc: Range,
+}
+
+impl Default for NodeFactory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NodeFactory {
+    /// Returns a factory that creates synthetic nodes
+    pub fn new() -> Self {
+        Self::at(Range::synthetic())
+    }
+
+    /// Returns a factory that attributes all created nodes to the given range,
+    /// useful for rewriters that replace existing code
+    pub fn at(loc: Range) -> Self {
+        Self { loc }
+    }
+
+    /// Returns the range used for all locations
+    pub fn loc(&self) -> Range {
+        self.loc.clone()
+    }
+
+    fn maybe_loc(&self) -> Option<Range> {
+        Some(self.loc())
+    }
+
+    // Mirrors `Builder::unary_num`
+    fn sign_loc(&self, negative: bool) -> Option<Range> {
+        if negative {
+            self.maybe_loc()
+        } else {
+            None
+        }
+    }
+
+    // Mirrors `Builder::compstmt`
+    fn compstmt(&self, mut statements: Vec<Node>) -> Option<Node> {
+        match statements.len() {
+            0 => None,
+            1 => statements.pop(),
+            _ => Some(self.begin(statements)),
+        }
+    }
+
+    //
+    // Literals
+    //
+
+    pub fn nil(&self) -> Node {
+        Node::Nil(Box::new(Nil {
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn true_(&self) -> Node {
+        Node::True(Box::new(True {
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn false_(&self) -> Node {
+        Node::False(Box::new(False {
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn self_(&self) -> Node {
+        Node::Self_(Box::new(Self_ {
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// Negative numbers get `operator_l` like `-1` in the source
+    pub fn int(&self, value: i64) -> Node {
+        Node::Int(Box::new(Int {
+            value: value.to_string(),
+            expression_l: self.loc(),
+            operator_l: self.sign_loc(value < 0),
+        }))
+    }
+
+    /// `value` must be finite, Ruby has no literals for NaN and infinity
+    pub fn float(&self, value: f64) -> Node {
+        Node::Float(Box::new(Float {
+            value: format!("{:?}", value),
+            expression_l: self.loc(),
+            operator_l: self.sign_loc(value.is_sign_negative()),
+        }))
+    }
+
+    /// `"value"`
+    pub fn str(&self, value: &str) -> Node {
+        Node::Str(Box::new(Str {
+            value: StringValue::from(value),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `"a#{b}"`, `str` parts are inlined, other parts are interpolated
+    pub fn dstr(&self, parts: Vec<Node>) -> Node {
+        Node::Dstr(Box::new(Dstr {
+            parts: self.interpolated_parts(parts),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `:name`
+    pub fn sym(&self, name: &str) -> Node {
+        Node::Sym(Box::new(Sym {
+            name: StringValue::from(name),
+            begin_l: self.maybe_loc(),
+            end_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `:"a#{b}"`
+    pub fn dsym(&self, parts: Vec<Node>) -> Node {
+        Node::Dsym(Box::new(Dsym {
+            parts: self.interpolated_parts(parts),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `/source/options`, `source` is taken as is (i.e. must be escaped)
+    pub fn regexp(&self, source: &str, options: &str) -> Node {
+        let options = if options.is_empty() {
+            None
+        } else {
+            let mut options: Vec<char> = options.chars().collect();
+            options.sort_unstable();
+            options.dedup();
+            Some(Node::RegOpt(Box::new(RegOpt {
+                options,
+                expression_l: self.loc(),
+            })))
+        };
+        let parts = if source.is_empty() {
+            vec![]
+        } else {
+            vec![self.str_part(source)]
+        };
+        Node::Regexp(Box::new(Regexp {
+            parts,
+            options,
+            begin_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `[elements]`
+    pub fn array(&self, elements: Vec<Node>) -> Node {
+        Node::Array(Box::new(Array {
+            elements,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `{ pairs }`
+    pub fn hash(&self, pairs: Vec<Node>) -> Node {
+        Node::Hash(Box::new(Hash {
+            pairs,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `key => value`, or `key: value` for a symbol key
+    pub fn pair(&self, key: Node, value: Node) -> Node {
+        Node::Pair(Box::new(Pair {
+            key,
+            value,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `left..right`
+    pub fn irange(&self, left: Option<Node>, right: Option<Node>) -> Node {
+        Node::Irange(Box::new(Irange {
+            left,
+            right,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `left...right`
+    pub fn erange(&self, left: Option<Node>, right: Option<Node>) -> Node {
+        Node::Erange(Box::new(Erange {
+            left,
+            right,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Variables and constants
+    //
+
+    pub fn lvar(&self, name: &str) -> Node {
+        Node::Lvar(Box::new(Lvar {
+            name: name.to_owned(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `@name`, `name` includes `@`
+    pub fn ivar(&self, name: &str) -> Node {
+        Node::Ivar(Box::new(Ivar {
+            name: name.to_owned(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `$name`, `name` includes `$`
+    pub fn gvar(&self, name: &str) -> Node {
+        Node::Gvar(Box::new(Gvar {
+            name: name.to_owned(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `@@name`, `name` includes `@@`
+    pub fn cvar(&self, name: &str) -> Node {
+        Node::Cvar(Box::new(Cvar {
+            name: name.to_owned(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `Name` or `scope::Name`
+    pub fn const_(&self, scope: Option<Node>, name: &str) -> Node {
+        Node::Const(Box::new(Const {
+            double_colon_l: scope.as_ref().map(|_| self.loc()),
+            scope,
+            name: name.to_owned(),
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `::` prefix of top-level constants, `scope` for `::Name`
+    pub fn cbase(&self) -> Node {
+        Node::Cbase(Box::new(Cbase {
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Assignments
+    //
+
+    /// `name = value`, or a `mlhs` item without value
+    pub fn lvasgn(&self, name: &str, value: Option<Node>) -> Node {
+        Node::Lvasgn(Box::new(Lvasgn {
+            name: name.to_owned(),
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn ivasgn(&self, name: &str, value: Option<Node>) -> Node {
+        Node::Ivasgn(Box::new(Ivasgn {
+            name: name.to_owned(),
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn gvasgn(&self, name: &str, value: Option<Node>) -> Node {
+        Node::Gvasgn(Box::new(Gvasgn {
+            name: name.to_owned(),
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn cvasgn(&self, name: &str, value: Option<Node>) -> Node {
+        Node::Cvasgn(Box::new(Cvasgn {
+            name: name.to_owned(),
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    pub fn casgn(&self, scope: Option<Node>, name: &str, value: Option<Node>) -> Node {
+        Node::Casgn(Box::new(Casgn {
+            double_colon_l: scope.as_ref().map(|_| self.loc()),
+            scope,
+            name: name.to_owned(),
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv op= value`, `recv` is an assignment without value (like `lvasgn("a", None)`),
+    /// `operator` is a binary operator without `=` (like `"+"`)
+    pub fn op_asgn(&self, recv: Node, operator: &str, value: Node) -> Node {
+        Node::OpAsgn(Box::new(OpAsgn {
+            recv,
+            value,
+            operator: operator.to_owned(),
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv ||= value`
+    pub fn or_asgn(&self, recv: Node, value: Node) -> Node {
+        Node::OrAsgn(Box::new(OrAsgn {
+            recv,
+            value,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv &&= value`
+    pub fn and_asgn(&self, recv: Node, value: Node) -> Node {
+        Node::AndAsgn(Box::new(AndAsgn {
+            recv,
+            value,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `a, b = rhs`, items are assignments without value, splats or nested `mlhs`
+    pub fn masgn(&self, items: Vec<Node>, rhs: Node) -> Node {
+        Node::Masgn(Box::new(Masgn {
+            lhs: Node::Mlhs(Box::new(Mlhs {
+                items,
+                begin_l: None,
+                end_l: None,
+                expression_l: self.loc(),
+            })),
+            rhs,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `(a, b)` inside of `masgn` or block arguments
+    pub fn mlhs(&self, items: Vec<Node>) -> Node {
+        Node::Mlhs(Box::new(Mlhs {
+            items,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Method calls
+    //
+
+    /// `recv.method_name(args)`, or `method_name(args)` without receiver.
+    ///
+    /// Operators are also method calls, `1 + 2` is `send(Some(int(1)), "+", vec![int(2)])`
+    pub fn send(&self, recv: Option<Node>, method_name: &str, args: Vec<Node>) -> Node {
+        Node::Send(Box::new(Send {
+            dot_l: recv.as_ref().map(|_| self.loc()),
+            recv,
+            method_name: method_name.to_owned(),
+            begin_l: None,
+            end_l: None,
+            args,
+            selector_l: self.maybe_loc(),
+            operator_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv&.method_name(args)`
+    pub fn csend(&self, recv: Node, method_name: &str, args: Vec<Node>) -> Node {
+        Node::CSend(Box::new(CSend {
+            method_name: method_name.to_owned(),
+            recv,
+            args,
+            dot_l: self.loc(),
+            selector_l: self.loc(),
+            begin_l: None,
+            end_l: None,
+            operator_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv[indexes]`
+    pub fn index(&self, recv: Node, indexes: Vec<Node>) -> Node {
+        Node::Index(Box::new(Index {
+            recv,
+            indexes,
+            begin_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `recv[indexes] = value`
+    pub fn index_asgn(&self, recv: Node, indexes: Vec<Node>, value: Option<Node>) -> Node {
+        Node::IndexAsgn(Box::new(IndexAsgn {
+            recv,
+            indexes,
+            operator_l: value.as_ref().map(|_| self.loc()),
+            value,
+            begin_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `call { |args| body }`, `call` is a `send`, `csend`, `super` or `zsuper`
+    pub fn block(&self, call: Node, args: Vec<Node>, body: Vec<Node>) -> Node {
+        let args = match &args[..] {
+            [] => None,
+            // `|a|`
+            [Node::Arg(_)] => Some(Node::Procarg0(Box::new(Procarg0 {
+                args,
+                begin_l: None,
+                end_l: None,
+                expression_l: self.loc(),
+            }))),
+            _ => Some(self.args(args)),
+        };
+        Node::Block(Box::new(Block {
+            call,
+            args,
+            body: self.compstmt(body),
+            begin_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `->(args) { body }`
+    pub fn lambda(&self, args: Vec<Node>, body: Vec<Node>) -> Node {
+        Node::Block(Box::new(Block {
+            call: Node::Lambda(Box::new(Lambda {
+                expression_l: self.loc(),
+            })),
+            args: if args.is_empty() {
+                None
+            } else {
+                Some(self.args(args))
+            },
+            body: self.compstmt(body),
+            begin_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `&value`
+    pub fn block_pass(&self, value: Node) -> Node {
+        Node::BlockPass(Box::new(BlockPass {
+            value,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `*value`
+    pub fn splat(&self, value: Option<Node>) -> Node {
+        Node::Splat(Box::new(Splat {
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+            value,
+        }))
+    }
+
+    /// `**value`
+    pub fn kwsplat(&self, value: Node) -> Node {
+        Node::Kwsplat(Box::new(Kwsplat {
+            value,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `super(args)`
+    pub fn super_(&self, args: Vec<Node>) -> Node {
+        Node::Super(Box::new(Super {
+            args,
+            keyword_l: self.loc(),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `super` without arguments
+    pub fn zsuper(&self) -> Node {
+        Node::ZSuper(Box::new(ZSuper {
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `yield(args)`
+    pub fn yield_(&self, args: Vec<Node>) -> Node {
+        Node::Yield(Box::new(Yield {
+            args,
+            keyword_l: self.loc(),
+            begin_l: None,
+            end_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Operators
+    //
+
+    /// `lhs && rhs`
+    pub fn and(&self, lhs: Node, rhs: Node) -> Node {
+        Node::And(Box::new(And {
+            lhs,
+            rhs,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `lhs || rhs`
+    pub fn or(&self, lhs: Node, rhs: Node) -> Node {
+        Node::Or(Box::new(Or {
+            lhs,
+            rhs,
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `!value`
+    pub fn not(&self, value: Node) -> Node {
+        self.send(Some(value), "!", vec![])
+    }
+
+    /// `defined?(value)`
+    pub fn defined(&self, value: Node) -> Node {
+        Node::Defined(Box::new(Defined {
+            value,
+            keyword_l: self.loc(),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Control flow
+    //
+
+    /// Statements without delimiters (like a body of a method)
+    pub fn begin(&self, statements: Vec<Node>) -> Node {
+        Node::Begin(Box::new(Begin {
+            statements,
+            begin_l: None,
+            end_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `(statements)`
+    pub fn parens(&self, statements: Vec<Node>) -> Node {
+        Node::Begin(Box::new(Begin {
+            statements,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `begin; statements; end`
+    pub fn kw_begin(&self, statements: Vec<Node>) -> Node {
+        Node::KwBegin(Box::new(KwBegin {
+            statements,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `if cond; if_true; else; if_false; end`
+    pub fn if_(&self, cond: Node, if_true: Vec<Node>, if_false: Vec<Node>) -> Node {
+        let if_false = self.compstmt(if_false);
+        Node::If(Box::new(If {
+            cond,
+            if_true: self.compstmt(if_true),
+            else_l: if_false.as_ref().map(|_| self.loc()),
+            if_false,
+            keyword_l: self.loc(),
+            begin_l: self.loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `cond ? if_true : if_false`
+    pub fn ternary(&self, cond: Node, if_true: Node, if_false: Node) -> Node {
+        Node::IfTernary(Box::new(IfTernary {
+            cond,
+            if_true,
+            if_false,
+            question_l: self.loc(),
+            colon_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `while cond; body; end`
+    pub fn while_(&self, cond: Node, body: Vec<Node>) -> Node {
+        Node::While(Box::new(While {
+            cond,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `until cond; body; end`
+    pub fn until(&self, cond: Node, body: Vec<Node>) -> Node {
+        Node::Until(Box::new(Until {
+            cond,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `case expr; when_bodies; else; else_body; end`
+    pub fn case(&self, expr: Option<Node>, when_bodies: Vec<Node>, else_body: Vec<Node>) -> Node {
+        let else_body = self.compstmt(else_body);
+        Node::Case(Box::new(Case {
+            expr,
+            when_bodies,
+            else_l: else_body.as_ref().map(|_| self.loc()),
+            else_body,
+            keyword_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `when patterns; body`
+    pub fn when(&self, patterns: Vec<Node>, body: Vec<Node>) -> Node {
+        Node::When(Box::new(When {
+            patterns,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            begin_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `return args`
+    pub fn return_(&self, args: Vec<Node>) -> Node {
+        Node::Return(Box::new(Return {
+            args,
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `break args`
+    pub fn break_(&self, args: Vec<Node>) -> Node {
+        Node::Break(Box::new(Break {
+            args,
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `next args`
+    pub fn next(&self, args: Vec<Node>) -> Node {
+        Node::Next(Box::new(Next {
+            args,
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `body` with `rescue` clauses (`rescue_body` nodes) and optional `else`
+    pub fn rescue(&self, body: Vec<Node>, rescue_bodies: Vec<Node>, else_: Vec<Node>) -> Node {
+        let else_ = self.compstmt(else_);
+        Node::Rescue(Box::new(Rescue {
+            body: self.compstmt(body),
+            rescue_bodies,
+            else_l: else_.as_ref().map(|_| self.loc()),
+            else_,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `rescue exc_list => exc_var; body`
+    pub fn rescue_body(&self, exc_list: Vec<Node>, exc_var: Option<Node>, body: Vec<Node>) -> Node {
+        let exc_list = if exc_list.is_empty() {
+            None
+        } else {
+            Some(Node::Array(Box::new(Array {
+                elements: exc_list,
+                begin_l: None,
+                end_l: None,
+                expression_l: self.loc(),
+            })))
+        };
+        Node::RescueBody(Box::new(RescueBody {
+            exc_list,
+            assoc_l: exc_var.as_ref().map(|_| self.loc()),
+            exc_var,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            begin_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `body` followed by `ensure` clause
+    pub fn ensure(&self, body: Vec<Node>, ensure: Vec<Node>) -> Node {
+        Node::Ensure(Box::new(Ensure {
+            body: self.compstmt(body),
+            ensure: self.compstmt(ensure),
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Definitions
+    //
+
+    /// `def name(args); body; end`
+    pub fn def(&self, name: &str, args: Vec<Node>, body: Vec<Node>) -> Node {
+        Node::Def(Box::new(Def {
+            name: name.to_owned(),
+            args: self.maybe_args(args),
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            name_l: self.loc(),
+            assignment_l: None,
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `def definee.name(args); body; end`
+    pub fn defs(&self, definee: Node, name: &str, args: Vec<Node>, body: Vec<Node>) -> Node {
+        Node::Defs(Box::new(Defs {
+            definee,
+            name: name.to_owned(),
+            args: self.maybe_args(args),
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            operator_l: self.loc(),
+            name_l: self.loc(),
+            assignment_l: None,
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `class name < superclass; body; end`
+    pub fn class(&self, name: Node, superclass: Option<Node>, body: Vec<Node>) -> Node {
+        Node::Class(Box::new(Class {
+            name,
+            operator_l: superclass.as_ref().map(|_| self.loc()),
+            superclass,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `module name; body; end`
+    pub fn module(&self, name: Node, body: Vec<Node>) -> Node {
+        Node::Module(Box::new(Module {
+            name,
+            body: self.compstmt(body),
+            keyword_l: self.loc(),
+            end_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `alias to from`
+    pub fn alias(&self, to: Node, from: Node) -> Node {
+        Node::Alias(Box::new(Alias {
+            to,
+            from,
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `undef names`
+    pub fn undef(&self, names: Vec<Node>) -> Node {
+        Node::Undef(Box::new(Undef {
+            names,
+            keyword_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Arguments
+    //
+
+    /// List of method or lambda arguments
+    pub fn args(&self, args: Vec<Node>) -> Node {
+        Node::Args(Box::new(Args {
+            args,
+            begin_l: self.maybe_loc(),
+            end_l: self.maybe_loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    fn maybe_args(&self, args: Vec<Node>) -> Option<Node> {
+        if args.is_empty() {
+            None
+        } else {
+            Some(self.args(args))
+        }
+    }
+
+    /// `name`
+    pub fn arg(&self, name: &str) -> Node {
+        Node::Arg(Box::new(Arg {
+            name: name.to_owned(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `name = default`
+    pub fn optarg(&self, name: &str, default: Node) -> Node {
+        Node::Optarg(Box::new(Optarg {
+            name: name.to_owned(),
+            default,
+            name_l: self.loc(),
+            operator_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `*name`
+    pub fn restarg(&self, name: Option<&str>) -> Node {
+        Node::Restarg(Box::new(Restarg {
+            name: name.map(|name| name.to_owned()),
+            operator_l: self.loc(),
+            name_l: name.map(|_| self.loc()),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `name:`
+    pub fn kwarg(&self, name: &str) -> Node {
+        Node::Kwarg(Box::new(Kwarg {
+            name: name.to_owned(),
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `name: default`
+    pub fn kwoptarg(&self, name: &str, default: Node) -> Node {
+        Node::Kwoptarg(Box::new(Kwoptarg {
+            name: name.to_owned(),
+            default,
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `**name`
+    pub fn kwrestarg(&self, name: Option<&str>) -> Node {
+        Node::Kwrestarg(Box::new(Kwrestarg {
+            name: name.map(|name| name.to_owned()),
+            operator_l: self.loc(),
+            name_l: name.map(|_| self.loc()),
+            expression_l: self.loc(),
+        }))
+    }
+
+    /// `&name`
+    pub fn blockarg(&self, name: &str) -> Node {
+        Node::Blockarg(Box::new(Blockarg {
+            name: name.to_owned(),
+            operator_l: self.loc(),
+            name_l: self.loc(),
+            expression_l: self.loc(),
+        }))
+    }
+
+    //
+    // Helpers
+    //
+
+    // Part of `dstr` (without quotes)
+    fn str_part(&self, value: &str) -> Node {
+        Node::Str(Box::new(Str {
+            value: StringValue::from(value),
+            begin_l: None,
+            end_l: None,
+            expression_l: self.loc(),
+        }))
+    }
+
+    fn interpolated_parts(&self, parts: Vec<Node>) -> Vec<Node> {
+        parts
+            .into_iter()
+            .map(|part| match part {
+                Node::Str(mut inner) => {
+                    inner.begin_l = None;
+                    inner.end_l = None;
+                    Node::Str(inner)
+                }
+                // `#{...}`
+                Node::Begin(inner) if inner.begin_l.is_some() => Node::Begin(inner),
+                part => self.parens(vec![part]),
+            })
+            .collect()
+    }
+}
@@ -21,6 +21,11 @@ pub trait InnerNode {
     }
 
     fn print_with_locs(&self);
+
+    fn for_each_loc_mut(&mut self, f: &mut dyn FnMut(&mut Range));
+
+    /// Returns direct children in the order of `Visitor` traversal
+    fn children(&self) -> Vec<&Node>;
 }
 
 pub(crate) struct InspectVec {
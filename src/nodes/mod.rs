@@ -2,6 +2,11 @@ mod node;
 pub use node::Node;
 mod inner_node;
 pub(crate) use inner_node::{InnerNode, InspectVec};
+mod structural;
+pub(crate) use structural::{diff_maybe_node, diff_node, diff_nodes, diff_value, Structural};
 
 mod types;
 pub use types::*;
+
+mod factory;
+pub use factory::NodeFactory;
@@ -1,13 +1,18 @@
 use crate::nodes::InnerNode;
 use crate::nodes::*;
 use crate::source::Range;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
 
 /// Generic combination of all known nodes.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
 pub enum Node {
     Alias(Box<Alias>),
     And(Box<And>),
     AndAsgn(Box<AndAsgn>),
+    AnonymousBlockarg(Box<AnonymousBlockarg>),
     Arg(Box<Arg>),
     Args(Box<Args>),
     Array(Box<Array>),
@@ -40,6 +45,7 @@ pub enum Node {
     Encoding(Box<Encoding>),
     Ensure(Box<Ensure>),
     Erange(Box<Erange>),
+    Error(Box<Error>),
     False(Box<False>),
     File(Box<File>),
     FindPattern(Box<FindPattern>),
@@ -47,6 +53,9 @@ pub enum Node {
     For(Box<For>),
     ForwardArg(Box<ForwardArg>),
     ForwardedArgs(Box<ForwardedArgs>),
+    ForwardedBlockarg(Box<ForwardedBlockarg>),
+    ForwardedKwrestarg(Box<ForwardedKwrestarg>),
+    ForwardedRestarg(Box<ForwardedRestarg>),
     Gvar(Box<Gvar>),
     Gvasgn(Box<Gvasgn>),
     Hash(Box<Hash>),
@@ -63,6 +72,7 @@ pub enum Node {
     InPattern(Box<InPattern>),
     Int(Box<Int>),
     Irange(Box<Irange>),
+    Itblock(Box<Itblock>),
     Ivar(Box<Ivar>),
     Ivasgn(Box<Ivasgn>),
     Kwarg(Box<Kwarg>),
@@ -80,6 +90,7 @@ pub enum Node {
     MatchAs(Box<MatchAs>),
     MatchCurrentLine(Box<MatchCurrentLine>),
     MatchNilPattern(Box<MatchNilPattern>),
+    MatchPattern(Box<MatchPattern>),
     MatchRest(Box<MatchRest>),
     MatchVar(Box<MatchVar>),
     MatchWithLvasgn(Box<MatchWithLvasgn>),
@@ -135,6 +146,7 @@ impl Node {
             Node::Alias(inner) => inner.as_ref(),
             Node::And(inner) => inner.as_ref(),
             Node::AndAsgn(inner) => inner.as_ref(),
+            Node::AnonymousBlockarg(inner) => inner.as_ref(),
             Node::Arg(inner) => inner.as_ref(),
             Node::Args(inner) => inner.as_ref(),
             Node::Array(inner) => inner.as_ref(),
@@ -167,6 +179,7 @@ impl Node {
             Node::Encoding(inner) => inner.as_ref(),
             Node::Ensure(inner) => inner.as_ref(),
             Node::Erange(inner) => inner.as_ref(),
+            Node::Error(inner) => inner.as_ref(),
             Node::False(inner) => inner.as_ref(),
             Node::File(inner) => inner.as_ref(),
             Node::FindPattern(inner) => inner.as_ref(),
@@ -174,6 +187,9 @@ impl Node {
             Node::For(inner) => inner.as_ref(),
             Node::ForwardArg(inner) => inner.as_ref(),
             Node::ForwardedArgs(inner) => inner.as_ref(),
+            Node::ForwardedBlockarg(inner) => inner.as_ref(),
+            Node::ForwardedKwrestarg(inner) => inner.as_ref(),
+            Node::ForwardedRestarg(inner) => inner.as_ref(),
             Node::Gvar(inner) => inner.as_ref(),
             Node::Gvasgn(inner) => inner.as_ref(),
             Node::Hash(inner) => inner.as_ref(),
@@ -190,6 +206,7 @@ impl Node {
             Node::InPattern(inner) => inner.as_ref(),
             Node::Int(inner) => inner.as_ref(),
             Node::Irange(inner) => inner.as_ref(),
+            Node::Itblock(inner) => inner.as_ref(),
             Node::Ivar(inner) => inner.as_ref(),
             Node::Ivasgn(inner) => inner.as_ref(),
             Node::Kwarg(inner) => inner.as_ref(),
@@ -207,6 +224,7 @@ impl Node {
             Node::MatchAs(inner) => inner.as_ref(),
             Node::MatchCurrentLine(inner) => inner.as_ref(),
             Node::MatchNilPattern(inner) => inner.as_ref(),
+            Node::MatchPattern(inner) => inner.as_ref(),
             Node::MatchRest(inner) => inner.as_ref(),
             Node::MatchVar(inner) => inner.as_ref(),
             Node::MatchWithLvasgn(inner) => inner.as_ref(),
@@ -257,6 +275,140 @@ impl Node {
         }
     }
 
+    pub(crate) fn inner_mut(&mut self) -> &mut dyn InnerNode {
+        match self {
+            Node::Alias(inner) => inner.as_mut(),
+            Node::And(inner) => inner.as_mut(),
+            Node::AndAsgn(inner) => inner.as_mut(),
+            Node::AnonymousBlockarg(inner) => inner.as_mut(),
+            Node::Arg(inner) => inner.as_mut(),
+            Node::Args(inner) => inner.as_mut(),
+            Node::Array(inner) => inner.as_mut(),
+            Node::ArrayPattern(inner) => inner.as_mut(),
+            Node::ArrayPatternWithTail(inner) => inner.as_mut(),
+            Node::BackRef(inner) => inner.as_mut(),
+            Node::Begin(inner) => inner.as_mut(),
+            Node::Block(inner) => inner.as_mut(),
+            Node::Blockarg(inner) => inner.as_mut(),
+            Node::BlockPass(inner) => inner.as_mut(),
+            Node::Break(inner) => inner.as_mut(),
+            Node::Case(inner) => inner.as_mut(),
+            Node::CaseMatch(inner) => inner.as_mut(),
+            Node::Casgn(inner) => inner.as_mut(),
+            Node::Cbase(inner) => inner.as_mut(),
+            Node::Class(inner) => inner.as_mut(),
+            Node::Complex(inner) => inner.as_mut(),
+            Node::Const(inner) => inner.as_mut(),
+            Node::ConstPattern(inner) => inner.as_mut(),
+            Node::CSend(inner) => inner.as_mut(),
+            Node::Cvar(inner) => inner.as_mut(),
+            Node::Cvasgn(inner) => inner.as_mut(),
+            Node::Def(inner) => inner.as_mut(),
+            Node::Defined(inner) => inner.as_mut(),
+            Node::Defs(inner) => inner.as_mut(),
+            Node::Dstr(inner) => inner.as_mut(),
+            Node::Dsym(inner) => inner.as_mut(),
+            Node::EFlipFlop(inner) => inner.as_mut(),
+            Node::EmptyElse(inner) => inner.as_mut(),
+            Node::Encoding(inner) => inner.as_mut(),
+            Node::Ensure(inner) => inner.as_mut(),
+            Node::Erange(inner) => inner.as_mut(),
+            Node::Error(inner) => inner.as_mut(),
+            Node::False(inner) => inner.as_mut(),
+            Node::File(inner) => inner.as_mut(),
+            Node::FindPattern(inner) => inner.as_mut(),
+            Node::Float(inner) => inner.as_mut(),
+            Node::For(inner) => inner.as_mut(),
+            Node::ForwardArg(inner) => inner.as_mut(),
+            Node::ForwardedArgs(inner) => inner.as_mut(),
+            Node::ForwardedBlockarg(inner) => inner.as_mut(),
+            Node::ForwardedKwrestarg(inner) => inner.as_mut(),
+            Node::ForwardedRestarg(inner) => inner.as_mut(),
+            Node::Gvar(inner) => inner.as_mut(),
+            Node::Gvasgn(inner) => inner.as_mut(),
+            Node::Hash(inner) => inner.as_mut(),
+            Node::HashPattern(inner) => inner.as_mut(),
+            Node::Heredoc(inner) => inner.as_mut(),
+            Node::If(inner) => inner.as_mut(),
+            Node::IfGuard(inner) => inner.as_mut(),
+            Node::IFlipFlop(inner) => inner.as_mut(),
+            Node::IfMod(inner) => inner.as_mut(),
+            Node::IfTernary(inner) => inner.as_mut(),
+            Node::Index(inner) => inner.as_mut(),
+            Node::IndexAsgn(inner) => inner.as_mut(),
+            Node::InMatch(inner) => inner.as_mut(),
+            Node::InPattern(inner) => inner.as_mut(),
+            Node::Int(inner) => inner.as_mut(),
+            Node::Irange(inner) => inner.as_mut(),
+            Node::Itblock(inner) => inner.as_mut(),
+            Node::Ivar(inner) => inner.as_mut(),
+            Node::Ivasgn(inner) => inner.as_mut(),
+            Node::Kwarg(inner) => inner.as_mut(),
+            Node::KwBegin(inner) => inner.as_mut(),
+            Node::Kwnilarg(inner) => inner.as_mut(),
+            Node::Kwoptarg(inner) => inner.as_mut(),
+            Node::Kwrestarg(inner) => inner.as_mut(),
+            Node::Kwsplat(inner) => inner.as_mut(),
+            Node::Lambda(inner) => inner.as_mut(),
+            Node::Line(inner) => inner.as_mut(),
+            Node::Lvar(inner) => inner.as_mut(),
+            Node::Lvasgn(inner) => inner.as_mut(),
+            Node::Masgn(inner) => inner.as_mut(),
+            Node::MatchAlt(inner) => inner.as_mut(),
+            Node::MatchAs(inner) => inner.as_mut(),
+            Node::MatchCurrentLine(inner) => inner.as_mut(),
+            Node::MatchNilPattern(inner) => inner.as_mut(),
+            Node::MatchPattern(inner) => inner.as_mut(),
+            Node::MatchRest(inner) => inner.as_mut(),
+            Node::MatchVar(inner) => inner.as_mut(),
+            Node::MatchWithLvasgn(inner) => inner.as_mut(),
+            Node::Mlhs(inner) => inner.as_mut(),
+            Node::Module(inner) => inner.as_mut(),
+            Node::Next(inner) => inner.as_mut(),
+            Node::Nil(inner) => inner.as_mut(),
+            Node::NthRef(inner) => inner.as_mut(),
+            Node::Numblock(inner) => inner.as_mut(),
+            Node::OpAsgn(inner) => inner.as_mut(),
+            Node::Optarg(inner) => inner.as_mut(),
+            Node::Or(inner) => inner.as_mut(),
+            Node::OrAsgn(inner) => inner.as_mut(),
+            Node::Pair(inner) => inner.as_mut(),
+            Node::Pin(inner) => inner.as_mut(),
+            Node::Postexe(inner) => inner.as_mut(),
+            Node::Preexe(inner) => inner.as_mut(),
+            Node::Procarg0(inner) => inner.as_mut(),
+            Node::Rational(inner) => inner.as_mut(),
+            Node::Redo(inner) => inner.as_mut(),
+            Node::Regexp(inner) => inner.as_mut(),
+            Node::RegOpt(inner) => inner.as_mut(),
+            Node::Rescue(inner) => inner.as_mut(),
+            Node::RescueBody(inner) => inner.as_mut(),
+            Node::Restarg(inner) => inner.as_mut(),
+            Node::Retry(inner) => inner.as_mut(),
+            Node::Return(inner) => inner.as_mut(),
+            Node::SClass(inner) => inner.as_mut(),
+            Node::Self_(inner) => inner.as_mut(),
+            Node::Send(inner) => inner.as_mut(),
+            Node::Shadowarg(inner) => inner.as_mut(),
+            Node::Splat(inner) => inner.as_mut(),
+            Node::Str(inner) => inner.as_mut(),
+            Node::Super(inner) => inner.as_mut(),
+            Node::Sym(inner) => inner.as_mut(),
+            Node::True(inner) => inner.as_mut(),
+            Node::Undef(inner) => inner.as_mut(),
+            Node::UnlessGuard(inner) => inner.as_mut(),
+            Node::Until(inner) => inner.as_mut(),
+            Node::UntilPost(inner) => inner.as_mut(),
+            Node::When(inner) => inner.as_mut(),
+            Node::While(inner) => inner.as_mut(),
+            Node::WhilePost(inner) => inner.as_mut(),
+            Node::XHeredoc(inner) => inner.as_mut(),
+            Node::Xstr(inner) => inner.as_mut(),
+            Node::Yield(inner) => inner.as_mut(),
+            Node::ZSuper(inner) => inner.as_mut(),
+        }
+    }
+
     /// Returs a whitequark/parser -like representation of `self`.
     ///
     /// Used in tests and example scripts
@@ -269,6 +421,11 @@ impl Node {
         self.inner_ref().expression()
     }
 
+    /// Returns true if the node was built by `NodeFactory` and has no source location
+    pub fn is_synthetic(&self) -> bool {
+        self.expression().is_synthetic()
+    }
+
     /// Returns a whitequark/parser -like node name.
     ///
     /// Used in tests and example scripts
@@ -280,4 +437,451 @@ impl Node {
     pub fn print_with_locs(&self) {
         self.inner_ref().print_with_locs()
     }
+
+    /// Returns true if `self` and `other` are the same trees, locations are ignored
+    pub fn structurally_eq(&self, other: &Node) -> bool {
+        match (self, other) {
+            (Node::Alias(lhs), Node::Alias(rhs)) => lhs.structurally_eq(rhs),
+            (Node::And(lhs), Node::And(rhs)) => lhs.structurally_eq(rhs),
+            (Node::AndAsgn(lhs), Node::AndAsgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::AnonymousBlockarg(lhs), Node::AnonymousBlockarg(rhs)) => {
+                lhs.structurally_eq(rhs)
+            }
+            (Node::Arg(lhs), Node::Arg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Args(lhs), Node::Args(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Array(lhs), Node::Array(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ArrayPattern(lhs), Node::ArrayPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ArrayPatternWithTail(lhs), Node::ArrayPatternWithTail(rhs)) => {
+                lhs.structurally_eq(rhs)
+            }
+            (Node::BackRef(lhs), Node::BackRef(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Begin(lhs), Node::Begin(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Block(lhs), Node::Block(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Blockarg(lhs), Node::Blockarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::BlockPass(lhs), Node::BlockPass(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Break(lhs), Node::Break(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Case(lhs), Node::Case(rhs)) => lhs.structurally_eq(rhs),
+            (Node::CaseMatch(lhs), Node::CaseMatch(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Casgn(lhs), Node::Casgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Cbase(lhs), Node::Cbase(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Class(lhs), Node::Class(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Complex(lhs), Node::Complex(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Const(lhs), Node::Const(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ConstPattern(lhs), Node::ConstPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::CSend(lhs), Node::CSend(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Cvar(lhs), Node::Cvar(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Cvasgn(lhs), Node::Cvasgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Def(lhs), Node::Def(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Defined(lhs), Node::Defined(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Defs(lhs), Node::Defs(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Dstr(lhs), Node::Dstr(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Dsym(lhs), Node::Dsym(rhs)) => lhs.structurally_eq(rhs),
+            (Node::EFlipFlop(lhs), Node::EFlipFlop(rhs)) => lhs.structurally_eq(rhs),
+            (Node::EmptyElse(lhs), Node::EmptyElse(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Encoding(lhs), Node::Encoding(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Ensure(lhs), Node::Ensure(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Erange(lhs), Node::Erange(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Error(lhs), Node::Error(rhs)) => lhs.structurally_eq(rhs),
+            (Node::False(lhs), Node::False(rhs)) => lhs.structurally_eq(rhs),
+            (Node::File(lhs), Node::File(rhs)) => lhs.structurally_eq(rhs),
+            (Node::FindPattern(lhs), Node::FindPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Float(lhs), Node::Float(rhs)) => lhs.structurally_eq(rhs),
+            (Node::For(lhs), Node::For(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ForwardArg(lhs), Node::ForwardArg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ForwardedArgs(lhs), Node::ForwardedArgs(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ForwardedBlockarg(lhs), Node::ForwardedBlockarg(rhs)) => {
+                lhs.structurally_eq(rhs)
+            }
+            (Node::ForwardedKwrestarg(lhs), Node::ForwardedKwrestarg(rhs)) => {
+                lhs.structurally_eq(rhs)
+            }
+            (Node::ForwardedRestarg(lhs), Node::ForwardedRestarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Gvar(lhs), Node::Gvar(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Gvasgn(lhs), Node::Gvasgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Hash(lhs), Node::Hash(rhs)) => lhs.structurally_eq(rhs),
+            (Node::HashPattern(lhs), Node::HashPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Heredoc(lhs), Node::Heredoc(rhs)) => lhs.structurally_eq(rhs),
+            (Node::If(lhs), Node::If(rhs)) => lhs.structurally_eq(rhs),
+            (Node::IfGuard(lhs), Node::IfGuard(rhs)) => lhs.structurally_eq(rhs),
+            (Node::IFlipFlop(lhs), Node::IFlipFlop(rhs)) => lhs.structurally_eq(rhs),
+            (Node::IfMod(lhs), Node::IfMod(rhs)) => lhs.structurally_eq(rhs),
+            (Node::IfTernary(lhs), Node::IfTernary(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Index(lhs), Node::Index(rhs)) => lhs.structurally_eq(rhs),
+            (Node::IndexAsgn(lhs), Node::IndexAsgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::InMatch(lhs), Node::InMatch(rhs)) => lhs.structurally_eq(rhs),
+            (Node::InPattern(lhs), Node::InPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Int(lhs), Node::Int(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Irange(lhs), Node::Irange(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Itblock(lhs), Node::Itblock(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Ivar(lhs), Node::Ivar(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Ivasgn(lhs), Node::Ivasgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Kwarg(lhs), Node::Kwarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::KwBegin(lhs), Node::KwBegin(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Kwnilarg(lhs), Node::Kwnilarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Kwoptarg(lhs), Node::Kwoptarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Kwrestarg(lhs), Node::Kwrestarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Kwsplat(lhs), Node::Kwsplat(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Lambda(lhs), Node::Lambda(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Line(lhs), Node::Line(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Lvar(lhs), Node::Lvar(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Lvasgn(lhs), Node::Lvasgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Masgn(lhs), Node::Masgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchAlt(lhs), Node::MatchAlt(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchAs(lhs), Node::MatchAs(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchCurrentLine(lhs), Node::MatchCurrentLine(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchNilPattern(lhs), Node::MatchNilPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchPattern(lhs), Node::MatchPattern(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchRest(lhs), Node::MatchRest(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchVar(lhs), Node::MatchVar(rhs)) => lhs.structurally_eq(rhs),
+            (Node::MatchWithLvasgn(lhs), Node::MatchWithLvasgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Mlhs(lhs), Node::Mlhs(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Module(lhs), Node::Module(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Next(lhs), Node::Next(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Nil(lhs), Node::Nil(rhs)) => lhs.structurally_eq(rhs),
+            (Node::NthRef(lhs), Node::NthRef(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Numblock(lhs), Node::Numblock(rhs)) => lhs.structurally_eq(rhs),
+            (Node::OpAsgn(lhs), Node::OpAsgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Optarg(lhs), Node::Optarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Or(lhs), Node::Or(rhs)) => lhs.structurally_eq(rhs),
+            (Node::OrAsgn(lhs), Node::OrAsgn(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Pair(lhs), Node::Pair(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Pin(lhs), Node::Pin(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Postexe(lhs), Node::Postexe(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Preexe(lhs), Node::Preexe(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Procarg0(lhs), Node::Procarg0(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Rational(lhs), Node::Rational(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Redo(lhs), Node::Redo(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Regexp(lhs), Node::Regexp(rhs)) => lhs.structurally_eq(rhs),
+            (Node::RegOpt(lhs), Node::RegOpt(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Rescue(lhs), Node::Rescue(rhs)) => lhs.structurally_eq(rhs),
+            (Node::RescueBody(lhs), Node::RescueBody(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Restarg(lhs), Node::Restarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Retry(lhs), Node::Retry(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Return(lhs), Node::Return(rhs)) => lhs.structurally_eq(rhs),
+            (Node::SClass(lhs), Node::SClass(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Self_(lhs), Node::Self_(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Send(lhs), Node::Send(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Shadowarg(lhs), Node::Shadowarg(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Splat(lhs), Node::Splat(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Str(lhs), Node::Str(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Super(lhs), Node::Super(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Sym(lhs), Node::Sym(rhs)) => lhs.structurally_eq(rhs),
+            (Node::True(lhs), Node::True(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Undef(lhs), Node::Undef(rhs)) => lhs.structurally_eq(rhs),
+            (Node::UnlessGuard(lhs), Node::UnlessGuard(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Until(lhs), Node::Until(rhs)) => lhs.structurally_eq(rhs),
+            (Node::UntilPost(lhs), Node::UntilPost(rhs)) => lhs.structurally_eq(rhs),
+            (Node::When(lhs), Node::When(rhs)) => lhs.structurally_eq(rhs),
+            (Node::While(lhs), Node::While(rhs)) => lhs.structurally_eq(rhs),
+            (Node::WhilePost(lhs), Node::WhilePost(rhs)) => lhs.structurally_eq(rhs),
+            (Node::XHeredoc(lhs), Node::XHeredoc(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Xstr(lhs), Node::Xstr(rhs)) => lhs.structurally_eq(rhs),
+            (Node::Yield(lhs), Node::Yield(rhs)) => lhs.structurally_eq(rhs),
+            (Node::ZSuper(lhs), Node::ZSuper(rhs)) => lhs.structurally_eq(rhs),
+            _ => false,
+        }
+    }
+
+    /// Returns a hash of the tree that ignores locations,
+    /// structurally equal trees have equal hashes
+    pub fn structural_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash_structure(&mut hasher);
+        hasher.finish()
+    }
+
+    pub(crate) fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        state.write(self.str_type().as_bytes());
+        match self {
+            Node::Alias(inner) => inner.hash_structure(state),
+            Node::And(inner) => inner.hash_structure(state),
+            Node::AndAsgn(inner) => inner.hash_structure(state),
+            Node::AnonymousBlockarg(inner) => inner.hash_structure(state),
+            Node::Arg(inner) => inner.hash_structure(state),
+            Node::Args(inner) => inner.hash_structure(state),
+            Node::Array(inner) => inner.hash_structure(state),
+            Node::ArrayPattern(inner) => inner.hash_structure(state),
+            Node::ArrayPatternWithTail(inner) => inner.hash_structure(state),
+            Node::BackRef(inner) => inner.hash_structure(state),
+            Node::Begin(inner) => inner.hash_structure(state),
+            Node::Block(inner) => inner.hash_structure(state),
+            Node::Blockarg(inner) => inner.hash_structure(state),
+            Node::BlockPass(inner) => inner.hash_structure(state),
+            Node::Break(inner) => inner.hash_structure(state),
+            Node::Case(inner) => inner.hash_structure(state),
+            Node::CaseMatch(inner) => inner.hash_structure(state),
+            Node::Casgn(inner) => inner.hash_structure(state),
+            Node::Cbase(inner) => inner.hash_structure(state),
+            Node::Class(inner) => inner.hash_structure(state),
+            Node::Complex(inner) => inner.hash_structure(state),
+            Node::Const(inner) => inner.hash_structure(state),
+            Node::ConstPattern(inner) => inner.hash_structure(state),
+            Node::CSend(inner) => inner.hash_structure(state),
+            Node::Cvar(inner) => inner.hash_structure(state),
+            Node::Cvasgn(inner) => inner.hash_structure(state),
+            Node::Def(inner) => inner.hash_structure(state),
+            Node::Defined(inner) => inner.hash_structure(state),
+            Node::Defs(inner) => inner.hash_structure(state),
+            Node::Dstr(inner) => inner.hash_structure(state),
+            Node::Dsym(inner) => inner.hash_structure(state),
+            Node::EFlipFlop(inner) => inner.hash_structure(state),
+            Node::EmptyElse(inner) => inner.hash_structure(state),
+            Node::Encoding(inner) => inner.hash_structure(state),
+            Node::Ensure(inner) => inner.hash_structure(state),
+            Node::Erange(inner) => inner.hash_structure(state),
+            Node::Error(inner) => inner.hash_structure(state),
+            Node::False(inner) => inner.hash_structure(state),
+            Node::File(inner) => inner.hash_structure(state),
+            Node::FindPattern(inner) => inner.hash_structure(state),
+            Node::Float(inner) => inner.hash_structure(state),
+            Node::For(inner) => inner.hash_structure(state),
+            Node::ForwardArg(inner) => inner.hash_structure(state),
+            Node::ForwardedArgs(inner) => inner.hash_structure(state),
+            Node::ForwardedBlockarg(inner) => inner.hash_structure(state),
+            Node::ForwardedKwrestarg(inner) => inner.hash_structure(state),
+            Node::ForwardedRestarg(inner) => inner.hash_structure(state),
+            Node::Gvar(inner) => inner.hash_structure(state),
+            Node::Gvasgn(inner) => inner.hash_structure(state),
+            Node::Hash(inner) => inner.hash_structure(state),
+            Node::HashPattern(inner) => inner.hash_structure(state),
+            Node::Heredoc(inner) => inner.hash_structure(state),
+            Node::If(inner) => inner.hash_structure(state),
+            Node::IfGuard(inner) => inner.hash_structure(state),
+            Node::IFlipFlop(inner) => inner.hash_structure(state),
+            Node::IfMod(inner) => inner.hash_structure(state),
+            Node::IfTernary(inner) => inner.hash_structure(state),
+            Node::Index(inner) => inner.hash_structure(state),
+            Node::IndexAsgn(inner) => inner.hash_structure(state),
+            Node::InMatch(inner) => inner.hash_structure(state),
+            Node::InPattern(inner) => inner.hash_structure(state),
+            Node::Int(inner) => inner.hash_structure(state),
+            Node::Irange(inner) => inner.hash_structure(state),
+            Node::Itblock(inner) => inner.hash_structure(state),
+            Node::Ivar(inner) => inner.hash_structure(state),
+            Node::Ivasgn(inner) => inner.hash_structure(state),
+            Node::Kwarg(inner) => inner.hash_structure(state),
+            Node::KwBegin(inner) => inner.hash_structure(state),
+            Node::Kwnilarg(inner) => inner.hash_structure(state),
+            Node::Kwoptarg(inner) => inner.hash_structure(state),
+            Node::Kwrestarg(inner) => inner.hash_structure(state),
+            Node::Kwsplat(inner) => inner.hash_structure(state),
+            Node::Lambda(inner) => inner.hash_structure(state),
+            Node::Line(inner) => inner.hash_structure(state),
+            Node::Lvar(inner) => inner.hash_structure(state),
+            Node::Lvasgn(inner) => inner.hash_structure(state),
+            Node::Masgn(inner) => inner.hash_structure(state),
+            Node::MatchAlt(inner) => inner.hash_structure(state),
+            Node::MatchAs(inner) => inner.hash_structure(state),
+            Node::MatchCurrentLine(inner) => inner.hash_structure(state),
+            Node::MatchNilPattern(inner) => inner.hash_structure(state),
+            Node::MatchPattern(inner) => inner.hash_structure(state),
+            Node::MatchRest(inner) => inner.hash_structure(state),
+            Node::MatchVar(inner) => inner.hash_structure(state),
+            Node::MatchWithLvasgn(inner) => inner.hash_structure(state),
+            Node::Mlhs(inner) => inner.hash_structure(state),
+            Node::Module(inner) => inner.hash_structure(state),
+            Node::Next(inner) => inner.hash_structure(state),
+            Node::Nil(inner) => inner.hash_structure(state),
+            Node::NthRef(inner) => inner.hash_structure(state),
+            Node::Numblock(inner) => inner.hash_structure(state),
+            Node::OpAsgn(inner) => inner.hash_structure(state),
+            Node::Optarg(inner) => inner.hash_structure(state),
+            Node::Or(inner) => inner.hash_structure(state),
+            Node::OrAsgn(inner) => inner.hash_structure(state),
+            Node::Pair(inner) => inner.hash_structure(state),
+            Node::Pin(inner) => inner.hash_structure(state),
+            Node::Postexe(inner) => inner.hash_structure(state),
+            Node::Preexe(inner) => inner.hash_structure(state),
+            Node::Procarg0(inner) => inner.hash_structure(state),
+            Node::Rational(inner) => inner.hash_structure(state),
+            Node::Redo(inner) => inner.hash_structure(state),
+            Node::Regexp(inner) => inner.hash_structure(state),
+            Node::RegOpt(inner) => inner.hash_structure(state),
+            Node::Rescue(inner) => inner.hash_structure(state),
+            Node::RescueBody(inner) => inner.hash_structure(state),
+            Node::Restarg(inner) => inner.hash_structure(state),
+            Node::Retry(inner) => inner.hash_structure(state),
+            Node::Return(inner) => inner.hash_structure(state),
+            Node::SClass(inner) => inner.hash_structure(state),
+            Node::Self_(inner) => inner.hash_structure(state),
+            Node::Send(inner) => inner.hash_structure(state),
+            Node::Shadowarg(inner) => inner.hash_structure(state),
+            Node::Splat(inner) => inner.hash_structure(state),
+            Node::Str(inner) => inner.hash_structure(state),
+            Node::Super(inner) => inner.hash_structure(state),
+            Node::Sym(inner) => inner.hash_structure(state),
+            Node::True(inner) => inner.hash_structure(state),
+            Node::Undef(inner) => inner.hash_structure(state),
+            Node::UnlessGuard(inner) => inner.hash_structure(state),
+            Node::Until(inner) => inner.hash_structure(state),
+            Node::UntilPost(inner) => inner.hash_structure(state),
+            Node::When(inner) => inner.hash_structure(state),
+            Node::While(inner) => inner.hash_structure(state),
+            Node::WhilePost(inner) => inner.hash_structure(state),
+            Node::XHeredoc(inner) => inner.hash_structure(state),
+            Node::Xstr(inner) => inner.hash_structure(state),
+            Node::Yield(inner) => inner.hash_structure(state),
+            Node::ZSuper(inner) => inner.hash_structure(state),
+        }
+    }
+
+    /// Returns path to the first node that differs structurally from `other`,
+    /// or `None` if trees are structurally equal.
+    ///
+    /// Path has the same format as patterns of `traverse::Find`
+    /// (like `["body", "stmt[1]", "arg[0]"]`) and points to the node
+    /// whose own values, children count or type differ
+    /// (an empty path means that roots differ).
+    pub fn structural_diff(&self, other: &Node) -> Option<Vec<String>> {
+        match (self, other) {
+            (Node::Alias(lhs), Node::Alias(rhs)) => lhs.structural_diff(rhs),
+            (Node::And(lhs), Node::And(rhs)) => lhs.structural_diff(rhs),
+            (Node::AndAsgn(lhs), Node::AndAsgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::AnonymousBlockarg(lhs), Node::AnonymousBlockarg(rhs)) => {
+                lhs.structural_diff(rhs)
+            }
+            (Node::Arg(lhs), Node::Arg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Args(lhs), Node::Args(rhs)) => lhs.structural_diff(rhs),
+            (Node::Array(lhs), Node::Array(rhs)) => lhs.structural_diff(rhs),
+            (Node::ArrayPattern(lhs), Node::ArrayPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::ArrayPatternWithTail(lhs), Node::ArrayPatternWithTail(rhs)) => {
+                lhs.structural_diff(rhs)
+            }
+            (Node::BackRef(lhs), Node::BackRef(rhs)) => lhs.structural_diff(rhs),
+            (Node::Begin(lhs), Node::Begin(rhs)) => lhs.structural_diff(rhs),
+            (Node::Block(lhs), Node::Block(rhs)) => lhs.structural_diff(rhs),
+            (Node::Blockarg(lhs), Node::Blockarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::BlockPass(lhs), Node::BlockPass(rhs)) => lhs.structural_diff(rhs),
+            (Node::Break(lhs), Node::Break(rhs)) => lhs.structural_diff(rhs),
+            (Node::Case(lhs), Node::Case(rhs)) => lhs.structural_diff(rhs),
+            (Node::CaseMatch(lhs), Node::CaseMatch(rhs)) => lhs.structural_diff(rhs),
+            (Node::Casgn(lhs), Node::Casgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Cbase(lhs), Node::Cbase(rhs)) => lhs.structural_diff(rhs),
+            (Node::Class(lhs), Node::Class(rhs)) => lhs.structural_diff(rhs),
+            (Node::Complex(lhs), Node::Complex(rhs)) => lhs.structural_diff(rhs),
+            (Node::Const(lhs), Node::Const(rhs)) => lhs.structural_diff(rhs),
+            (Node::ConstPattern(lhs), Node::ConstPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::CSend(lhs), Node::CSend(rhs)) => lhs.structural_diff(rhs),
+            (Node::Cvar(lhs), Node::Cvar(rhs)) => lhs.structural_diff(rhs),
+            (Node::Cvasgn(lhs), Node::Cvasgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Def(lhs), Node::Def(rhs)) => lhs.structural_diff(rhs),
+            (Node::Defined(lhs), Node::Defined(rhs)) => lhs.structural_diff(rhs),
+            (Node::Defs(lhs), Node::Defs(rhs)) => lhs.structural_diff(rhs),
+            (Node::Dstr(lhs), Node::Dstr(rhs)) => lhs.structural_diff(rhs),
+            (Node::Dsym(lhs), Node::Dsym(rhs)) => lhs.structural_diff(rhs),
+            (Node::EFlipFlop(lhs), Node::EFlipFlop(rhs)) => lhs.structural_diff(rhs),
+            (Node::EmptyElse(lhs), Node::EmptyElse(rhs)) => lhs.structural_diff(rhs),
+            (Node::Encoding(lhs), Node::Encoding(rhs)) => lhs.structural_diff(rhs),
+            (Node::Ensure(lhs), Node::Ensure(rhs)) => lhs.structural_diff(rhs),
+            (Node::Erange(lhs), Node::Erange(rhs)) => lhs.structural_diff(rhs),
+            (Node::Error(lhs), Node::Error(rhs)) => lhs.structural_diff(rhs),
+            (Node::False(lhs), Node::False(rhs)) => lhs.structural_diff(rhs),
+            (Node::File(lhs), Node::File(rhs)) => lhs.structural_diff(rhs),
+            (Node::FindPattern(lhs), Node::FindPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::Float(lhs), Node::Float(rhs)) => lhs.structural_diff(rhs),
+            (Node::For(lhs), Node::For(rhs)) => lhs.structural_diff(rhs),
+            (Node::ForwardArg(lhs), Node::ForwardArg(rhs)) => lhs.structural_diff(rhs),
+            (Node::ForwardedArgs(lhs), Node::ForwardedArgs(rhs)) => lhs.structural_diff(rhs),
+            (Node::ForwardedBlockarg(lhs), Node::ForwardedBlockarg(rhs)) => {
+                lhs.structural_diff(rhs)
+            }
+            (Node::ForwardedKwrestarg(lhs), Node::ForwardedKwrestarg(rhs)) => {
+                lhs.structural_diff(rhs)
+            }
+            (Node::ForwardedRestarg(lhs), Node::ForwardedRestarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Gvar(lhs), Node::Gvar(rhs)) => lhs.structural_diff(rhs),
+            (Node::Gvasgn(lhs), Node::Gvasgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Hash(lhs), Node::Hash(rhs)) => lhs.structural_diff(rhs),
+            (Node::HashPattern(lhs), Node::HashPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::Heredoc(lhs), Node::Heredoc(rhs)) => lhs.structural_diff(rhs),
+            (Node::If(lhs), Node::If(rhs)) => lhs.structural_diff(rhs),
+            (Node::IfGuard(lhs), Node::IfGuard(rhs)) => lhs.structural_diff(rhs),
+            (Node::IFlipFlop(lhs), Node::IFlipFlop(rhs)) => lhs.structural_diff(rhs),
+            (Node::IfMod(lhs), Node::IfMod(rhs)) => lhs.structural_diff(rhs),
+            (Node::IfTernary(lhs), Node::IfTernary(rhs)) => lhs.structural_diff(rhs),
+            (Node::Index(lhs), Node::Index(rhs)) => lhs.structural_diff(rhs),
+            (Node::IndexAsgn(lhs), Node::IndexAsgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::InMatch(lhs), Node::InMatch(rhs)) => lhs.structural_diff(rhs),
+            (Node::InPattern(lhs), Node::InPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::Int(lhs), Node::Int(rhs)) => lhs.structural_diff(rhs),
+            (Node::Irange(lhs), Node::Irange(rhs)) => lhs.structural_diff(rhs),
+            (Node::Itblock(lhs), Node::Itblock(rhs)) => lhs.structural_diff(rhs),
+            (Node::Ivar(lhs), Node::Ivar(rhs)) => lhs.structural_diff(rhs),
+            (Node::Ivasgn(lhs), Node::Ivasgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Kwarg(lhs), Node::Kwarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::KwBegin(lhs), Node::KwBegin(rhs)) => lhs.structural_diff(rhs),
+            (Node::Kwnilarg(lhs), Node::Kwnilarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Kwoptarg(lhs), Node::Kwoptarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Kwrestarg(lhs), Node::Kwrestarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Kwsplat(lhs), Node::Kwsplat(rhs)) => lhs.structural_diff(rhs),
+            (Node::Lambda(lhs), Node::Lambda(rhs)) => lhs.structural_diff(rhs),
+            (Node::Line(lhs), Node::Line(rhs)) => lhs.structural_diff(rhs),
+            (Node::Lvar(lhs), Node::Lvar(rhs)) => lhs.structural_diff(rhs),
+            (Node::Lvasgn(lhs), Node::Lvasgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Masgn(lhs), Node::Masgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchAlt(lhs), Node::MatchAlt(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchAs(lhs), Node::MatchAs(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchCurrentLine(lhs), Node::MatchCurrentLine(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchNilPattern(lhs), Node::MatchNilPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchPattern(lhs), Node::MatchPattern(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchRest(lhs), Node::MatchRest(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchVar(lhs), Node::MatchVar(rhs)) => lhs.structural_diff(rhs),
+            (Node::MatchWithLvasgn(lhs), Node::MatchWithLvasgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Mlhs(lhs), Node::Mlhs(rhs)) => lhs.structural_diff(rhs),
+            (Node::Module(lhs), Node::Module(rhs)) => lhs.structural_diff(rhs),
+            (Node::Next(lhs), Node::Next(rhs)) => lhs.structural_diff(rhs),
+            (Node::Nil(lhs), Node::Nil(rhs)) => lhs.structural_diff(rhs),
+            (Node::NthRef(lhs), Node::NthRef(rhs)) => lhs.structural_diff(rhs),
+            (Node::Numblock(lhs), Node::Numblock(rhs)) => lhs.structural_diff(rhs),
+            (Node::OpAsgn(lhs), Node::OpAsgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Optarg(lhs), Node::Optarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Or(lhs), Node::Or(rhs)) => lhs.structural_diff(rhs),
+            (Node::OrAsgn(lhs), Node::OrAsgn(rhs)) => lhs.structural_diff(rhs),
+            (Node::Pair(lhs), Node::Pair(rhs)) => lhs.structural_diff(rhs),
+            (Node::Pin(lhs), Node::Pin(rhs)) => lhs.structural_diff(rhs),
+            (Node::Postexe(lhs), Node::Postexe(rhs)) => lhs.structural_diff(rhs),
+            (Node::Preexe(lhs), Node::Preexe(rhs)) => lhs.structural_diff(rhs),
+            (Node::Procarg0(lhs), Node::Procarg0(rhs)) => lhs.structural_diff(rhs),
+            (Node::Rational(lhs), Node::Rational(rhs)) => lhs.structural_diff(rhs),
+            (Node::Redo(lhs), Node::Redo(rhs)) => lhs.structural_diff(rhs),
+            (Node::Regexp(lhs), Node::Regexp(rhs)) => lhs.structural_diff(rhs),
+            (Node::RegOpt(lhs), Node::RegOpt(rhs)) => lhs.structural_diff(rhs),
+            (Node::Rescue(lhs), Node::Rescue(rhs)) => lhs.structural_diff(rhs),
+            (Node::RescueBody(lhs), Node::RescueBody(rhs)) => lhs.structural_diff(rhs),
+            (Node::Restarg(lhs), Node::Restarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Retry(lhs), Node::Retry(rhs)) => lhs.structural_diff(rhs),
+            (Node::Return(lhs), Node::Return(rhs)) => lhs.structural_diff(rhs),
+            (Node::SClass(lhs), Node::SClass(rhs)) => lhs.structural_diff(rhs),
+            (Node::Self_(lhs), Node::Self_(rhs)) => lhs.structural_diff(rhs),
+            (Node::Send(lhs), Node::Send(rhs)) => lhs.structural_diff(rhs),
+            (Node::Shadowarg(lhs), Node::Shadowarg(rhs)) => lhs.structural_diff(rhs),
+            (Node::Splat(lhs), Node::Splat(rhs)) => lhs.structural_diff(rhs),
+            (Node::Str(lhs), Node::Str(rhs)) => lhs.structural_diff(rhs),
+            (Node::Super(lhs), Node::Super(rhs)) => lhs.structural_diff(rhs),
+            (Node::Sym(lhs), Node::Sym(rhs)) => lhs.structural_diff(rhs),
+            (Node::True(lhs), Node::True(rhs)) => lhs.structural_diff(rhs),
+            (Node::Undef(lhs), Node::Undef(rhs)) => lhs.structural_diff(rhs),
+            (Node::UnlessGuard(lhs), Node::UnlessGuard(rhs)) => lhs.structural_diff(rhs),
+            (Node::Until(lhs), Node::Until(rhs)) => lhs.structural_diff(rhs),
+            (Node::UntilPost(lhs), Node::UntilPost(rhs)) => lhs.structural_diff(rhs),
+            (Node::When(lhs), Node::When(rhs)) => lhs.structural_diff(rhs),
+            (Node::While(lhs), Node::While(rhs)) => lhs.structural_diff(rhs),
+            (Node::WhilePost(lhs), Node::WhilePost(rhs)) => lhs.structural_diff(rhs),
+            (Node::XHeredoc(lhs), Node::XHeredoc(rhs)) => lhs.structural_diff(rhs),
+            (Node::Xstr(lhs), Node::Xstr(rhs)) => lhs.structural_diff(rhs),
+            (Node::Yield(lhs), Node::Yield(rhs)) => lhs.structural_diff(rhs),
+            (Node::ZSuper(lhs), Node::ZSuper(rhs)) => lhs.structural_diff(rhs),
+            _ => Some(vec![]),
+        }
+    }
+
+    /// Calls `f` for every location of `self` and its children
+    pub(crate) fn for_each_loc_mut(&mut self, f: &mut dyn FnMut(&mut Range)) {
+        self.inner_mut().for_each_loc_mut(f)
+    }
+
+    /// Returns direct children of `self` in the order of `Visitor` traversal
+    pub(crate) fn children(&self) -> Vec<&Node> {
+        self.inner_ref().children()
+    }
 }
@@ -0,0 +1,130 @@
+use crate::Node;
+use crate::StringValue;
+use std::hash::{Hash, Hasher};
+
+/// Location-insensitive comparison and hashing of node fields.
+///
+/// Implemented for all non-location field types, node structs
+/// get `structurally_eq`, `hash_structure` and `structural_diff`
+/// methods generated on top of it.
+pub(crate) trait Structural {
+    fn structurally_eq(&self, other: &Self) -> bool;
+    fn hash_structure<H: Hasher>(&self, state: &mut H);
+}
+
+impl Structural for Node {
+    fn structurally_eq(&self, other: &Self) -> bool {
+        Node::structurally_eq(self, other)
+    }
+
+    fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        Node::hash_structure(self, state)
+    }
+}
+
+impl Structural for Option<Node> {
+    fn structurally_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(lhs), Some(rhs)) => lhs.structurally_eq(rhs),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Some(node) => {
+                state.write_u8(1);
+                node.hash_structure(state)
+            }
+            None => state.write_u8(0),
+        }
+    }
+}
+
+impl Structural for Vec<Node> {
+    fn structurally_eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|(lhs, rhs)| lhs.structurally_eq(rhs))
+    }
+
+    fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        for node in self.iter() {
+            node.hash_structure(state)
+        }
+    }
+}
+
+impl Structural for StringValue {
+    fn structurally_eq(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn hash_structure<H: Hasher>(&self, state: &mut H) {
+        self.valid.hash(state);
+        self.bytes.hash(state);
+    }
+}
+
+macro_rules! structural_by_value {
+    ($($t:ty),*) => {
+        $(
+            impl Structural for $t {
+                fn structurally_eq(&self, other: &Self) -> bool {
+                    self == other
+                }
+
+                fn hash_structure<H: Hasher>(&self, state: &mut H) {
+                    self.hash(state)
+                }
+            }
+        )*
+    };
+}
+
+structural_by_value!(String, Option<String>, Vec<char>, u8, usize);
+
+// Helpers for generated `structural_diff` methods,
+// all of them return `None` if there's no difference
+// or a `Find`-compatible path to the first differing node
+
+pub(crate) fn diff_value<T: Structural>(lhs: &T, rhs: &T) -> Option<Vec<String>> {
+    if lhs.structurally_eq(rhs) {
+        None
+    } else {
+        Some(vec![])
+    }
+}
+
+pub(crate) fn diff_node(lhs: &Node, rhs: &Node, item: &str) -> Option<Vec<String>> {
+    let mut path = lhs.structural_diff(rhs)?;
+    path.insert(0, item.to_owned());
+    Some(path)
+}
+
+pub(crate) fn diff_maybe_node(
+    lhs: &Option<Node>,
+    rhs: &Option<Node>,
+    item: &str,
+) -> Option<Vec<String>> {
+    match (lhs, rhs) {
+        (Some(lhs), Some(rhs)) => diff_node(lhs, rhs, item),
+        (None, None) => None,
+        // one of them is missing, so the difference is in the parent node
+        _ => Some(vec![]),
+    }
+}
+
+pub(crate) fn diff_nodes(lhs: &[Node], rhs: &[Node], item: &str) -> Option<Vec<String>> {
+    if lhs.len() != rhs.len() {
+        return Some(vec![]);
+    }
+    lhs.iter()
+        .zip(rhs.iter())
+        .enumerate()
+        .find_map(|(idx, (lhs, rhs))| diff_node(lhs, rhs, &format!("{}[{}]", item, idx)))
+}
@@ -19,11 +19,21 @@
     pattern_hash_keys: VariablesStack,
     tokens: Vec<Token>,
     diagnostics: Diagnostics,
+    error_recovery: bool,
+    has_syntax_errors: bool,
+    error_begin: usize,
+    suppressed_warnings: Vec<String>,
+    promoted_warnings: Vec<String>,
+    lossless_tokens: bool,
+    ruby_version: RubyVersion,
+    expected_tokens: bool,
 }
 
 %code use {
-    use crate::{ParserOptions, ParserResult};
+    use crate::{ParserOptions, ParserResult, RubyVersion};
     use crate::{Lexer, Builder, CurrentArgStack, StaticEnvironment, MaxNumparamStack, VariablesStack};
+    use crate::static_environment::AnonymousArg;
+    use crate::lexer::Trivia;
     use crate::lex_states::*;
     use crate::{Context as ParserContext, ContextItem};
     use crate::builder::{LoopType, KeywordCmd, LogicalOp, PKwLabel, ArgsType};
@@ -113,10 +123,10 @@
 %type <node> string_content
 %type <node> words symbols qwords qsymbols
 %type <node> literal numeric simple_numeric ssym dsym symbol cpath
-%type <node> top_compstmt top_stmt rassign
+%type <node> top_compstmt top_stmt
 %type <node> stmt_or_begin stmt expr arg primary command command_call method_call
 %type <node> expr_value arg_value primary_value rel_expr
-%type <node> block_arg var_ref
+%type <node> block_arg arg_splat var_ref
 %type <node> command_rhs arg_rhs
 %type <node> command_asgn mrhs_arg block_call block_command
 %type <node> f_block_opt
@@ -130,10 +140,10 @@
 %type <node> p_top_expr_body
 %type <node> p_expr p_as p_alt p_expr_basic
 %type <node> p_arg
-%type <node> p_value p_primitive p_variable p_var_ref p_const
+%type <node> p_value p_primitive p_variable p_var_ref p_expr_ref p_const
 %type <node> p_kw
 %type <node> f_block_arg keyword_variable program
-%type <node> var_lhs lhs mlhs_node mlhs mlhs_item mlhs_inner for_var
+%type <node> nonlocal_var var_lhs lhs mlhs_node mlhs mlhs_item mlhs_inner for_var
 
 %type <node_list> assocs assoc_list opt_f_block_arg f_rest_arg f_optarg f_args
 %type <node_list> f_block_optarg f_kwrest f_no_kwarg f_kwarg f_block_kwarg f_arg
@@ -301,6 +311,15 @@
 %right tPOW
 %right tBANG tTILDE tUPLUS
 
+/* trivia, recorded only in lossless mode and never passed to the parser */
+%token <token> tWHITESPACE        "whitespace"
+%token <token> tIGNORED_NL        "ignored newline"
+%token <token> tLINE_CONTINUATION "line continuation"
+%token <token> tCOMMENT           "comment"
+%token <token> tEMBDOC            "embedded document"
+%token <token> tEND_DATA          "__END__ section"
+%token <token> tSKIPPED           "skipped input"
+
 %token tLAST_TOKEN
 
 /* Grammar follows */
@@ -343,7 +362,12 @@
                     }
                 | error top_stmt
                     {
-                      $$ = Value::NodeList( vec![ $<Node>2 ] );
+                        let mut nodes = vec![];
+                        if self.error_recovery {
+                            nodes.push( self.builder.error_node(self.error_begin, @2.begin) );
+                        }
+                        nodes.push( $<Node>2 );
+                        $$ = Value::NodeList(nodes);
                     }
                 ;
 
@@ -439,7 +463,12 @@
                     }
                 | error stmt
                     {
-                        $$ = Value::NodeList( vec![ $<Node>2 ] );
+                        let mut nodes = vec![];
+                        if self.error_recovery {
+                            nodes.push( self.builder.error_node(self.error_begin, @2.begin) );
+                        }
+                        nodes.push( $<Node>2 );
+                        $$ = Value::NodeList(nodes);
                     }
                 ;
 
@@ -653,58 +682,12 @@
                             )
                         );
                     }
-                | rassign
-                    {
-                        $$ = $1;
-                    }
                 | expr
                     {
                         $$ = $1;
                     }
                 ;
 
-         rassign: arg_value tASSOC lhs
-                    {
-                        $$ = Value::Node(
-                            self.builder.rassign(
-                                $<Node>1,
-                                $<Token>2,
-                                $<Node>3
-                            )
-                        );
-                    }
-                | arg_value tASSOC mlhs
-                    {
-                        $$ = Value::Node(
-                            self.builder.multi_rassign(
-                                $<Node>1,
-                                $<Token>2,
-                                $<Node>3
-                            )
-                        );
-                    }
-                | rassign tASSOC lhs
-                    {
-                        $$ = Value::Node(
-                            self.builder.rassign(
-                                $<Node>1,
-                                $<Token>2,
-                                $<Node>3
-                            )
-                        );
-                    }
-                | rassign tASSOC mlhs
-                    {
-                        $$ = Value::Node(
-                            self.builder.multi_rassign(
-                                $<Node>1,
-                                $<Token>2,
-                                $<Node>3
-                            )
-                        );
-                    }
-                ;
-
     command_asgn: lhs tEQL command_rhs
                     {
                         $$ = Value::Node(
@@ -903,6 +886,38 @@
                             )?
                         );
                     }
+                | arg tASSOC
+                    {
+                        let arg = match yystack.borrow_value_at(1) {
+                            Value::Node(node) => node,
+                            other => unreachable!("expected Node, got {:?}", other)
+                        };
+                        self.value_expr(arg)?;
+
+                        self.yylexer.lex_state.set(EXPR_BEG|EXPR_LABEL);
+                        self.yylexer.command_start = false;
+                        self.pattern_variables.push();
+                        self.pattern_hash_keys.push();
+
+                        $<Bool>$ = Value::Bool(self.yylexer.in_kwarg);
+                        self.yylexer.in_kwarg = true;
+                    }
+                  p_top_expr_body
+                    {
+                        self.pattern_hash_keys.pop();
+                        self.pattern_variables.pop();
+                        self.yylexer.in_kwarg = $<Bool>3;
+
+                        self.check_ruby_version(RubyVersion::new(3, 0), "rightward pattern matching", &@2)?;
+
+                        $$ = Value::Node(
+                            self.builder.match_pattern(
+                                $<Node>1,
+                                $<Token>2,
+                                $<Node>4
+                            )
+                        );
+                    }
                 | arg kIN
                     {
                         let arg = match yystack.borrow_value_at(1) {
@@ -912,14 +927,16 @@
                         self.value_expr(arg)?;
 
                         self.yylexer.lex_state.set(EXPR_BEG|EXPR_LABEL);
-                        self.yylexer.command_start = true;
+                        self.yylexer.command_start = false;
                         self.pattern_variables.push();
+                        self.pattern_hash_keys.push();
 
                         $<Bool>$ = Value::Bool(self.yylexer.in_kwarg);
                         self.yylexer.in_kwarg = true;
                     }
-                  p_expr
+                  p_top_expr_body
                     {
+                        self.pattern_hash_keys.pop();
                         self.pattern_variables.pop();
                         self.yylexer.in_kwarg = $<Bool>3;
 
@@ -1182,6 +1199,8 @@
                     }
                 | kYIELD command_args
                     {
+                        self.validate_yield(&@1);
+
                         $$ = Value::Node(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
@@ -2183,6 +2202,8 @@
                         self.static_env.unextend();
                         self.context.pop();
                         self.current_arg_stack.pop();
+
+                        self.check_ruby_version(RubyVersion::new(3, 0), "endless method definition", &@3)?;
                     }
                 | defn_head f_paren_args tEQL arg kRESCUE_MOD arg
                     {
@@ -2219,6 +2240,8 @@
                         self.static_env.unextend();
                         self.context.pop();
                         self.current_arg_stack.pop();
+
+                        self.check_ruby_version(RubyVersion::new(3, 0), "endless method definition", &@3)?;
                     }
                 | defs_head f_paren_args tEQL arg
                     {
@@ -2241,6 +2264,8 @@
                         self.static_env.unextend();
                         self.context.pop();
                         self.current_arg_stack.pop();
+
+                        self.check_ruby_version(RubyVersion::new(3, 0), "endless method definition", &@3)?;
                     }
                 | defs_head f_paren_args tEQL arg kRESCUE_MOD arg
                     {
@@ -2279,6 +2304,8 @@
                         self.static_env.unextend();
                         self.context.pop();
                         self.current_arg_stack.pop();
+
+                        self.check_ruby_version(RubyVersion::new(3, 0), "endless method definition", &@3)?;
                     }
                 | primary
                     {
@@ -2559,6 +2586,15 @@
                             )
                         );
                     }
+                | tAMPER
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 1), "anonymous block forwarding", &@1)?;
+                        self.check_anonymous_arg(AnonymousArg::Blockarg, &@1)?;
+
+                        $$ = Value::Node(
+                            self.builder.forwarded_blockarg($<Token>1)
+                        );
+                    }
                 ;
 
    opt_block_arg: tCOMMA block_arg
@@ -2575,13 +2611,9 @@
                     {
                         $$ = Value::NodeList( vec![ $<Node>1 ] );
                     }
-                | tSTAR arg_value
+                | arg_splat
                     {
-                        $$ = Value::NodeList(
-                            vec![
-                                self.builder.splat($<Token>1, Some($<Node>2))
-                            ]
-                        );
+                        $$ = Value::NodeList( vec![ $<Node>1 ] );
                     }
                 | args tCOMMA arg_value
                     {
@@ -2589,14 +2621,31 @@
                         nodes.push( $<Node>3 );
                         $$ = Value::NodeList(nodes);
                     }
-                | args tCOMMA tSTAR arg_value
+                | args tCOMMA arg_splat
                     {
                         let mut nodes = $<NodeList>1;
-                        nodes.push( self.builder.splat($<Token>3, Some($<Node>4)) );
+                        nodes.push( $<Node>3 );
                         $$ = Value::NodeList(nodes);
                     }
                 ;
 
+       arg_splat: tSTAR arg_value
+                    {
+                        $$ = Value::Node(
+                            self.builder.splat($<Token>1, Some($<Node>2))
+                        );
+                    }
+                | tSTAR
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 2), "anonymous rest forwarding", &@1)?;
+                        self.check_anonymous_arg(AnonymousArg::Restarg, &@1)?;
+
+                        $$ = Value::Node(
+                            self.builder.forwarded_restarg($<Token>1)
+                        );
+                    }
+                ;
+
         mrhs_arg: mrhs
                     {
                         $$ = Value::Node(
@@ -2780,6 +2829,8 @@
                     }
                 | kYIELD tLPAREN2 call_args rparen
                     {
+                        self.validate_yield(&@1);
+
                         $$ = Value::Node(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
@@ -2792,6 +2843,8 @@
                     }
                 | kYIELD tLPAREN2 rparen
                     {
+                        self.validate_yield(&@1);
+
                         $$ = Value::Node(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
@@ -2804,6 +2857,8 @@
                     }
                 | kYIELD
                     {
+                        self.validate_yield(&@1);
+
                         $$ = Value::Node(
                             self.builder.keyword_cmd(
                                 KeywordCmd::Yield,
@@ -3733,6 +3788,8 @@ opt_block_args_tail:
                         let lambda_call = self.builder.call_lambda($<Token>1);
                         let args = if self.max_numparam_stack.has_numparams() {
                             ArgsType::Numargs(self.max_numparam_stack.top() as u8)
+                        } else if self.max_numparam_stack.uses_it() {
+                            ArgsType::It
                         } else {
                             ArgsType::Args($<MaybeNode>3)
                         };
@@ -4081,6 +4138,8 @@ opt_block_args_tail:
                     {
                         let args_type = if self.max_numparam_stack.has_numparams() {
                             ArgsType::Numargs(self.max_numparam_stack.top() as u8)
+                        } else if self.max_numparam_stack.uses_it() {
+                            ArgsType::It
                         } else {
                             ArgsType::Args($<MaybeNode>2)
                         };
@@ -4107,6 +4166,8 @@ opt_block_args_tail:
                     {
                         let args_type = if self.max_numparam_stack.has_numparams() {
                             ArgsType::Numargs(self.max_numparam_stack.top() as u8)
+                        } else if self.max_numparam_stack.uses_it() {
+                            ArgsType::It
                         } else {
                             ArgsType::Args($<MaybeNode>2)
                         };
@@ -4631,6 +4692,8 @@ opt_block_args_tail:
 
           p_find: p_rest tCOMMA p_args_post tCOMMA p_rest
                     {
+                        self.check_ruby_version(RubyVersion::new(3, 0), "find pattern", &@1)?;
+
                         let nodes = [ vec![ $<Node>1 ], $<NodeList>3, vec![ $<Node>5 ] ].concat();
                         $$ = Value::NodeList(nodes);
                     }
@@ -4841,6 +4904,10 @@ opt_block_args_tail:
                     {
                         $$ = $1;
                     }
+                | p_expr_ref
+                    {
+                        $$ = $1;
+                    }
                 | p_const
                     {
                         $$ = $1;
@@ -4939,6 +5006,26 @@ opt_block_args_tail:
                             self.builder.pin($<Token>1, lvar)
                         );
                     }
+                | tCARET nonlocal_var
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 1), "pinning of instance, class and global variables", &@1)?;
+
+                        let non_lvar = self.builder.accessible($<Node>2);
+                        $$ = Value::Node(
+                            self.builder.pin($<Token>1, non_lvar)
+                        );
+                    }
+                ;
+
+      p_expr_ref: tCARET tLPAREN expr_value rparen
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 1), "pinning of expressions", &@1)?;
+
+                        let expr = self.builder.begin($<Token>2, Some($<Node>3), $<Token>4);
+                        $$ = Value::Node(
+                            self.builder.pin($<Token>1, expr)
+                        );
+                    }
                 ;
 
          p_const: tCOLON3 cname
@@ -5407,36 +5494,42 @@ xstring_contents: /* none */
                     }
                 ;
 
-   user_variable: tIDENTIFIER
+    nonlocal_var: tIVAR
                     {
                         $$ = Value::Node(
-                            self.builder.lvar($<Token>1)
+                            self.builder.ivar($<Token>1)
                         );
                     }
-                | tIVAR
+                | tGVAR
                     {
                         $$ = Value::Node(
-                            self.builder.ivar($<Token>1)
+                            self.builder.gvar($<Token>1)
                         );
                     }
-                | tGVAR
+                | tCVAR
                     {
                         $$ = Value::Node(
-                            self.builder.gvar($<Token>1)
+                            self.builder.cvar($<Token>1)
                         );
                     }
-                | tCONSTANT
+                ;
+
+   user_variable: tIDENTIFIER
                     {
                         $$ = Value::Node(
-                            self.builder.const_($<Token>1)
+                            self.builder.lvar($<Token>1)
                         );
                     }
-                | tCVAR
+                | tCONSTANT
                     {
                         $$ = Value::Node(
-                            self.builder.cvar($<Token>1)
+                            self.builder.const_($<Token>1)
                         );
                     }
+                | nonlocal_var
+                    {
+                        $$ = $1;
+                    }
                 ;
 
 keyword_variable: kNIL
@@ -5486,11 +5579,14 @@ keyword_variable: kNIL
          var_ref: user_variable
                     {
                         let node = Node::from(yystack.owned_value_at(0));
+                        let mut is_it_param = false;
                         if let Node::Lvar(node) = &node {
                             let name = &node.name;
                             match name.chars().collect::<Vec<_>>()[..] {
                                 ['_', n] if n >= '1' && n <= '9' => {
-                                    if !self.static_env.is_declared(&name) && self.context.is_in_dynamic_block() {
+                                    if self.ruby_version >= RubyVersion::new(2, 7)
+                                        && !self.static_env.is_declared(&name)
+                                        && self.context.is_in_dynamic_block() {
                                         /* definitely an implicit param */
 
                                         if self.max_numparam_stack.has_ordinary_params() {
@@ -5500,6 +5596,13 @@ keyword_variable: kNIL
                                             );
                                         }
 
+                                        if self.max_numparam_stack.uses_it() {
+                                            return self.yyerror(
+                                                &@1,
+                                                DiagnosticMessage::NumparamNotAllowedWithIt,
+                                            );
+                                        }
+
                                         let mut raw_context = self.context.inner_clone();
                                         let mut raw_max_numparam_stack = self.max_numparam_stack.inner_clone();
 
@@ -5533,12 +5636,35 @@ keyword_variable: kNIL
                                         self.max_numparam_stack.register(n.to_digit(10).expect("numparam must have a digit after _") as i32)
                                     }
                                 },
+                                ['i', 't'] => {
+                                    if self.ruby_version >= RubyVersion::new(3, 4)
+                                        && !self.static_env.is_declared(&name)
+                                        && self.context.is_in_dynamic_block()
+                                        && !self.max_numparam_stack.has_ordinary_params() {
+                                        /* implicit `it` param, it's not declared
+                                            to keep nested blocks with their own `it` */
+
+                                        if self.max_numparam_stack.has_numparams() {
+                                            return self.yyerror(
+                                                &@1,
+                                                DiagnosticMessage::ItNotAllowedWithNumparam,
+                                            );
+                                        }
+
+                                        self.max_numparam_stack.set_uses_it();
+                                        is_it_param = true;
+                                    }
+                                },
                                 _ => {}
                             }
                         }
 
                         $$ = Value::Node(
-                            self.builder.accessible(node)
+                            if is_it_param {
+                                node
+                            } else {
+                                self.builder.accessible(node)
+                            }
                         );
                     }
                 | keyword_variable
@@ -5947,6 +6073,11 @@ keyword_variable: kNIL
                     }
                 | kwrest_mark
                     {
+                        self.static_env.declare_anonymous_arg(
+                            AnonymousArg::Kwrestarg,
+                            self.context.is_in_dynamic_block()
+                        );
+
                         $$ = Value::NodeList(
                             vec![
                                 self.builder.kwrestarg($<Token>1, None)?
@@ -6028,6 +6159,11 @@ keyword_variable: kNIL
                     }
                 | restarg_mark
                     {
+                        self.static_env.declare_anonymous_arg(
+                            AnonymousArg::Restarg,
+                            self.context.is_in_dynamic_block()
+                        );
+
                         $$ = Value::NodeList(
                             vec![
                                 self.builder.restarg($<Token>1, None)?
@@ -6054,6 +6190,19 @@ keyword_variable: kNIL
                             self.builder.blockarg($<Token>1, ident_t)?
                         );
                     }
+                | blkarg_mark
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 1), "anonymous block forwarding", &@1)?;
+
+                        self.static_env.declare_anonymous_arg(
+                            AnonymousArg::Blockarg,
+                            self.context.is_in_dynamic_block()
+                        );
+
+                        $$ = Value::Node(
+                            self.builder.anonymous_blockarg($<Token>1)
+                        );
+                    }
                 ;
 
  opt_f_block_arg: tCOMMA f_block_arg
@@ -6143,6 +6292,14 @@ keyword_variable: kNIL
                             )
                         );
                     }
+                | tLABEL
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 1), "hash shorthand", &@1)?;
+
+                        $$ = Value::Node(
+                            self.builder.pair_label($<Token>1)
+                        );
+                    }
                 | tSTRING_BEG string_contents tLABEL_END arg_value
                     {
                         $$ = Value::Node(
@@ -6160,6 +6317,15 @@ keyword_variable: kNIL
                             self.builder.kwsplat($<Token>1, $<Node>2)
                         );
                     }
+                | tDSTAR
+                    {
+                        self.check_ruby_version(RubyVersion::new(3, 2), "anonymous keyword rest forwarding", &@1)?;
+                        self.check_anonymous_arg(AnonymousArg::Kwrestarg, &@1)?;
+
+                        $$ = Value::Node(
+                            self.builder.forwarded_kwrestarg($<Token>1)
+                        );
+                    }
                 ;
 
        operation: tIDENTIFIER
@@ -6327,6 +6493,14 @@ impl Parser {
             buffer_name,
             debug,
             decoder,
+            error_recovery,
+            suppressed_warnings,
+            promoted_warnings,
+            lossless_tokens,
+            ruby_version,
+            locals,
+            context,
+            expected_tokens,
         } = options;
 
         let mut lexer = Lexer::new(input, &buffer_name, decoder);
@@ -6337,6 +6511,21 @@ impl Parser {
         let pattern_variables = VariablesStack::new();
         let pattern_hash_keys = VariablesStack::new();
 
+        for (idx, scope) in locals.iter().enumerate() {
+            if idx > 0 {
+                lexer.static_env.extend_dynamic();
+            }
+            for name in scope {
+                lexer.static_env.declare(name);
+            }
+        }
+        for item in context {
+            if item == ContextItem::Block || item == ContextItem::Lambda {
+                max_numparam_stack.push();
+            }
+            lexer.context.push(item);
+        }
+
         let builder = Builder::new(
             lexer.static_env.clone(),
             lexer.context.clone(),
@@ -6345,6 +6534,7 @@ impl Parser {
             pattern_variables.clone(),
             pattern_hash_keys.clone(),
             lexer.diagnostics.clone(),
+            ruby_version,
         );
 
         let last_token = Token {
@@ -6369,6 +6559,14 @@ impl Parser {
             last_token,
             tokens: vec![],
             diagnostics: lexer.diagnostics.clone(),
+            error_recovery,
+            has_syntax_errors: false,
+            error_begin: 0,
+            suppressed_warnings,
+            promoted_warnings,
+            lossless_tokens,
+            ruby_version,
+            expected_tokens,
             yylexer: lexer,
         }
     }
@@ -6382,16 +6580,54 @@ impl Parser {
     pub fn do_parse(mut self) -> ParserResult  {
         self.parse();
 
+        // without error recovery the parser still skips invalid statements
+        // (to report errors in the same way), but the AST is incomplete
+        let ast = if self.has_syntax_errors && !self.error_recovery {
+            None
+        } else {
+            self.result.take()
+        };
+
+        let mut tokens = std::mem::take(&mut self.tokens);
+        if self.lossless_tokens {
+            tokens = self.yylexer.add_trivia(tokens);
+        }
+
         ParserResult {
-            ast: self.result,
-            tokens: std::mem::take(&mut self.tokens),
-            diagnostics: self.diagnostics.take(),
+            ast,
+            tokens,
+            diagnostics: self.take_diagnostics(),
             comments: self.yylexer.comments,
             magic_comments: self.yylexer.magic_comments,
             input: self.yylexer.buffer.input,
         }
     }
 
+    // Applies `suppressed_warnings` and `promoted_warnings` from options
+    fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        let suppressed = &self.suppressed_warnings;
+        let promoted = &self.promoted_warnings;
+
+        self.diagnostics
+            .take()
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                if diagnostic.is_warning() {
+                    let is_listed = |list: &[String]| {
+                        list.iter().any(|item| diagnostic.message.is_identified_by(item))
+                    };
+                    if is_listed(suppressed) {
+                        return None;
+                    }
+                    if is_listed(promoted) {
+                        diagnostic.level = ErrorLevel::Error;
+                    }
+                }
+                Some(diagnostic)
+            })
+            .collect()
+    }
+
     /// Turns `self` and `yylexer` into debug mode
     ///
     /// Use it only for debugging to see bison/lexer debug info
@@ -6400,6 +6636,12 @@ impl Parser {
         self.yylexer.set_debug(debug);
     }
 
+    /// Makes the lexer treat the beginning of input as a beginning of a statement
+    /// that follows a newline or `;` (used to parse a part of the source)
+    pub(crate) fn set_command_start(&mut self, command_start: bool) {
+        self.yylexer.command_start = command_start;
+    }
+
     fn warn(&mut self, loc: &Loc, message: DiagnosticMessage) {
         let diagnostic = Diagnostic::new(
             ErrorLevel::Warning,
@@ -6443,19 +6685,71 @@ impl Parser {
     }
 
     fn yyerror1(&mut self, message: DiagnosticMessage, range: Range) -> Result<i32, ()> {
+        self.error_begin = range.begin_pos;
+        self.emit_error(message, range);
+        Err(())
+    }
+
+    fn emit_error(&mut self, message: DiagnosticMessage, range: Range) {
         let diagnostic = Diagnostic::new(ErrorLevel::Error, message, range);
         self.diagnostics.emit(diagnostic);
-        Err(())
+        self.has_syntax_errors = true;
     }
 
     fn report_syntax_error(&mut self, ctx: &Context) {
-        let id: usize = ctx.token().code().try_into().expect("failed to convert token code into i32, is it too big?");
-        let diagnostic = Diagnostic::new(
-            ErrorLevel::Error,
-            DiagnosticMessage::UnexpectedToken(Lexer::TOKEN_NAMES[id].to_owned()),
-            Range::new(ctx.location().begin, ctx.location().end)
-        );
-        self.diagnostics.emit(diagnostic);
+        let got = Self::symbol_name(ctx.token());
+        let expected = if self.expected_tokens { Self::expected_token_names(ctx) } else { vec![] };
+
+        let message = if expected.is_empty() {
+            DiagnosticMessage::UnexpectedToken(got)
+        } else {
+            DiagnosticMessage::UnexpectedTokenExpected { got, expected }
+        };
+        self.error_begin = ctx.location().begin;
+        self.emit_error(message, Range::new(ctx.location().begin, ctx.location().end));
+    }
+
+    fn symbol_name(symbol: &SymbolKind) -> String {
+        let id: usize = symbol.code().try_into().expect("failed to convert token code into i32, is it too big?");
+        Lexer::TOKEN_NAMES[id].to_owned()
+    }
+
+    // Like MRI, expected tokens are listed only if there are few of them,
+    // a longer list doesn't really help
+    const MAX_EXPECTED_TOKENS: usize = 4;
+
+    // Tokens that have an action in the state where the error was detected
+    // (the same lookup that bison does in yysyntax_error).
+    // `Context` doesn't provide them, so they are taken from parser tables directly.
+    #[allow(clippy::unnecessary_cast)]
+    fn expected_token_names(ctx: &Context) -> Vec<String> {
+        // symbol kind of the `error` token
+        const YYERROR_SYMBOL: i32 = 1;
+
+        let state = ctx.yystack.state_at(0) as usize;
+        let yyn = Self::yypact_[state] as i32;
+        if yyn == YYPACT_NINF_ as i32 {
+            return vec![];
+        }
+
+        let begin = if yyn < 0 { -yyn } else { 0 };
+        let end = std::cmp::min(Self::YYLAST_ as i32 - yyn + 1, Self::YYNTOKENS_ as i32);
+
+        let mut names = vec![];
+        for token in begin..end {
+            let idx = (token + yyn) as usize;
+            if Self::yycheck_[idx] as i32 != token
+                || token == YYERROR_SYMBOL
+                || Self::yytable_[idx] as i32 == YYTABLE_NINF_ as i32
+            {
+                continue;
+            }
+            if names.len() == Self::MAX_EXPECTED_TOKENS {
+                return vec![];
+            }
+            names.push(Lexer::TOKEN_NAMES[token as usize].to_owned());
+        }
+        names
     }
 
     fn warn_eol(&mut self, loc: &Loc, tok: &str) {
@@ -6467,6 +6761,49 @@ impl Parser {
     fn value_expr(&self, node: &Node) -> Result<(), ()> {
         self.builder.value_expr(node)
     }
+
+    fn check_ruby_version(&mut self, since: RubyVersion, syntax: &str, loc: &Loc) -> Result<(), ()> {
+        if self.ruby_version < since {
+            self.yyerror(
+                loc,
+                DiagnosticMessage::SyntaxNotAvailable {
+                    syntax: syntax.to_owned(),
+                    ruby_version: self.ruby_version.to_string(),
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+
+    // Reported without aborting the rule, so error recovery still gets the node
+    fn validate_yield(&mut self, loc: &Loc) {
+        if self.context.is_in_class_or_module_body() {
+            self.emit_error(DiagnosticMessage::InvalidYield, Range::new(loc.begin, loc.end));
+        }
+    }
+
+    fn check_anonymous_arg(&mut self, arg: AnonymousArg, loc: &Loc) -> Result<(), ()> {
+        if !self.static_env.is_anonymous_arg_declared(arg) {
+            let message = match arg {
+                AnonymousArg::Restarg => DiagnosticMessage::NoAnonymousRestarg,
+                AnonymousArg::Kwrestarg => DiagnosticMessage::NoAnonymousKwrestarg,
+                AnonymousArg::Blockarg => DiagnosticMessage::NoAnonymousBlockarg,
+            };
+            self.yyerror(loc, message)?;
+        }
+
+        if self.static_env.is_anonymous_arg_declared_in_block(arg) {
+            let kind = match arg {
+                AnonymousArg::Restarg => "rest",
+                AnonymousArg::Kwrestarg => "keyword rest",
+                AnonymousArg::Blockarg => "block",
+            };
+            self.yyerror(loc, DiagnosticMessage::AnonymousArgUsedInBlock(kind.to_owned()))?;
+        }
+
+        Ok(())
+    }
 }
 
 impl TokenValue {
@@ -6483,6 +6820,13 @@ impl TokenValue {
             Self::InvalidString(bytes) => bytes,
         }
     }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::String(s) => s.as_bytes(),
+            Self::InvalidString(bytes) => bytes,
+        }
+    }
 }
 
 impl Token {
@@ -6493,4 +6837,8 @@ impl Token {
     pub fn into_bytes(self) -> Vec<u8> {
         self.token_value.into_bytes()
     }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        self.token_value.as_bytes()
+    }
 }
@@ -1,4 +1,6 @@
 use crate::source::CustomDecoder;
+use crate::ContextItem;
+use crate::RubyVersion;
 
 /// Configuration of the parser
 pub struct ParserOptions {
@@ -13,7 +15,8 @@ pub struct ParserOptions {
 
     /// Custom decoder that can be used if the source is encoded
     /// in unknown encoding. Only UTF-8 and ASCII-8BIT/BINARY are
-    /// supported out of the box.
+    /// supported out of the box, with `encodings` feature enabled
+    /// `source::encodings::decode` is used if there's no custom decoder.
     ///
     /// # Example
     /// ```rust
@@ -40,6 +43,92 @@ pub struct ParserOptions {
     /// assert_eq!(ast.unwrap().expression().source(&input).unwrap(), "decoded".to_owned())
     /// ```
     pub decoder: CustomDecoder,
+
+    /// Controls whether the parser should recover from syntax errors.
+    ///
+    /// By default `ParserResult::ast` is `None` if there's a syntax error.
+    /// In recovery mode the parser skips tokens until it finds a statement
+    /// to continue from, statements before it in the same list are replaced
+    /// with a `Node::Error` placeholder.
+    /// Syntax errors are still reported in `ParserResult::diagnostics`.
+    ///
+    /// Errors that reach the end of input (like a missing `end`)
+    /// are not recoverable, `ast` is `None` in this case.
+    pub error_recovery: bool,
+
+    /// Codes or names of warnings (like `"W0007"` or `"ambiguous_first_argument"`)
+    /// that must not be reported in `ParserResult::diagnostics`.
+    pub suppressed_warnings: Vec<String>,
+
+    /// Codes or names of warnings that must be reported as errors.
+    ///
+    /// Promoted warnings don't affect parsing, `ParserResult::ast` is still returned.
+    pub promoted_warnings: Vec<String>,
+
+    /// Controls whether `ParserResult::tokens` must also contain trivia:
+    /// whitespaces, ignored newlines, line continuations, comments,
+    /// embedded documents and `__END__` section.
+    ///
+    /// In this mode tokens are sorted by location and cover the whole input,
+    /// so concatenating their sources gives the original input byte-for-byte.
+    /// Trivia tokens can be recognized with `Lexer::is_trivia`.
+    pub lossless_tokens: bool,
+
+    /// Version of Ruby whose syntax is accepted, the latest supported one by default.
+    ///
+    /// Syntax introduced in newer versions (like endless methods in 3.0
+    /// or hash shorthand in 3.1) is reported as an error.
+    pub ruby_version: RubyVersion,
+
+    /// Local variables that are defined before the parsed code,
+    /// grouped by scope from the outermost to the innermost.
+    ///
+    /// All of them are visible in the parsed code (like in `eval` inside of a block),
+    /// so `foo` is parsed as `Lvar` if `foo` is in one of the lists.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lib_ruby_parser::{Node, Parser, ParserOptions};
+    ///
+    /// let locals = vec![vec!["foo".to_owned()], vec!["bar".to_owned()]];
+    /// let options = ParserOptions { locals, ..Default::default() };
+    /// let ast = Parser::new(b"bar", options).do_parse().ast.unwrap();
+    ///
+    /// assert!(matches!(ast, Node::Lvar(_)));
+    /// ```
+    pub locals: Vec<Vec<String>>,
+
+    /// Constructs that enclose the parsed code, from the outermost to the innermost.
+    ///
+    /// Parsed code is validated as if it was written inside of them,
+    /// e.g. with `vec![ContextItem::Def]` a class definition is an error,
+    /// with `vec![ContextItem::Class]` `return` and `yield` are errors
+    /// and with `vec![ContextItem::Block]` `_1` is a numbered parameter of the block.
+    pub context: Vec<ContextItem>,
+
+    /// Controls whether syntax errors must list tokens that the parser expected.
+    ///
+    /// By default a syntax error is reported as `DiagnosticMessage::UnexpectedToken`
+    /// (`unexpected tRPAREN`). In this mode it's reported as
+    /// `DiagnosticMessage::UnexpectedTokenExpected` if there are at most 4 expected tokens.
+    ///
+    /// # Example
+    /// ```rust
+    /// use lib_ruby_parser::{DiagnosticMessage, Parser, ParserOptions};
+    ///
+    /// let options = ParserOptions { expected_tokens: true, ..Default::default() };
+    /// let diagnostics = Parser::new(b"alias $a 1", options).do_parse().diagnostics;
+    ///
+    /// assert!(matches!(
+    ///     &diagnostics[0].message,
+    ///     DiagnosticMessage::UnexpectedTokenExpected { got, .. } if got == "tINTEGER"
+    /// ));
+    /// assert_eq!(
+    ///     diagnostics[0].render_message(),
+    ///     "unexpected integer literal, expected global variable, numbered reference or back reference"
+    /// );
+    /// ```
+    pub expected_tokens: bool,
 }
 
 const DEFAULT_BUFFER_NAME: &str = "(eval)";
@@ -50,6 +139,14 @@ impl Default for ParserOptions {
             buffer_name: DEFAULT_BUFFER_NAME.to_owned(),
             debug: false,
             decoder: CustomDecoder { f: None },
+            error_recovery: false,
+            suppressed_warnings: vec![],
+            promoted_warnings: vec![],
+            lossless_tokens: false,
+            ruby_version: RubyVersion::LATEST,
+            locals: vec![],
+            context: vec![],
+            expected_tokens: false,
         }
     }
 }
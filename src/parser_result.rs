@@ -6,6 +6,7 @@ use crate::Node;
 use crate::Token;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParserResult {
     pub ast: Option<Node>,
     pub tokens: Vec<Token>,
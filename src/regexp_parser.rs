@@ -0,0 +1,733 @@
+// Validator of regexps in Onigmo syntax (the one used by Ruby),
+// used when "onig" feature is disabled.
+//
+// It doesn't compile regexps, only checks the syntax
+// and collects names of named groups, error messages are the same as in Onigmo.
+// `analysis::ScopeGraph` uses it to locate named groups regardless of the feature.
+
+const MAX_REPEAT_NUM: usize = 100_000;
+
+const POSIX_BRACKETS: &[&str] = &[
+    "alnum", "alpha", "ascii", "blank", "cntrl", "digit", "graph", "lower", "print", "punct",
+    "space", "upper", "xdigit", "word",
+];
+
+// Whether the last parsed element can be a target of `*`, `+`, `?` or `{n,m}`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    None,
+    Invalid,
+    Valid,
+}
+
+// Item of a character class
+enum ClassItem {
+    Char(Option<u32>),
+    Set,
+}
+
+pub(crate) struct RegexpParser {
+    chars: Vec<char>,
+    pos: usize,
+    extended: bool,
+    groups: usize,
+    // names of named groups with byte offsets of the names in the source
+    names: Vec<(String, usize)>,
+    name_refs: Vec<String>,
+    backrefs: Vec<usize>,
+    calls: Vec<usize>,
+}
+
+impl RegexpParser {
+    /// Parses given regexp source, returns names of named groups
+    /// or an error message
+    #[cfg_attr(feature = "onig", allow(dead_code))]
+    pub(crate) fn parse(source: &str, options: &[char]) -> Result<Vec<String>, String> {
+        let mut names: Vec<String> = vec![];
+        for (name, _) in Self::named_groups(source, options)? {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Same as `parse`, but returns every named group
+    /// with a byte offset of its name in `source`
+    pub(crate) fn named_groups(
+        source: &str,
+        options: &[char],
+    ) -> Result<Vec<(String, usize)>, String> {
+        let mut parser = Self {
+            chars: source.chars().collect(),
+            pos: 0,
+            extended: options.contains(&'x'),
+            groups: 0,
+            names: vec![],
+            name_refs: vec![],
+            backrefs: vec![],
+            calls: vec![],
+        };
+        parser.parse_alternation(0)?;
+
+        for name in parser.name_refs.iter() {
+            if !parser.names.iter().any(|(defined, _)| defined == name) {
+                return Err(format!("undefined name <{}> reference", name));
+            }
+        }
+        for n in parser.backrefs.iter() {
+            if *n > parser.groups {
+                return Err("invalid backref number/name".to_owned());
+            }
+        }
+        for n in parser.calls.iter() {
+            if *n > parser.groups {
+                return Err(format!("undefined group <{}> reference", n));
+            }
+        }
+
+        Ok(parser.names)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn skip_if(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_alternation(&mut self, depth: usize) -> Result<(), String> {
+        loop {
+            self.parse_sequence()?;
+            match self.peek() {
+                Some('|') => self.pos += 1,
+                Some(')') if depth == 0 => return Err("unmatched close parenthesis".to_owned()),
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<(), String> {
+        let mut target = Target::None;
+
+        loop {
+            self.skip_extended_whitespace();
+
+            let c = match self.peek() {
+                None | Some('|') | Some(')') => return Ok(()),
+                Some(c) => c,
+            };
+            self.pos += 1;
+
+            target = match c {
+                '*' | '+' | '?' => {
+                    check_repeat_target(target)?;
+                    self.skip_repeat_modifier();
+                    Target::Valid
+                }
+                '{' => match self.parse_interval()? {
+                    Some(_) => {
+                        check_repeat_target(target)?;
+                        self.skip_repeat_modifier();
+                        Target::Valid
+                    }
+                    None => Target::Valid,
+                },
+                '(' => match self.parse_group()? {
+                    // comments don't change the target
+                    None => target,
+                    Some(target) => target,
+                },
+                '[' => {
+                    self.parse_class()?;
+                    Target::Valid
+                }
+                '\\' => self.parse_escape()?,
+                '^' | '$' => Target::Invalid,
+                _ => Target::Valid,
+            };
+        }
+    }
+
+    fn skip_extended_whitespace(&mut self) {
+        if !self.extended {
+            return;
+        }
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else if c == '#' {
+                while let Some(c) = self.next() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    // lazy `?` or possessive `+`
+    fn skip_repeat_modifier(&mut self) {
+        if !self.skip_if('?') {
+            self.skip_if('+');
+        }
+    }
+
+    // `{n}`, `{n,}`, `{,m}`, `{n,m}` after `{`, returns None if it's a literal `{`
+    fn parse_interval(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        let start = self.pos;
+        let lower = self.scan_number();
+        let upper = if self.skip_if(',') {
+            self.scan_number()
+        } else {
+            lower.clone()
+        };
+
+        let is_interval = self.skip_if('}') && (lower.is_some() || upper.is_some());
+        if !is_interval {
+            self.pos = start;
+            return Ok(None);
+        }
+
+        let parse = |digits: Option<String>| -> Result<Option<usize>, String> {
+            match digits {
+                Some(digits) => match digits.parse::<usize>() {
+                    Ok(n) if n <= MAX_REPEAT_NUM => Ok(Some(n)),
+                    _ => Err("too big number for repeat range".to_owned()),
+                },
+                None => Ok(None),
+            }
+        };
+        let lower = parse(lower)?.unwrap_or(0);
+        let upper = parse(upper)?;
+        if let Some(upper) = upper {
+            if lower > upper {
+                return Err("upper is smaller than lower in repeat range".to_owned());
+            }
+        }
+        Ok(Some((lower, upper)))
+    }
+
+    fn scan_number(&mut self) -> Option<String> {
+        let mut digits = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        if digits.is_empty() {
+            None
+        } else {
+            Some(digits)
+        }
+    }
+
+    // Group after `(`, returns None for comments `(?#...)`
+    fn parse_group(&mut self) -> Result<Option<Target>, String> {
+        if !self.skip_if('?') {
+            self.groups += 1;
+            self.parse_group_body(self.extended)?;
+            return Ok(Some(Target::Valid));
+        }
+
+        let c = self
+            .next()
+            .ok_or_else(|| "end pattern in group".to_owned())?;
+        match c {
+            ':' | '>' | '~' => {
+                self.parse_group_body(self.extended)?;
+                Ok(Some(Target::Valid))
+            }
+            '=' | '!' => {
+                self.parse_group_body(self.extended)?;
+                Ok(Some(Target::Invalid))
+            }
+            '<' if self.peek() == Some('=') || self.peek() == Some('!') => {
+                self.pos += 1;
+                self.parse_group_body(self.extended)?;
+                Ok(Some(Target::Invalid))
+            }
+            '<' | '\'' => {
+                let terminator = if c == '<' { '>' } else { '\'' };
+                let offset = self.chars[..self.pos].iter().map(|c| c.len_utf8()).sum();
+                let name = self.parse_group_name(terminator)?;
+                self.groups += 1;
+                self.names.push((name, offset));
+                self.parse_group_body(self.extended)?;
+                Ok(Some(Target::Valid))
+            }
+            '#' => {
+                while let Some(c) = self.next() {
+                    if c == ')' {
+                        return Ok(None);
+                    }
+                }
+                Err("end pattern in group".to_owned())
+            }
+            '(' => {
+                self.parse_condition()?;
+                self.parse_group_body(self.extended)?;
+                Ok(Some(Target::Valid))
+            }
+            'i' | 'm' | 'x' | 'a' | 'd' | 'u' | '-' => {
+                self.pos -= 1;
+                let extended = self.parse_options()?;
+                match self.next() {
+                    // `(?x)` changes options until the end of the enclosing group
+                    Some(')') => {
+                        self.extended = extended;
+                        Ok(Some(Target::None))
+                    }
+                    Some(':') => {
+                        self.parse_group_body(extended)?;
+                        Ok(Some(Target::Valid))
+                    }
+                    Some(_) => Err("undefined group option".to_owned()),
+                    None => Err("end pattern in group".to_owned()),
+                }
+            }
+            _ => Err("undefined group option".to_owned()),
+        }
+    }
+
+    fn parse_group_body(&mut self, extended: bool) -> Result<(), String> {
+        let outer_extended = self.extended;
+        self.extended = extended;
+        self.parse_alternation(1)?;
+        self.extended = outer_extended;
+
+        if self.skip_if(')') {
+            Ok(())
+        } else {
+            Err("end pattern with unmatched parenthesis".to_owned())
+        }
+    }
+
+    // `imx-imx` part of `(?imx-imx)` and `(?imx-imx:...)`, returns the state of `x` option
+    fn parse_options(&mut self) -> Result<bool, String> {
+        let mut extended = self.extended;
+        let mut negative = false;
+        while let Some(c) = self.peek() {
+            match c {
+                '-' => negative = true,
+                'x' => extended = !negative,
+                'i' | 'm' => {}
+                'a' | 'd' | 'u' if !negative => {}
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        Ok(extended)
+    }
+
+    // `(?(cond)yes|no)` after `(?(`
+    fn parse_condition(&mut self) -> Result<(), String> {
+        let condition = match self.peek() {
+            Some('<') => {
+                self.pos += 1;
+                self.read_until('>').filter(|_| self.skip_if(')'))
+            }
+            Some('\'') => {
+                self.pos += 1;
+                self.read_until('\'').filter(|_| self.skip_if(')'))
+            }
+            _ => self.read_until(')'),
+        };
+        let condition = match condition {
+            Some(condition) if !condition.is_empty() => condition,
+            _ => return Err("invalid conditional pattern".to_owned()),
+        };
+
+        match condition.parse::<usize>() {
+            Ok(0) => return Err("invalid backref number/name".to_owned()),
+            Ok(n) => self.backrefs.push(n),
+            Err(_) => {
+                if !condition.chars().all(is_name_char) {
+                    return Err("invalid conditional pattern".to_owned());
+                }
+                self.name_refs.push(condition)
+            }
+        }
+        Ok(())
+    }
+
+    // Reads chars until `terminator` (and consumes it)
+    fn read_until(&mut self, terminator: char) -> Option<String> {
+        let mut result = String::new();
+        loop {
+            let c = self.next()?;
+            if c == terminator {
+                return Some(result);
+            }
+            result.push(c);
+        }
+    }
+
+    fn parse_group_name(&mut self, terminator: char) -> Result<String, String> {
+        let start = self.pos;
+        let name = match self.read_until(terminator) {
+            Some(name) => name,
+            None => {
+                let name = self.chars[start..].iter().collect::<String>();
+                return Err(format!("invalid group name <{}>", name));
+            }
+        };
+
+        match name.chars().next() {
+            None => Err("group name is empty".to_owned()),
+            Some(c) if c.is_ascii_digit() || c == '-' || c == '+' => {
+                Err(format!("invalid group name <{}>", name))
+            }
+            _ if !name.chars().all(is_name_char) => {
+                Err(format!("invalid char in group name <{}>", name))
+            }
+            _ => Ok(name),
+        }
+    }
+
+    // Escape sequence after `\` outside of a character class
+    fn parse_escape(&mut self) -> Result<Target, String> {
+        let c = self
+            .next()
+            .ok_or_else(|| "end pattern at escape".to_owned())?;
+        match c {
+            'A' | 'z' | 'Z' | 'b' | 'B' | 'G' | 'K' => Ok(Target::Invalid),
+            'k' => {
+                self.parse_reference(true)?;
+                Ok(Target::Valid)
+            }
+            'g' => {
+                self.parse_reference(false)?;
+                Ok(Target::Valid)
+            }
+            '1'..='9' => {
+                self.pos -= 1;
+                let start = self.pos;
+                let digits = self.scan_number().unwrap_or_default();
+                let n = digits.parse::<usize>().unwrap_or(usize::MAX);
+                if n <= 9 || n <= self.groups {
+                    self.backrefs.push(n);
+                } else if c == '8' || c == '9' {
+                    // a plain char
+                    self.pos = start + 1;
+                } else {
+                    // octal char code
+                    self.pos = start;
+                    self.skip_octal();
+                }
+                Ok(Target::Valid)
+            }
+            _ => {
+                self.pos -= 1;
+                self.parse_char_escape()?;
+                Ok(Target::Valid)
+            }
+        }
+    }
+
+    // `\k<name>`/`\k'name'` (backreference) or `\g<name>`/`\g'name'` (subexp call) after `\k`/`\g`
+    fn parse_reference(&mut self, is_backref: bool) -> Result<(), String> {
+        let terminator = match self.next() {
+            Some('<') => '>',
+            Some('\'') => '\'',
+            _ if is_backref => return Err("invalid backref number/name".to_owned()),
+            _ => return Err("invalid group name <>".to_owned()),
+        };
+        let name = self.read_until(terminator).ok_or_else(|| {
+            if is_backref {
+                "invalid backref number/name".to_owned()
+            } else {
+                "invalid group name <>".to_owned()
+            }
+        })?;
+        if name.is_empty() {
+            return Err("group name is empty".to_owned());
+        }
+
+        // `\k<name+level>`
+        let name = if is_backref {
+            match name
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '+' || *c == '-')
+            {
+                Some((idx, _)) => name[..idx].to_owned(),
+                None => name,
+            }
+        } else {
+            name
+        };
+
+        let number = name
+            .strip_prefix(&['-', '+'][..])
+            .unwrap_or(&name)
+            .parse::<usize>();
+        match number {
+            Ok(n) if name.starts_with('-') || name.starts_with('+') => {
+                // relative reference
+                if n == 0 || (name.starts_with('-') && n > self.groups) {
+                    return Err("invalid backref number/name".to_owned());
+                }
+            }
+            Ok(0) if is_backref => return Err("invalid backref number/name".to_owned()),
+            Ok(n) if is_backref => self.backrefs.push(n),
+            Ok(n) => self.calls.push(n),
+            Err(_) => {
+                if !name.chars().all(is_name_char) {
+                    return Err(format!("invalid char in group name <{}>", name));
+                }
+                self.name_refs.push(name)
+            }
+        }
+        Ok(())
+    }
+
+    // Escape sequence that is not a backreference/anchor,
+    // returns a char code if it's a single char
+    fn parse_char_escape(&mut self) -> Result<ClassItem, String> {
+        let c = self
+            .next()
+            .ok_or_else(|| "end pattern at escape".to_owned())?;
+        let code = match c {
+            'd' | 'D' | 'w' | 'W' | 's' | 'S' | 'h' | 'H' | 'R' | 'X' => return Ok(ClassItem::Set),
+            'p' | 'P' => {
+                self.parse_property(c)?;
+                return Ok(ClassItem::Set);
+            }
+            't' => Some(0x09),
+            'n' => Some(0x0a),
+            'v' => Some(0x0b),
+            'f' => Some(0x0c),
+            'r' => Some(0x0d),
+            'a' => Some(0x07),
+            'e' => Some(0x1b),
+            '0' => {
+                self.pos -= 1;
+                self.skip_octal()
+            }
+            'x' => self.scan_hex(2),
+            'u' => {
+                if self.skip_if('{') {
+                    let code = self.scan_hex(6);
+                    self.skip_if('}');
+                    code
+                } else {
+                    self.scan_hex(4)
+                }
+            }
+            'c' => {
+                self.parse_control_char("end pattern at control")?;
+                None
+            }
+            'C' => {
+                if !self.skip_if('-') {
+                    return Err("invalid control-code syntax".to_owned());
+                }
+                self.parse_control_char("end pattern at control")?;
+                None
+            }
+            'M' => {
+                if !self.skip_if('-') {
+                    return Err("invalid meta-code syntax".to_owned());
+                }
+                self.parse_control_char("end pattern at meta")?;
+                None
+            }
+            c => Some(c as u32),
+        };
+        Ok(ClassItem::Char(code))
+    }
+
+    fn parse_control_char(&mut self, error: &str) -> Result<(), String> {
+        match self.next() {
+            Some('\\') => self.parse_char_escape().map(|_| ()),
+            Some(_) => Ok(()),
+            None => Err(error.to_owned()),
+        }
+    }
+
+    // `\p{Name}`, `\p{^Name}`, `\P{Name}` after `\p`
+    fn parse_property(&mut self, c: char) -> Result<(), String> {
+        if !self.skip_if('{') {
+            return Err(format!("invalid character property name {{{}}}", c));
+        }
+        self.skip_if('^');
+        match self.read_until('}') {
+            Some(name)
+                if !name.is_empty()
+                    && name
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || c == '_' || c == ' ' || c == '-') =>
+            {
+                Ok(())
+            }
+            Some(name) => Err(format!("invalid character property name {{{}}}", name)),
+            None => Err(format!(
+                "invalid character property name {{{}}}",
+                self.chars
+                    .iter()
+                    .skip_while(|c| **c != '{')
+                    .skip(1)
+                    .collect::<String>()
+            )),
+        }
+    }
+
+    fn skip_octal(&mut self) -> Option<u32> {
+        let mut code = 0;
+        for _ in 0..3 {
+            match self.peek().and_then(|c| c.to_digit(8)) {
+                Some(digit) => {
+                    code = code * 8 + digit;
+                    self.pos += 1;
+                }
+                None => break,
+            }
+        }
+        Some(code)
+    }
+
+    fn scan_hex(&mut self, max_len: usize) -> Option<u32> {
+        let mut code = None;
+        for _ in 0..max_len {
+            match self.peek().and_then(|c| c.to_digit(16)) {
+                Some(digit) => {
+                    code = Some(code.unwrap_or(0) * 16 + digit);
+                    self.pos += 1;
+                }
+                None => break,
+            }
+        }
+        code
+    }
+
+    // Character class after `[`
+    fn parse_class(&mut self) -> Result<(), String> {
+        self.skip_if('^');
+
+        let mut is_first = true;
+        let mut prev: Option<ClassItem> = None;
+        loop {
+            let c = self
+                .next()
+                .ok_or_else(|| "premature end of char-class".to_owned())?;
+
+            let item = match c {
+                ']' if is_first && !self.chars[self.pos..].contains(&']') => {
+                    return Err("empty char-class".to_owned())
+                }
+                ']' if !is_first => return Ok(()),
+                '[' if self.peek() == Some(':') => self.parse_posix_bracket()?,
+                '[' => {
+                    self.parse_class()?;
+                    ClassItem::Set
+                }
+                '&' if self.peek() == Some('&') => {
+                    self.pos += 1;
+                    prev = None;
+                    is_first = false;
+                    continue;
+                }
+                '-' if prev.is_some() && self.peek() != Some(']') => {
+                    let begin = prev.take();
+                    let end = self.parse_class_item()?;
+                    if let (Some(ClassItem::Char(Some(begin))), ClassItem::Char(Some(end))) =
+                        (&begin, &end)
+                    {
+                        if begin > end {
+                            return Err("empty range in char class".to_owned());
+                        }
+                    }
+                    if let ClassItem::Set = end {
+                        return Err("char-class value at end of range".to_owned());
+                    }
+                    is_first = false;
+                    continue;
+                }
+                '\\' => self.parse_char_escape()?,
+                c => ClassItem::Char(Some(c as u32)),
+            };
+            prev = Some(item);
+            is_first = false;
+        }
+    }
+
+    // End of a range like `a-z` after `-`
+    fn parse_class_item(&mut self) -> Result<ClassItem, String> {
+        match self.next() {
+            None => Err("premature end of char-class".to_owned()),
+            Some('\\') => self.parse_char_escape(),
+            Some('[') => {
+                if self.peek() == Some(':') {
+                    self.parse_posix_bracket()
+                } else {
+                    self.parse_class()?;
+                    Ok(ClassItem::Set)
+                }
+            }
+            Some(c) => Ok(ClassItem::Char(Some(c as u32))),
+        }
+    }
+
+    // `[:alpha:]` or `[:^alpha:]` after `[`
+    fn parse_posix_bracket(&mut self) -> Result<ClassItem, String> {
+        let start = self.pos;
+        self.pos += 1;
+        self.skip_if('^');
+        let name_start = self.pos;
+        while let Some(c) = self.peek() {
+            if c == ':' || c == ']' {
+                break;
+            }
+            self.pos += 1;
+        }
+
+        if self.peek() == Some(':') && self.peek_at(1) == Some(']') {
+            let name = self.chars[name_start..self.pos].iter().collect::<String>();
+            self.pos += 2;
+            if POSIX_BRACKETS.contains(&&name[..]) {
+                Ok(ClassItem::Set)
+            } else {
+                Err("invalid POSIX bracket type".to_owned())
+            }
+        } else {
+            // not a POSIX bracket, but a nested class that starts with `:`
+            self.pos = start;
+            self.parse_class()?;
+            Ok(ClassItem::Set)
+        }
+    }
+}
+
+fn check_repeat_target(target: Target) -> Result<(), String> {
+    match target {
+        Target::Valid => Ok(()),
+        Target::None => Err("target of repeat operator is not specified".to_owned()),
+        Target::Invalid => Err("target of repeat operator is invalid".to_owned()),
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || !c.is_ascii()
+}
@@ -0,0 +1,508 @@
+use std::collections::HashSet;
+
+use crate::nodes::*;
+use crate::source::buffer::Input;
+use crate::source::{Comment, CustomDecoder, MagicComment, MagicCommentKind, Range, TextEdit};
+use crate::traverse::Visitor;
+use crate::{Diagnostic, Lexer, Node, Parser, ParserOptions, ParserResult, Token};
+
+impl Parser {
+    /// Parses source of `previous` with `edit` applied.
+    ///
+    /// Top-level statements that are not affected by the edit are taken
+    /// from `previous` (with shifted locations), only the edited region is parsed again.
+    /// If it's not possible (e.g. `previous` has syntax errors or the edit changes
+    /// a set of top-level local variables) the whole source is parsed from scratch.
+    ///
+    /// `options` must be the same as the ones that `previous` was parsed with.
+    ///
+    /// Either way the result is the same as a result of a full parse of the edited source.
+    pub fn reparse(
+        previous: &ParserResult,
+        edit: TextEdit,
+        options: ParserOptions,
+    ) -> ParserResult {
+        let source = edit.apply(&previous.input.bytes);
+
+        if let Some(result) =
+            Reparser::new(previous, &edit, &source, &options).and_then(|r| r.reparse())
+        {
+            return result;
+        }
+
+        Parser::new(&source, options).do_parse()
+    }
+}
+
+// Top-level statements that can be parsed independently
+// from other statements (if local variables are known).
+struct Chunk {
+    begin: usize,
+    first_token: usize,
+    first_stmt: usize,
+}
+
+struct Reparser<'a> {
+    previous: &'a ParserResult,
+    statements: Vec<&'a Node>,
+    chunks: Vec<Chunk>,
+    edit: &'a TextEdit,
+    source: &'a [u8],
+    options: &'a ParserOptions,
+}
+
+impl<'a> Reparser<'a> {
+    fn new(
+        previous: &'a ParserResult,
+        edit: &'a TextEdit,
+        source: &'a [u8],
+        options: &'a ParserOptions,
+    ) -> Option<Self> {
+        let has_errors = previous.diagnostics.iter().any(|d| d.is_error());
+        // chunks are split by tokens of the parser, lossless results are parsed from scratch
+        if has_errors
+            || has_encoding_magic_comment(&previous.magic_comments)
+            || options.lossless_tokens
+        {
+            return None;
+        }
+
+        let statements = match previous.ast.as_ref()? {
+            Node::Begin(begin) if begin.begin_l.is_none() => begin.statements.iter().collect(),
+            node => vec![node],
+        };
+        let chunks = split_into_chunks(&statements, &previous.tokens);
+
+        Some(Self {
+            previous,
+            statements,
+            chunks,
+            edit,
+            source,
+            options,
+        })
+    }
+
+    fn chunk_at(&self, pos: usize) -> usize {
+        self.chunks
+            .iter()
+            .rposition(|chunk| chunk.begin <= pos)
+            .unwrap_or(0)
+    }
+
+    // Returns begin of the chunk with index `idx` in the old source
+    fn chunk_begin(&self, idx: usize) -> usize {
+        match self.chunks.get(idx) {
+            Some(chunk) => chunk.begin,
+            None => self.previous.input.len(),
+        }
+    }
+
+    fn stmts_before(&self, idx: usize) -> &[&'a Node] {
+        match self.chunks.get(idx) {
+            Some(chunk) => &self.statements[..chunk.first_stmt],
+            None => &self.statements,
+        }
+    }
+
+    fn tokens_before(&self, idx: usize) -> usize {
+        match self.chunks.get(idx) {
+            Some(chunk) => chunk.first_token,
+            None => self.previous.tokens.len(),
+        }
+    }
+
+    fn reparse(&self) -> Option<ParserResult> {
+        // chunks around the edit are re-parsed, including one extra chunk
+        // on each side, so edits that join or split statements are handled too
+        let first = self.chunk_at(self.edit.range.begin_pos).saturating_sub(1);
+        let last = std::cmp::min(
+            self.chunk_at(self.edit.range.end_pos) + 1,
+            self.chunks.len() - 1,
+        );
+        let is_last_chunk = last == self.chunks.len() - 1;
+
+        let delta = self.edit.delta();
+        let begin = self.chunk_begin(first);
+        let old_end = self.chunk_begin(last + 1);
+        let new_end = shift(old_end, delta);
+
+        let snippet = self.parse_snippet(&self.source[begin..new_end], first)?;
+
+        // the rest of the source can be reused only if it's not affected by the snippet
+        if !is_last_chunk {
+            if !ends_with_terminator(&snippet.tokens) {
+                return None;
+            }
+
+            let prefix = Locals::collect(self.stmts_before(first))?;
+            let before_edit = Locals::collect(
+                &self.statements[self.stmts_before(first).len()..self.stmts_before(last + 1).len()],
+            )?;
+            let after_edit = Locals::collect(&top_level_statements(&snippet.ast))?;
+            if prefix.union(&before_edit).collect::<HashSet<_>>()
+                != prefix.union(&after_edit).collect::<HashSet<_>>()
+            {
+                return None;
+            }
+        }
+
+        let ParserResult {
+            ast: snippet_ast,
+            tokens: mut snippet_tokens,
+            diagnostics: snippet_diagnostics,
+            comments: snippet_comments,
+            magic_comments: snippet_magic_comments,
+            ..
+        } = snippet;
+        if !is_last_chunk {
+            // END_OF_INPUT
+            snippet_tokens.pop();
+        }
+
+        let previous = self.previous;
+        // diagnostics and comments at the very end of the input belong to the last chunk
+        let splice_end = if is_last_chunk { usize::MAX } else { old_end };
+
+        let mut statements = self
+            .stmts_before(first)
+            .iter()
+            .map(|node| (*node).clone())
+            .collect::<Vec<_>>();
+        for mut node in snippet_ast.map(unwrap_statements).unwrap_or_default() {
+            node.for_each_loc_mut(&mut |range| *range = shift_range(range, begin as isize));
+            statements.push(node);
+        }
+        for node in &self.statements[self.stmts_before(last + 1).len()..] {
+            let mut node = (*node).clone();
+            node.for_each_loc_mut(&mut |range| *range = shift_range(range, delta));
+            statements.push(node);
+        }
+
+        let mut tokens = previous.tokens[..self.tokens_before(first)].to_vec();
+        tokens.extend(
+            snippet_tokens
+                .into_iter()
+                .map(|token| shift_token(token, begin as isize)),
+        );
+        tokens.extend(
+            previous.tokens[self.tokens_before(last + 1)..]
+                .iter()
+                .cloned()
+                .map(|token| shift_token(token, delta)),
+        );
+
+        let diagnostics = splice(
+            &previous.diagnostics,
+            snippet_diagnostics,
+            |d: &Diagnostic| d.range.begin_pos,
+            |d: &mut Diagnostic, delta| {
+                d.range = shift_range(&d.range, delta);
+                for label in d.labels.iter_mut() {
+                    label.range = shift_range(&label.range, delta);
+                }
+            },
+            (begin, splice_end, delta),
+        );
+        let comments = splice(
+            &previous.comments,
+            snippet_comments,
+            |c: &Comment| c.location.begin_pos,
+            |c: &mut Comment, delta| c.location = shift_range(&c.location, delta),
+            (begin, splice_end, delta),
+        );
+        let magic_comments = splice(
+            &previous.magic_comments,
+            snippet_magic_comments,
+            |c: &MagicComment| c.key_l.begin_pos,
+            |c: &mut MagicComment, delta| {
+                c.key_l = shift_range(&c.key_l, delta);
+                c.value_l = shift_range(&c.value_l, delta);
+            },
+            (begin, splice_end, delta),
+        );
+
+        let mut input = Input {
+            name: self.options.buffer_name.clone(),
+            ..Default::default()
+        };
+        input.set_bytes(self.source.to_vec());
+
+        Some(ParserResult {
+            ast: compstmt(statements),
+            tokens,
+            diagnostics,
+            comments,
+            magic_comments,
+            input,
+        })
+    }
+
+    fn parse_snippet(&self, snippet: &[u8], first: usize) -> Option<ParserResult> {
+        // __END__ stops parsing, so the rest of the source must be dropped
+        if snippet
+            .split(|byte| *byte == b'\n')
+            .any(|line| line == b"__END__" || line == b"__END__\r")
+        {
+            return None;
+        }
+
+        let mut parser = Parser::new(snippet, snippet_options(self.options));
+        for name in Locals::collect(self.stmts_before(first))? {
+            parser.static_env.declare(&name);
+        }
+        if first != 0 {
+            parser.set_command_start(true);
+        }
+        let result = parser.do_parse();
+
+        let has_errors = result.diagnostics.iter().any(|d| d.is_error());
+        if has_errors || has_encoding_magic_comment(&result.magic_comments) {
+            return None;
+        }
+        // magic comments are recognized only at the top of the file
+        if first != 0 && !result.magic_comments.is_empty() {
+            return None;
+        }
+
+        Some(result)
+    }
+}
+
+fn split_into_chunks(statements: &[&Node], tokens: &[Token]) -> Vec<Chunk> {
+    let mut chunks = vec![Chunk {
+        begin: 0,
+        first_token: 0,
+        first_stmt: 0,
+    }];
+
+    let mut starts = statements
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(idx, node)| (idx, node.expression().begin_pos))
+        .peekable();
+    let mut max_end = 0;
+
+    for (idx, token) in tokens.iter().enumerate() {
+        while let Some((_, stmt_begin)) = starts.peek() {
+            if *stmt_begin < token.loc.begin {
+                // statement starts in the middle of a token (or after a heredoc body)
+                starts.next();
+            } else {
+                break;
+            }
+        }
+
+        if let Some((stmt_idx, stmt_begin)) = starts.peek() {
+            // statement can be parsed on its own only if it starts after a terminator
+            // and all previous tokens (including heredoc bodies) end before it
+            if *stmt_begin == token.loc.begin
+                && max_end <= token.loc.begin
+                && idx > 0
+                && is_terminator(&tokens[idx - 1])
+            {
+                chunks.push(Chunk {
+                    begin: *stmt_begin,
+                    first_token: idx,
+                    first_stmt: *stmt_idx,
+                });
+                starts.next();
+            }
+        }
+
+        max_end = std::cmp::max(max_end, token.loc.end);
+    }
+
+    chunks
+}
+
+fn is_terminator(token: &Token) -> bool {
+    token.token_type == Lexer::tNL || token.token_type == Lexer::tSEMI
+}
+
+fn ends_with_terminator(tokens: &[Token]) -> bool {
+    match tokens {
+        [.., last, eof] if eof.token_type == Lexer::END_OF_INPUT => is_terminator(last),
+        _ => false,
+    }
+}
+
+// Options of the full parse without a custom decoder,
+// snippets with encoding magic comments are not reused anyway
+fn snippet_options(options: &ParserOptions) -> ParserOptions {
+    let ParserOptions {
+        buffer_name,
+        debug,
+        decoder: _,
+        error_recovery,
+        suppressed_warnings,
+        promoted_warnings,
+        lossless_tokens,
+        ruby_version,
+        locals,
+        context,
+        expected_tokens,
+    } = options;
+
+    ParserOptions {
+        buffer_name: buffer_name.clone(),
+        debug: *debug,
+        decoder: CustomDecoder::default(),
+        error_recovery: *error_recovery,
+        suppressed_warnings: suppressed_warnings.clone(),
+        promoted_warnings: promoted_warnings.clone(),
+        lossless_tokens: *lossless_tokens,
+        ruby_version: *ruby_version,
+        locals: locals.clone(),
+        context: context.clone(),
+        expected_tokens: *expected_tokens,
+    }
+}
+
+fn has_encoding_magic_comment(magic_comments: &[MagicComment]) -> bool {
+    magic_comments
+        .iter()
+        .any(|c| c.kind == MagicCommentKind::Encoding)
+}
+
+fn top_level_statements(ast: &Option<Node>) -> Vec<&Node> {
+    match ast {
+        Some(Node::Begin(begin)) if begin.begin_l.is_none() => begin.statements.iter().collect(),
+        Some(node) => vec![node],
+        None => vec![],
+    }
+}
+
+fn unwrap_statements(node: Node) -> Vec<Node> {
+    match node {
+        Node::Begin(begin) if begin.begin_l.is_none() => begin.statements,
+        node => vec![node],
+    }
+}
+
+fn compstmt(mut statements: Vec<Node>) -> Option<Node> {
+    match &statements[..] {
+        [] => None,
+        [_] => statements.pop(),
+        [first, .., last] => {
+            let expression_l = first.expression().join(last.expression());
+            Some(Node::Begin(Box::new(Begin {
+                statements,
+                begin_l: None,
+                end_l: None,
+                expression_l,
+            })))
+        }
+    }
+}
+
+// Takes items located before `begin` and after `end` from `previous`
+// (shifting the latter by `delta`) and puts `snippet` items (shifted by `begin`) between them.
+fn splice<T: Clone>(
+    previous: &[T],
+    snippet: Vec<T>,
+    pos: impl Fn(&T) -> usize,
+    shift: impl Fn(&mut T, isize),
+    (begin, end, delta): (usize, usize, isize),
+) -> Vec<T> {
+    let mut result = previous
+        .iter()
+        .filter(|item| pos(item) < begin)
+        .cloned()
+        .collect::<Vec<_>>();
+    for mut item in snippet {
+        shift(&mut item, begin as isize);
+        result.push(item);
+    }
+    for item in previous.iter().filter(|item| pos(item) >= end) {
+        let mut item = item.clone();
+        shift(&mut item, delta);
+        result.push(item);
+    }
+    result
+}
+
+fn shift(pos: usize, delta: isize) -> usize {
+    (pos as isize + delta) as usize
+}
+
+fn shift_range(range: &Range, delta: isize) -> Range {
+    Range::new(shift(range.begin_pos, delta), shift(range.end_pos, delta))
+}
+
+fn shift_token(mut token: Token, delta: isize) -> Token {
+    token.loc.begin = shift(token.loc.begin, delta);
+    token.loc.end = shift(token.loc.end, delta);
+    token
+}
+
+// Collects local variables declared by top-level statements,
+// returns None if it's not possible to do statically
+#[derive(Default)]
+struct Locals {
+    names: HashSet<String>,
+    unsupported: bool,
+}
+
+impl Locals {
+    fn collect(statements: &[&Node]) -> Option<HashSet<String>> {
+        let mut locals = Self::default();
+        for node in statements {
+            locals.visit(node);
+        }
+        if locals.unsupported {
+            None
+        } else {
+            Some(locals.names)
+        }
+    }
+}
+
+impl Visitor for Locals {
+    fn on_lvasgn(&mut self, node: &Lvasgn) {
+        self.names.insert(node.name.clone());
+        self.maybe_visit(&node.value)
+    }
+
+    fn on_match_var(&mut self, node: &MatchVar) {
+        self.names.insert(node.name.clone());
+    }
+
+    fn on_match_with_lvasgn(&mut self, _node: &MatchWithLvasgn) {
+        // variables are declared by named captures of the regex
+        self.unsupported = true;
+    }
+
+    // nodes below open a new scope
+    fn on_def(&mut self, _node: &Def) {}
+
+    fn on_defs(&mut self, node: &Defs) {
+        self.visit(&node.definee)
+    }
+
+    fn on_class(&mut self, node: &Class) {
+        self.visit(&node.name);
+        self.maybe_visit(&node.superclass)
+    }
+
+    fn on_module(&mut self, node: &Module) {
+        self.visit(&node.name)
+    }
+
+    fn on_sclass(&mut self, node: &SClass) {
+        self.visit(&node.expr)
+    }
+
+    fn on_block(&mut self, node: &Block) {
+        self.visit(&node.call)
+    }
+
+    fn on_numblock(&mut self, node: &Numblock) {
+        self.visit(&node.call)
+    }
+
+    fn on_itblock(&mut self, node: &Itblock) {
+        self.visit(&node.call)
+    }
+}
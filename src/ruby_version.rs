@@ -0,0 +1,45 @@
+/// Version of Ruby whose syntax is accepted by the parser
+///
+/// Constructs introduced in newer versions are rejected
+/// with `DiagnosticMessage::SyntaxNotAvailable`.
+///
+/// # Example
+/// ```rust
+/// use lib_ruby_parser::{Parser, ParserOptions, ParserResult, RubyVersion};
+///
+/// let options = ParserOptions { ruby_version: RubyVersion::new(2, 7), ..Default::default() };
+/// let ParserResult { ast, diagnostics, .. } = Parser::new(b"def foo = 42", options).do_parse();
+///
+/// assert!(ast.is_none());
+/// assert_eq!(
+///     diagnostics[0].render_message(),
+///     "endless method definition is not available in Ruby 2.7"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RubyVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl RubyVersion {
+    /// The latest supported version, used by default
+    pub const LATEST: RubyVersion = RubyVersion::new(3, 4);
+
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Default for RubyVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl std::fmt::Display for RubyVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
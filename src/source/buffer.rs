@@ -1,16 +1,66 @@
 use crate::maybe_byte::*;
-use crate::source::SourceLine;
 use crate::source::{decode_input, CustomDecoder, InputError};
+use crate::source::{OffsetMap, Range, RecognizedEncoding, SourceLine};
 use std::convert::TryFrom;
 
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerializedInput"))]
 pub struct Input {
     pub name: String,
     pub bytes: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub lines: Vec<SourceLine>,
+
+    /// Bytes of the source before decoding, `None` if the source
+    /// has not been re-encoded by a decoder
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub original_bytes: Option<Vec<u8>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub(crate) offset_map: Option<OffsetMap>,
+}
+
+// lines are not serialized, they are computed from bytes on deserialization
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedInput {
+    name: String,
+    bytes: Vec<u8>,
+    #[serde(default)]
+    original_bytes: Option<Vec<u8>>,
+    #[serde(default)]
+    pub(crate) offset_map: Option<OffsetMap>,
+}
+
+#[cfg(feature = "serde")]
+impl From<SerializedInput> for Input {
+    fn from(input: SerializedInput) -> Self {
+        let mut result = Self {
+            name: input.name,
+            original_bytes: input.original_bytes,
+            offset_map: input.offset_map,
+            ..Default::default()
+        };
+        result.set_bytes(input.bytes);
+        result
+    }
 }
 
 impl Input {
+    // Replaces bytes with decoded bytes, original bytes are kept
+    pub(crate) fn set_decoded_bytes(
+        &mut self,
+        bytes: Vec<u8>,
+        encoding: Option<&RecognizedEncoding>,
+    ) {
+        if bytes != self.bytes && self.original_bytes.is_none() {
+            let original = std::mem::take(&mut self.bytes);
+            self.offset_map = OffsetMap::new(&original, &bytes, encoding);
+            self.original_bytes = Some(original);
+        }
+        self.set_bytes(bytes);
+    }
+
     pub(crate) fn set_bytes(&mut self, bytes: Vec<u8>) {
         let mut line = SourceLine {
             start: 0,
@@ -55,22 +105,143 @@ impl Input {
         }
     }
 
-    pub fn line_col_for_pos(&self, mut pos: usize) -> Option<(usize, usize)> {
-        if pos == self.len() {
-            // EOF loc
-            let last_line = self.lines.last()?;
-            return Some((self.lines.len() - 1, last_line.len()));
+    /// Returns index of the line that contains a given position
+    pub fn line_for_pos(&self, pos: usize) -> Option<usize> {
+        if pos > self.len() {
+            return None;
         }
+        // lines[0].start is always 0 if there are any lines
+        self.lines
+            .partition_point(|line| line.start <= pos)
+            .checked_sub(1)
+    }
+
+    /// Returns (line, byte column) of a given position, both are 0-based
+    pub fn line_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        let line = self.line_for_pos(pos)?;
+        Some((line, pos - self.lines[line].start))
+    }
+
+    /// Returns a position of a given (line, byte column), reverse of `line_col_for_pos`
+    pub fn pos_for_line_col(&self, line: usize, col: usize) -> Option<usize> {
+        let line = self.lines.get(line)?;
+        let pos = line.start + col;
+        if pos <= line.line_end() {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Returns (line, char column) of a given position,
+    /// invalid UTF-8 bytes are counted as separate chars.
+    ///
+    /// Returns `None` if the position is in the middle of a multibyte char.
+    pub fn line_char_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        self.line_units_col_for_pos(pos, |_| 1)
+    }
+
+    /// Returns a position of a given (line, char column), reverse of `line_char_col_for_pos`
+    pub fn pos_for_line_char_col(&self, line: usize, col: usize) -> Option<usize> {
+        self.pos_for_line_units_col(line, col, |_| 1)
+    }
+
+    /// Returns (line, UTF-16 code unit column) of a given position
+    /// (that's how LSP clients count columns by default)
+    ///
+    /// Returns `None` if the position is in the middle of a multibyte char.
+    pub fn line_utf16_col_for_pos(&self, pos: usize) -> Option<(usize, usize)> {
+        self.line_units_col_for_pos(pos, utf16_len)
+    }
+
+    /// Returns a position of a given (line, UTF-16 code unit column),
+    /// reverse of `line_utf16_col_for_pos`
+    pub fn pos_for_line_utf16_col(&self, line: usize, col: usize) -> Option<usize> {
+        self.pos_for_line_units_col(line, col, utf16_len)
+    }
 
-        for (lineno, line) in self.lines.iter().enumerate() {
-            if line.len() > pos {
-                return Some((lineno, pos));
-            } else {
-                pos -= line.len()
+    fn line_units_col_for_pos(
+        &self,
+        pos: usize,
+        units: fn(usize) -> usize,
+    ) -> Option<(usize, usize)> {
+        let line = self.line_for_pos(pos)?;
+        let mut idx = self.lines[line].start;
+        let mut col = 0;
+        while idx < pos {
+            let len = char_len_at(&self.bytes, idx);
+            idx += len;
+            col += units(len);
+        }
+
+        if idx == pos {
+            Some((line, col))
+        } else {
+            None
+        }
+    }
+
+    fn pos_for_line_units_col(
+        &self,
+        line: usize,
+        col: usize,
+        units: fn(usize) -> usize,
+    ) -> Option<usize> {
+        let line = self.lines.get(line)?;
+        let mut idx = line.start;
+        let mut current_col = 0;
+        while current_col < col {
+            if idx >= line.line_end() {
+                return None;
+            }
+            let len = char_len_at(&self.bytes, idx);
+            idx += len;
+            current_col += units(len);
+        }
+
+        if current_col == col {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a position in `original_bytes` that corresponds
+    /// to a given position in `bytes`.
+    ///
+    /// If the source has not been re-encoded returns the same position.
+    /// Returns `None` if the position is in the middle of a multibyte char
+    /// or if the decoder changed the source beyond re-encoding
+    /// (so positions can't be mapped).
+    pub fn original_offset(&self, pos: usize) -> Option<usize> {
+        if pos > self.len() {
+            return None;
+        }
+        match &self.original_bytes {
+            None => Some(pos),
+            Some(_) => self.offset_map.as_ref()?.original_offset(pos),
+        }
+    }
+
+    /// Returns a position in `bytes` that corresponds
+    /// to a given position in `original_bytes`, reverse of `original_offset`
+    pub fn decoded_offset(&self, original_pos: usize) -> Option<usize> {
+        match &self.original_bytes {
+            None if original_pos <= self.len() => Some(original_pos),
+            None => None,
+            Some(original_bytes) if original_pos <= original_bytes.len() => {
+                self.offset_map.as_ref()?.decoded_offset(original_pos)
             }
+            Some(_) => None,
         }
+    }
 
-        None
+    /// Maps a range in `bytes` to a range in `original_bytes`, see `original_offset`
+    pub fn original_range(&self, range: &Range) -> Option<Range> {
+        Some(Range::new(
+            self.original_offset(range.begin_pos)?,
+            self.original_offset(range.end_pos)?,
+        ))
     }
 
     pub fn len(&self) -> usize {
@@ -84,6 +255,29 @@ impl Input {
     // pub fn take_bytes
 }
 
+// Length of a UTF-8 char that starts at `idx`, 1 for invalid bytes
+fn char_len_at(bytes: &[u8], idx: usize) -> usize {
+    let len = match bytes[idx] {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return 1,
+    };
+    match bytes.get(idx..idx + len) {
+        Some(char_bytes) if std::str::from_utf8(char_bytes).is_ok() => len,
+        _ => 1,
+    }
+}
+
+// Number of UTF-16 code units of a char that takes `len` bytes in UTF-8
+fn utf16_len(len: usize) -> usize {
+    if len == 4 {
+        2
+    } else {
+        1
+    }
+}
+
 impl Clone for Input {
     fn clone(&self) -> Self {
         println!("Cloning input");
@@ -91,6 +285,8 @@ impl Clone for Input {
             name: self.name.clone(),
             bytes: self.bytes.clone(),
             lines: self.lines.clone(),
+            original_bytes: self.original_bytes.clone(),
+            offset_map: self.offset_map.clone(),
         }
     }
 }
@@ -414,7 +610,8 @@ impl Buffer {
 
     pub(crate) fn set_encoding(&mut self, encoding: &str) -> Result<(), InputError> {
         let new_input = decode_input(&self.input.bytes, encoding, &self.decoder)?;
-        self.input.set_bytes(new_input);
+        self.input
+            .set_decoded_bytes(new_input, RecognizedEncoding::parse(encoding).as_ref());
         Ok(())
     }
 }
@@ -0,0 +1,197 @@
+use crate::analysis::{AstIndex, NodeId};
+use crate::source::buffer::Input;
+use crate::source::{Comment, Range};
+use crate::traverse::Visitor;
+use crate::Node;
+use std::collections::HashMap;
+
+/// Associates comments with nodes of the given AST, nodes are identified
+/// by their ids in `AstIndex::build(ast)`.
+///
+/// Comments are attached to the next node (leading comments)
+/// or to the last node that ends before them on the same line (trailing comments).
+/// Shebang, magic comment and encoding comment at the beginning of the file are skipped.
+///
+/// ```text
+/// # attached to `def foo`
+/// def foo
+///   bar # attached to `bar`
+/// end
+/// ```
+pub fn associate(ast: &Node, comments: &[Comment], input: &Input) -> HashMap<NodeId, Vec<Comment>> {
+    let mut mapping: HashMap<NodeId, Vec<Comment>> = HashMap::new();
+    for (node_id, _, comment) in Associator::run(ast, comments, input) {
+        mapping.entry(node_id).or_default().push(comment.clone());
+    }
+    mapping
+}
+
+/// Same as `associate`, but returns comments keyed by expression ranges of nodes.
+///
+/// Nodes with the same expression share an entry.
+pub fn associate_locations(
+    ast: &Node,
+    comments: &[Comment],
+    input: &Input,
+) -> HashMap<Range, Vec<Comment>> {
+    let mut mapping: HashMap<Range, Vec<Comment>> = HashMap::new();
+    for (_, range, comment) in Associator::run(ast, comments, input) {
+        mapping.entry(range).or_default().push(comment.clone());
+    }
+    mapping
+}
+
+struct Associator<'a> {
+    index: AstIndex<'a>,
+    comments: &'a [Comment],
+    input: &'a Input,
+    current: usize,
+    associated: Vec<(NodeId, Range, &'a Comment)>,
+}
+
+impl<'a> Associator<'a> {
+    fn run(
+        ast: &'a Node,
+        comments: &'a [Comment],
+        input: &'a Input,
+    ) -> Vec<(NodeId, Range, &'a Comment)> {
+        let mut associator = Self {
+            index: AstIndex::build(ast),
+            comments,
+            input,
+            current: 0,
+            associated: vec![],
+        };
+        associator.skip_directives();
+        associator.visit(ast);
+        associator.associated
+    }
+
+    fn current_comment(&self) -> Option<&'a Comment> {
+        self.comments.get(self.current)
+    }
+
+    fn current_comment_text(&self) -> Option<String> {
+        self.current_comment()?.location.source(self.input)
+    }
+
+    fn skip_directives(&mut self) {
+        let directives: [fn(&str) -> bool; 3] = [is_shebang, is_magic_comment, is_encoding_comment];
+        for directive in directives.iter() {
+            if let Some(text) = self.current_comment_text() {
+                if directive(&text) {
+                    self.current += 1;
+                }
+            }
+        }
+    }
+
+    fn associate_and_advance(&mut self, node: &Node) {
+        if let Some(comment) = self.current_comment() {
+            let id = self.index.id_of(node).expect("visited nodes are indexed");
+            self.associated
+                .push((id, node.expression().clone(), comment));
+            self.current += 1;
+        }
+    }
+
+    fn line_of(&self, pos: usize) -> usize {
+        match self.input.line_col_for_pos(pos) {
+            Some((line, _)) => line,
+            None => self.input.lines.len(),
+        }
+    }
+
+    fn current_comment_before(&self, node: &Node) -> bool {
+        match self.current_comment() {
+            Some(comment) => comment.location.end_pos <= node.expression().begin_pos,
+            None => false,
+        }
+    }
+
+    fn current_comment_before_end(&self, node: &Node) -> bool {
+        match self.current_comment() {
+            Some(comment) => comment.location.end_pos <= node.expression().end_pos,
+            None => false,
+        }
+    }
+
+    fn current_comment_decorates(&self, node: &Node) -> bool {
+        match self.current_comment() {
+            Some(comment) => {
+                self.line_of(comment.location.begin_pos) == self.line_of(node.expression().end_pos)
+            }
+            None => false,
+        }
+    }
+
+    fn process_leading_comments(&mut self, node: &Node) {
+        if let Node::Begin(_) = node {
+            return;
+        }
+        while self.current_comment_before(node) {
+            self.associate_and_advance(node)
+        }
+    }
+
+    fn process_trailing_comments(&mut self, node: &Node) {
+        while self.current_comment_before_end(node) {
+            self.associate_and_advance(node)
+        }
+        while self.current_comment_decorates(node) {
+            self.associate_and_advance(node)
+        }
+    }
+}
+
+impl Visitor for Associator<'_> {
+    fn visit(&mut self, node: &Node) {
+        self.process_leading_comments(node);
+
+        let comment = match self.current_comment() {
+            Some(comment) => comment,
+            None => return,
+        };
+
+        // heredoc bodies are located after the end of the heredoc node
+        let is_heredoc = matches!(node, Node::Heredoc(_) | Node::XHeredoc(_));
+        if is_heredoc
+            || self.line_of(comment.location.begin_pos) <= self.line_of(node.expression().end_pos)
+        {
+            self.walk(node);
+            self.process_trailing_comments(node);
+        }
+    }
+}
+
+fn is_shebang(text: &str) -> bool {
+    text.starts_with("#!")
+}
+
+fn is_magic_comment(text: &str) -> bool {
+    let text = match text.strip_prefix('#') {
+        Some(text) => text.trim_start(),
+        None => return false,
+    };
+    [
+        "-*-",
+        "frozen_string_literal:",
+        "warn_indent:",
+        "warn_past_scope:",
+    ]
+    .iter()
+    .any(|prefix| text.starts_with(prefix))
+}
+
+fn is_encoding_comment(text: &str) -> bool {
+    let text = text.to_lowercase();
+    let mut rest = &text[..];
+    while let Some(idx) = rest.find("coding") {
+        let after = rest[idx + "coding".len()..].trim_start();
+        if after.starts_with(':') || after.starts_with('=') {
+            return true;
+        }
+        rest = &rest[idx + "coding".len()..];
+    }
+    false
+}
@@ -0,0 +1,5 @@
+//! Association of comments with AST nodes, a port of `Parser::Source::Comment::Associator`
+
+mod associator;
+
+pub use associator::{associate, associate_locations};
@@ -55,8 +55,18 @@ pub fn decode_input(
             if let Some(f) = &decoder.f {
                 f(enc, input)
             } else {
-                Err(InputError::NoDecoder(enc))
+                default_decode(enc, input)
             }
         }
     }
 }
+
+#[cfg(feature = "encodings")]
+fn default_decode(enc: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    crate::source::encodings::decode(enc, input)
+}
+
+#[cfg(not(feature = "encodings"))]
+fn default_decode(enc: RecognizedEncoding, _input: &[u8]) -> Result<Vec<u8>, InputError> {
+    Err(InputError::NoDecoder(enc))
+}
@@ -0,0 +1,96 @@
+use crate::source::{InputError, RecognizedEncoding};
+use encoding_rs::Encoding;
+
+/// Decodes `input` from a given encoding to UTF-8 using built-in decoders,
+/// returns `InputError::NoDecoder` if the encoding is not supported.
+///
+/// Used by the parser if `ParserOptions::decoder` is not set,
+/// but can be also called from a custom decoder as a fallback.
+pub fn decode(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    let decoder: &'static Encoding = match encoding {
+        // US-ASCII is a subset of UTF-8
+        RecognizedEncoding::US_ASCII => return Ok(input.to_vec()),
+        RecognizedEncoding::ISO_8859_1 => return Ok(decode_latin1(input)),
+
+        RecognizedEncoding::ISO_8859_2 => encoding_rs::ISO_8859_2,
+        RecognizedEncoding::ISO_8859_3 => encoding_rs::ISO_8859_3,
+        RecognizedEncoding::ISO_8859_4 => encoding_rs::ISO_8859_4,
+        RecognizedEncoding::ISO_8859_5 => encoding_rs::ISO_8859_5,
+        RecognizedEncoding::ISO_8859_6 => encoding_rs::ISO_8859_6,
+        RecognizedEncoding::ISO_8859_7 => encoding_rs::ISO_8859_7,
+        RecognizedEncoding::ISO_8859_8 => encoding_rs::ISO_8859_8,
+        // Windows-1254 and Windows-874 are supersets of ISO-8859-9 and ISO-8859-11
+        // that have printable characters in place of 0x80-0x9F control codes
+        RecognizedEncoding::ISO_8859_9 => {
+            return decode_with_c1_controls(encoding_rs::WINDOWS_1254, input)
+        }
+        RecognizedEncoding::ISO_8859_10 => encoding_rs::ISO_8859_10,
+        RecognizedEncoding::ISO_8859_11 => {
+            return decode_with_c1_controls(encoding_rs::WINDOWS_874, input)
+        }
+        RecognizedEncoding::ISO_8859_13 => encoding_rs::ISO_8859_13,
+        RecognizedEncoding::ISO_8859_14 => encoding_rs::ISO_8859_14,
+        RecognizedEncoding::ISO_8859_15 => encoding_rs::ISO_8859_15,
+        RecognizedEncoding::ISO_8859_16 => encoding_rs::ISO_8859_16,
+
+        RecognizedEncoding::Windows_1250 => encoding_rs::WINDOWS_1250,
+        RecognizedEncoding::Windows_1251 => encoding_rs::WINDOWS_1251,
+        RecognizedEncoding::Windows_1252 => encoding_rs::WINDOWS_1252,
+        RecognizedEncoding::Windows_1253 => encoding_rs::WINDOWS_1253,
+        RecognizedEncoding::Windows_1254 => encoding_rs::WINDOWS_1254,
+        RecognizedEncoding::Windows_1255 => encoding_rs::WINDOWS_1255,
+        RecognizedEncoding::Windows_1256 => encoding_rs::WINDOWS_1256,
+        RecognizedEncoding::Windows_1257 => encoding_rs::WINDOWS_1257,
+        RecognizedEncoding::Windows_1258 => encoding_rs::WINDOWS_1258,
+        RecognizedEncoding::Windows_874 => encoding_rs::WINDOWS_874,
+
+        RecognizedEncoding::Shift_JIS | RecognizedEncoding::Windows_31J => encoding_rs::SHIFT_JIS,
+        RecognizedEncoding::EUC_JP | RecognizedEncoding::EucJP_ms | RecognizedEncoding::CP51932 => {
+            encoding_rs::EUC_JP
+        }
+
+        RecognizedEncoding::KOI8_R => encoding_rs::KOI8_R,
+        RecognizedEncoding::KOI8_U => encoding_rs::KOI8_U,
+        RecognizedEncoding::IBM866 => encoding_rs::IBM866,
+
+        encoding => return Err(InputError::NoDecoder(encoding)),
+    };
+
+    decode_with(decoder, input).map(String::into_bytes)
+}
+
+fn decode_with(decoder: &'static Encoding, input: &[u8]) -> Result<String, InputError> {
+    match decoder.decode_without_bom_handling_and_without_replacement(input) {
+        Some(decoded) => Ok(decoded.into_owned()),
+        None => Err(InputError::DecodingError(format!(
+            "invalid byte sequence in {}",
+            decoder.name()
+        ))),
+    }
+}
+
+// Decodes a single-byte encoding, mapping 0x80-0x9F bytes to U+0080-U+009F
+fn decode_with_c1_controls(
+    decoder: &'static Encoding,
+    input: &[u8],
+) -> Result<Vec<u8>, InputError> {
+    let decoded = decode_with(decoder, input)?;
+    Ok(decoded
+        .chars()
+        .zip(input.iter())
+        .map(|(c, byte)| match byte {
+            0x80..=0x9F => *byte as char,
+            _ => c,
+        })
+        .collect::<String>()
+        .into_bytes())
+}
+
+// ISO-8859-1 maps bytes to the first 256 codepoints
+fn decode_latin1(input: &[u8]) -> Vec<u8> {
+    input
+        .iter()
+        .map(|byte| *byte as char)
+        .collect::<String>()
+        .into_bytes()
+}
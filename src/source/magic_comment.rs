@@ -1,6 +1,7 @@
 use crate::source::Range;
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MagicCommentKind {
     Encoding,
     FrozenStringLiteral,
@@ -8,6 +9,7 @@ pub enum MagicCommentKind {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MagicComment {
     pub kind: MagicCommentKind,
     pub key_l: Range,
@@ -1,16 +1,24 @@
 pub mod buffer;
 mod comment;
+pub mod comments;
 mod decoder;
+#[cfg(feature = "encodings")]
+pub mod encodings;
 mod magic_comment;
+mod offset_map;
 mod range;
 mod recognized_encoding;
+pub mod rewriter;
 mod source_line;
+mod text_edit;
 
 pub use comment::{Comment, CommentType};
 pub(crate) use decoder::decode_input;
 pub use decoder::CustomDecoder;
 pub use decoder::InputError;
 pub use magic_comment::{MagicComment, MagicCommentKind};
+pub(crate) use offset_map::OffsetMap;
 pub use range::Range;
 pub use recognized_encoding::RecognizedEncoding;
 pub(crate) use source_line::SourceLine;
+pub use text_edit::TextEdit;
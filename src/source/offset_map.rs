@@ -0,0 +1,209 @@
+use crate::source::RecognizedEncoding;
+
+// Mapping between positions in decoded (UTF-8) and original (pre-decoding) bytes.
+//
+// ASCII bytes are the same in both buffers, so only non-ASCII chars are stored.
+// If a run of non-ASCII chars can't be split into the same number of chars
+// on both sides it's stored as a single span.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct OffsetMap {
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct Span {
+    decoded: usize,
+    decoded_len: usize,
+    original: usize,
+    original_len: usize,
+}
+
+impl OffsetMap {
+    // Returns None if ASCII parts of given buffers don't match,
+    // i.e. the decoder did something more than re-encoding
+    pub(crate) fn new(
+        original: &[u8],
+        decoded: &[u8],
+        encoding: Option<&RecognizedEncoding>,
+    ) -> Option<Self> {
+        let mut spans = vec![];
+        let mut o = 0;
+        let mut d = 0;
+
+        loop {
+            match (original.get(o), decoded.get(d)) {
+                (None, None) => return Some(Self { spans }),
+                (Some(a), Some(b)) if a.is_ascii() && b.is_ascii() => {
+                    if a != b {
+                        return None;
+                    }
+                    o += 1;
+                    d += 1;
+                }
+                (Some(a), Some(b)) if !a.is_ascii() && !b.is_ascii() => {
+                    let original_chars = chars_of_run(original, o, |idx| {
+                        original_char_len(encoding, original, idx)
+                    });
+                    let decoded_chars = chars_of_run(decoded, d, |idx| utf8_char_len(decoded[idx]));
+                    let original_end = run_end(&original_chars, o);
+                    let decoded_end = run_end(&decoded_chars, d);
+
+                    if original_chars.len() == decoded_chars.len() {
+                        for ((original, original_len), (decoded, decoded_len)) in
+                            original_chars.into_iter().zip(decoded_chars)
+                        {
+                            spans.push(Span {
+                                decoded,
+                                decoded_len,
+                                original,
+                                original_len,
+                            })
+                        }
+                    } else {
+                        spans.push(Span {
+                            decoded: d,
+                            decoded_len: decoded_end - d,
+                            original: o,
+                            original_len: original_end - o,
+                        })
+                    }
+
+                    o = original_end;
+                    d = decoded_end;
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    pub(crate) fn original_offset(&self, pos: usize) -> Option<usize> {
+        map_offset(
+            &self.spans,
+            pos,
+            |span| (span.decoded, span.decoded_len),
+            |span| (span.original, span.original_len),
+        )
+    }
+
+    pub(crate) fn decoded_offset(&self, pos: usize) -> Option<usize> {
+        map_offset(
+            &self.spans,
+            pos,
+            |span| (span.original, span.original_len),
+            |span| (span.decoded, span.decoded_len),
+        )
+    }
+}
+
+fn map_offset<From, To>(spans: &[Span], pos: usize, from: From, to: To) -> Option<usize>
+where
+    From: Fn(&Span) -> (usize, usize),
+    To: Fn(&Span) -> (usize, usize),
+{
+    let idx = spans.partition_point(|span| from(span).0 <= pos);
+    if idx == 0 {
+        return Some(pos);
+    }
+
+    let (from_start, from_len) = from(&spans[idx - 1]);
+    let (to_start, to_len) = to(&spans[idx - 1]);
+    if pos == from_start {
+        Some(to_start)
+    } else if pos < from_start + from_len {
+        // in the middle of a char
+        None
+    } else {
+        Some(to_start + to_len + (pos - from_start - from_len))
+    }
+}
+
+// (start, len) of consecutive non-ASCII chars starting at `start`
+fn chars_of_run<F>(bytes: &[u8], start: usize, char_len: F) -> Vec<(usize, usize)>
+where
+    F: Fn(usize) -> usize,
+{
+    let mut chars = vec![];
+    let mut idx = start;
+    while idx < bytes.len() && !bytes[idx].is_ascii() {
+        let len = char_len(idx).max(1).min(bytes.len() - idx);
+        chars.push((idx, len));
+        idx += len;
+    }
+    chars
+}
+
+fn run_end(chars: &[(usize, usize)], start: usize) -> usize {
+    chars
+        .last()
+        .map(|(begin, len)| begin + len)
+        .unwrap_or(start)
+}
+
+fn utf8_char_len(byte: u8) -> usize {
+    match byte {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xFF => 4,
+        _ => 1,
+    }
+}
+
+// Length of a char that starts with a non-ASCII byte at `idx` in a given encoding
+fn original_char_len(encoding: Option<&RecognizedEncoding>, bytes: &[u8], idx: usize) -> usize {
+    let byte = bytes[idx];
+    let encoding = match encoding {
+        Some(encoding) => encoding,
+        None => return 1,
+    };
+
+    match encoding {
+        RecognizedEncoding::Shift_JIS
+        | RecognizedEncoding::Windows_31J
+        | RecognizedEncoding::MacJapanese
+        | RecognizedEncoding::SJIS_DoCoMo
+        | RecognizedEncoding::SJIS_KDDI
+        | RecognizedEncoding::SJIS_SoftBank => match byte {
+            0x81..=0x9F | 0xE0..=0xFC => 2,
+            _ => 1,
+        },
+        RecognizedEncoding::EUC_JP
+        | RecognizedEncoding::EucJP_ms
+        | RecognizedEncoding::CP51932
+        | RecognizedEncoding::EUC_JIS_2004 => match byte {
+            0x8F => 3,
+            0x8E | 0xA1..=0xFE => 2,
+            _ => 1,
+        },
+        RecognizedEncoding::EUC_TW => match byte {
+            0x8E => 4,
+            0xA1..=0xFE => 2,
+            _ => 1,
+        },
+        RecognizedEncoding::GB18030 => match bytes.get(idx + 1) {
+            Some(0x30..=0x39) => 4,
+            _ => 2,
+        },
+        RecognizedEncoding::EUC_KR
+        | RecognizedEncoding::CP949
+        | RecognizedEncoding::GBK
+        | RecognizedEncoding::GB2312
+        | RecognizedEncoding::GB12345
+        | RecognizedEncoding::Big5
+        | RecognizedEncoding::Big5_HKSCS
+        | RecognizedEncoding::Big5_UAO
+        | RecognizedEncoding::CP950
+        | RecognizedEncoding::CP951 => match byte {
+            0x81..=0xFE => 2,
+            _ => 1,
+        },
+        RecognizedEncoding::UTF_8
+        | RecognizedEncoding::UTF8_MAC
+        | RecognizedEncoding::CESU_8
+        | RecognizedEncoding::UTF8_DoCoMo
+        | RecognizedEncoding::UTF8_KDDI
+        | RecognizedEncoding::UTF8_SoftBank => utf8_char_len(byte),
+        _ => 1,
+    }
+}
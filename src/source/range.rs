@@ -1,23 +1,32 @@
 use crate::source::buffer::Input;
 use std::convert::TryInto;
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range {
     pub begin_pos: usize,
     pub end_pos: usize,
 }
 
-impl PartialEq for Range {
-    fn eq(&self, other: &Self) -> bool {
-        self.begin_pos == other.begin_pos && self.end_pos == other.end_pos
-    }
-}
-
 impl Range {
     pub fn new(begin_pos: usize, end_pos: usize) -> Self {
         Self { begin_pos, end_pos }
     }
 
+    /// Returns a marker range for nodes that don't come from the source,
+    /// see `NodeFactory`.
+    ///
+    /// Synthetic ranges have no source, position or line: `source` and
+    /// `*_line_col` return `None`, `adjust_*` and `resize` keep them synthetic.
+    pub fn synthetic() -> Self {
+        Self::new(usize::MAX, usize::MAX)
+    }
+
+    /// Returns true if the range doesn't point to the source
+    pub fn is_synthetic(&self) -> bool {
+        self.begin_pos == usize::MAX && self.end_pos == usize::MAX
+    }
+
     pub fn validate(&self, input: &Input) {
         if cfg!(debug_assertions) {
             if input.bytes.last().unwrap() != &100 {
@@ -69,6 +78,9 @@ impl Range {
     }
 
     pub fn adjust_begin(&self, d: i32) -> Self {
+        if self.is_synthetic() {
+            return self.clone();
+        }
         let begin_pos: i32 = self
             .begin_pos
             .try_into()
@@ -80,6 +92,9 @@ impl Range {
     }
 
     pub fn adjust_end(&self, d: i32) -> Self {
+        if self.is_synthetic() {
+            return self.clone();
+        }
         let end_pos: i32 = self
             .end_pos
             .try_into()
@@ -91,10 +106,19 @@ impl Range {
     }
 
     pub fn resize(&self, new_size: usize) -> Self {
+        if self.is_synthetic() {
+            return self.clone();
+        }
         self.with_end(self.begin_pos + new_size)
     }
 
     pub fn join(&self, other: &Self) -> Self {
+        if self.is_synthetic() {
+            return other.clone();
+        }
+        if other.is_synthetic() {
+            return self.clone();
+        }
         Self::new(
             std::cmp::min(self.begin_pos, other.begin_pos),
             std::cmp::max(self.end_pos, other.end_pos),
@@ -113,10 +137,16 @@ impl Range {
     }
 
     pub fn begin_line_col(&self, input: &Input) -> Option<(usize, usize)> {
+        if self.is_synthetic() {
+            return None;
+        }
         input.line_col_for_pos(self.begin_pos)
     }
 
     pub fn end_line_col(&self, input: &Input) -> Option<(usize, usize)> {
+        if self.is_synthetic() {
+            return None;
+        }
         input.line_col_for_pos(self.end_pos)
     }
 
@@ -128,11 +158,18 @@ impl Range {
     }
 
     pub fn source(&self, input: &Input) -> Option<String> {
+        if self.is_synthetic() {
+            return None;
+        }
         let bytes = input.substr_at(self.begin_pos, self.end_pos)?;
         Some(String::from_utf8_lossy(bytes).into_owned())
     }
 
     pub(crate) fn print(&self, name: &str) {
+        if self.is_synthetic() {
+            println!("(synthetic) {}", name);
+            return;
+        }
         println!(
             "{}{} {}",
             " ".repeat(self.begin_pos),
@@ -144,6 +181,9 @@ impl Range {
 
 impl std::fmt::Debug for Range {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_synthetic() {
+            return f.write_str("synthetic");
+        }
         f.write_str(&format!("{}...{}", self.begin_pos, self.end_pos))
     }
 }
@@ -0,0 +1,326 @@
+use super::policy::{ConflictKind, Enforcer, RewriterError};
+use crate::source::Range;
+
+// A node of the tree of actions, a port of `Parser::Source::TreeRewriter::Action`.
+//
+// Invariants:
+//   + children are sorted and don't overlap (empty ranges are disjoint from everything)
+//   + every child range is contained in `range` and is not equal to it
+//   + if `replacement` is set there are no children
+#[derive(Debug, Clone)]
+pub(crate) struct Action {
+    range: Range,
+    insert_before: Vec<u8>,
+    replacement: Option<Vec<u8>>,
+    insert_after: Vec<u8>,
+    children: Vec<Action>,
+}
+
+enum Family {
+    // action is disjoint from all children
+    Sibling,
+    // child at `left` (the only one in `left..right`) contains action
+    Parent,
+    // action contains `left..right` children,
+    // first and/or last of them may overlap with it instead
+    Children { fuse_first: bool, fuse_last: bool },
+}
+
+impl Action {
+    pub(crate) fn new(
+        range: Range,
+        insert_before: Vec<u8>,
+        replacement: Option<Vec<u8>>,
+        insert_after: Vec<u8>,
+    ) -> Self {
+        Self {
+            range,
+            insert_before,
+            replacement,
+            insert_after,
+            children: vec![],
+        }
+    }
+
+    pub(crate) fn combine(self, action: Action, e: &mut Enforcer) -> Result<Self, RewriterError> {
+        if action.is_empty() {
+            return Ok(self);
+        }
+        self.do_combine(action, e)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.insert_before.is_empty()
+            && self.insert_after.is_empty()
+            && self.children.is_empty()
+            && match &self.replacement {
+                None => true,
+                Some(replacement) => replacement.is_empty() && self.range.is_empty(),
+            }
+    }
+
+    pub(crate) fn ordered_replacements(&self) -> Vec<(Range, &[u8])> {
+        let mut result = vec![];
+        if !self.insert_before.is_empty() {
+            result.push((self.range.begin(), &self.insert_before[..]));
+        }
+        if let Some(replacement) = &self.replacement {
+            result.push((self.range.clone(), &replacement[..]));
+        }
+        for child in self.children.iter() {
+            result.append(&mut child.ordered_replacements());
+        }
+        if !self.insert_after.is_empty() {
+            result.push((self.range.end(), &self.insert_after[..]));
+        }
+        result
+    }
+
+    fn is_insertion(&self) -> bool {
+        !self.insert_before.is_empty()
+            || !self.insert_after.is_empty()
+            || self.replacement.as_ref().map(|r| !r.is_empty()) == Some(true)
+    }
+
+    fn nested_actions(&self) -> Vec<&Action> {
+        let mut result = vec![self];
+        for child in self.children.iter() {
+            result.append(&mut child.nested_actions());
+        }
+        result
+    }
+
+    // Equivalent of `Action#with`, children of a replacement are swallowed
+    fn with_children(
+        mut self,
+        children: Vec<Action>,
+        e: &mut Enforcer,
+    ) -> Result<Self, RewriterError> {
+        self.children = if self.replacement.is_some() {
+            self.swallow(children, e)?
+        } else {
+            children
+        };
+        Ok(self)
+    }
+
+    fn do_combine(self, action: Action, e: &mut Enforcer) -> Result<Self, RewriterError> {
+        if action.range == self.range {
+            self.merge(action, e)
+        } else {
+            self.place_in_hierarchy(action, e)
+        }
+    }
+
+    fn place_in_hierarchy(
+        mut self,
+        mut action: Action,
+        e: &mut Enforcer,
+    ) -> Result<Self, RewriterError> {
+        let (left, right, family) = self.analyse_hierarchy(&action, e)?;
+
+        let mut left_siblings = std::mem::take(&mut self.children);
+        let mut right_siblings = left_siblings.split_off(right);
+        let mut middle = left_siblings.split_off(left);
+
+        let extra_sibling = match family {
+            Family::Sibling => action,
+            Family::Parent => {
+                let parent = middle.remove(0);
+                parent.do_combine(action, e)?
+            }
+            Family::Children {
+                fuse_first,
+                fuse_last,
+            } if fuse_first || fuse_last => {
+                let mut fusible = vec![];
+                if fuse_last {
+                    fusible.push(middle.pop().unwrap());
+                }
+                if fuse_first {
+                    fusible.push(middle.remove(0));
+                }
+                let mut other_siblings = left_siblings;
+                other_siblings.append(&mut middle);
+                other_siblings.append(&mut right_siblings);
+                return self.fuse_deletions(action, fusible, other_siblings, e);
+            }
+            Family::Children { .. } => {
+                let more_children = std::mem::take(&mut action.children);
+                action
+                    .with_children(middle, e)?
+                    .combine_children(more_children, e)?
+            }
+        };
+
+        let mut children = left_siblings;
+        children.push(extra_sibling);
+        children.append(&mut right_siblings);
+        self.with_children(children, e)
+    }
+
+    fn combine_children(
+        self,
+        more_children: Vec<Action>,
+        e: &mut Enforcer,
+    ) -> Result<Self, RewriterError> {
+        let mut parent = self;
+        for child in more_children {
+            parent = parent.place_in_hierarchy(child, e)?;
+        }
+        Ok(parent)
+    }
+
+    fn fuse_deletions(
+        self,
+        mut action: Action,
+        fusible: Vec<Action>,
+        other_siblings: Vec<Action>,
+        e: &mut Enforcer,
+    ) -> Result<Self, RewriterError> {
+        let without_fusible = self.with_children(other_siblings, e)?;
+        let fused_range = fusible.iter().fold(action.range.clone(), |range, child| {
+            range.join(&child.range)
+        });
+        action.range = fused_range;
+        let children = std::mem::take(&mut action.children);
+        let fused_deletion = action.with_children(children, e)?;
+        without_fusible.do_combine(fused_deletion, e)
+    }
+
+    fn child_index_from<F>(&self, from: usize, predicate: F) -> usize
+    where
+        F: Fn(&Action) -> bool,
+    {
+        self.children[from..]
+            .iter()
+            .position(predicate)
+            .map(|idx| idx + from)
+            .unwrap_or_else(|| self.children.len())
+    }
+
+    // Returns `left..right` bounds of children that are not disjoint from `action`
+    // and how `action` relates to them.
+    // In case a child has equal range to `action`, it is returned as a parent.
+    // An empty range 1...1 is considered disjoint from 1...10.
+    fn analyse_hierarchy(
+        &self,
+        action: &Action,
+        e: &mut Enforcer,
+    ) -> Result<(usize, usize, Family), RewriterError> {
+        let r = &action.range;
+        // index of the first child that isn't completely to the left of action
+        let mut left = self.child_index_from(0, |child| child.range.end_pos > r.begin_pos);
+        // index of the first child that is completely to the right of action,
+        // see the corner case below for the reason of `- 1`
+        let start = if left == 0 { 0 } else { left - 1 };
+        let mut right = self.child_index_from(start, |child| child.range.begin_pos >= r.end_pos);
+
+        let family = if right == left {
+            // all children are disjoint from action
+            Family::Sibling
+        } else if right + 1 == left {
+            // Corner case: if a child has empty range == action's range
+            // then it will appear to be both disjoint and to the left of action,
+            // as well as disjoint and to the right of action.
+            // Since ranges are equal, we return it as a parent
+            left -= 1;
+            right += 1;
+            Family::Parent
+        } else {
+            let first = &self.children[left];
+            let last = &self.children[right - 1];
+            let overlaps_left = first.range.begin_pos < r.begin_pos;
+            let overlaps_right = last.range.end_pos > r.end_pos;
+
+            if right - left == 1
+                && first.range.begin_pos <= r.begin_pos
+                && first.range.end_pos >= r.end_pos
+            {
+                Family::Parent
+            } else {
+                // a single child can't overlap with both sides (it would be a parent)
+                if overlaps_left {
+                    Self::check_fusible(action, first, e)?;
+                }
+                if overlaps_right {
+                    Self::check_fusible(action, last, e)?;
+                }
+                Family::Children {
+                    fuse_first: overlaps_left,
+                    fuse_last: overlaps_right,
+                }
+            }
+        };
+
+        Ok((left, right, family))
+    }
+
+    fn check_fusible(
+        action: &Action,
+        child: &Action,
+        e: &mut Enforcer,
+    ) -> Result<(), RewriterError> {
+        let kind = if action.is_insertion() || child.is_insertion() {
+            ConflictKind::CrossingInsertions
+        } else {
+            ConflictKind::CrossingDeletions
+        };
+        e.enforce(kind, || {
+            Some((action.range.clone(), vec![child.range.clone()]))
+        })
+    }
+
+    fn merge(self, mut action: Action, e: &mut Enforcer) -> Result<Self, RewriterError> {
+        e.enforce(ConflictKind::DifferentReplacements, || {
+            match (&self.replacement, &action.replacement) {
+                (Some(replacement), Some(other)) if replacement != other => {
+                    Some((self.range.clone(), vec![self.range.clone()]))
+                }
+                _ => None,
+            }
+        })?;
+
+        let more_children = std::mem::take(&mut action.children);
+        let Action {
+            range,
+            mut insert_before,
+            replacement,
+            mut insert_after,
+            children,
+        } = self;
+        action.insert_before.append(&mut insert_before);
+        insert_after.append(&mut action.insert_after);
+
+        Self {
+            range,
+            insert_before: action.insert_before,
+            replacement: action.replacement.or(replacement),
+            insert_after,
+            children: vec![],
+        }
+        .with_children(children, e)?
+        .combine_children(more_children, e)
+    }
+
+    fn swallow(
+        &self,
+        children: Vec<Action>,
+        e: &mut Enforcer,
+    ) -> Result<Vec<Action>, RewriterError> {
+        e.enforce(ConflictKind::SwallowedInsertions, || {
+            let insertions = children
+                .iter()
+                .flat_map(|child| child.nested_actions())
+                .filter(|action| action.is_insertion())
+                .map(|action| action.range.clone())
+                .collect::<Vec<_>>();
+            if insertions.is_empty() {
+                None
+            } else {
+                Some((self.range.clone(), insertions))
+            }
+        })?;
+        Ok(vec![])
+    }
+}
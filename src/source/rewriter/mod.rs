@@ -0,0 +1,6 @@
+mod action;
+mod policy;
+mod tree_rewriter;
+
+pub use policy::{Conflict, ConflictKind, Policy, RewriterError, RewriterPolicy};
+pub use tree_rewriter::TreeRewriter;
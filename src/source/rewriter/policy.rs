@@ -0,0 +1,134 @@
+use crate::source::Range;
+use std::error::Error;
+use std::fmt;
+
+/// Controls what happens when two actions of `TreeRewriter` clash
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Policy {
+    /// Silently merge conflicting actions
+    Accept,
+    /// Merge conflicting actions, but record a `Conflict` (see `TreeRewriter::warnings`)
+    Warn,
+    /// Reject the action that caused the conflict with `RewriterError::Clobbering`
+    Raise,
+}
+
+/// Per-kind conflict policies of `TreeRewriter`,
+/// mirrors options of `Parser::Source::TreeRewriter` from whitequark/parser
+#[derive(Debug, Clone)]
+pub struct RewriterPolicy {
+    /// Overlapping (but not nested) removals/replacements, merged into a single removal
+    pub crossing_deletions: Policy,
+    /// Two different replacements of the same range, the last one wins
+    pub different_replacements: Policy,
+    /// Insertions inside of a range that is (or gets) replaced, insertions are dropped
+    pub swallowed_insertions: Policy,
+}
+
+impl Default for RewriterPolicy {
+    fn default() -> Self {
+        Self {
+            crossing_deletions: Policy::Accept,
+            different_replacements: Policy::Raise,
+            swallowed_insertions: Policy::Raise,
+        }
+    }
+}
+
+impl RewriterPolicy {
+    fn policy_for(&self, kind: &ConflictKind) -> Policy {
+        match kind {
+            ConflictKind::CrossingDeletions => self.crossing_deletions,
+            ConflictKind::DifferentReplacements => self.different_replacements,
+            ConflictKind::SwallowedInsertions => self.swallowed_insertions,
+            // insertions can't be merged with overlapping actions
+            ConflictKind::CrossingInsertions => Policy::Raise,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConflictKind {
+    CrossingDeletions,
+    CrossingInsertions,
+    DifferentReplacements,
+    SwallowedInsertions,
+}
+
+/// Information about two (or more) clashing actions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    /// Range of the action that has caused a conflict
+    pub range: Range,
+    /// Ranges of existing actions that conflict with it
+    pub conflicts: Vec<Range>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RewriterError {
+    /// Given range is synthetic or outside of the rewritten input
+    OutOfBounds(Range),
+    /// Action conflicts with existing actions and policy for it is `Policy::Raise`
+    Clobbering(Conflict),
+}
+
+impl fmt::Display for RewriterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds(range) => {
+                write!(f, "range {:?} is outside the bounds of the source", range)
+            }
+            Self::Clobbering(conflict) => write!(
+                f,
+                "{:?} detected: {:?} conflicts with {:?}",
+                conflict.kind, conflict.range, conflict.conflicts
+            ),
+        }
+    }
+}
+
+impl Error for RewriterError {}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Enforcer {
+    pub(crate) policy: RewriterPolicy,
+    pub(crate) warnings: Vec<Conflict>,
+}
+
+impl Enforcer {
+    pub(crate) fn new(policy: RewriterPolicy) -> Self {
+        Self {
+            policy,
+            warnings: vec![],
+        }
+    }
+
+    // `f` is called only if the policy is not `Accept`,
+    // so it's fine to do some expensive lookup there
+    pub(crate) fn enforce<F>(&mut self, kind: ConflictKind, f: F) -> Result<(), RewriterError>
+    where
+        F: FnOnce() -> Option<(Range, Vec<Range>)>,
+    {
+        let policy = self.policy.policy_for(&kind);
+        if policy == Policy::Accept {
+            return Ok(());
+        }
+        let (range, conflicts) = match f() {
+            Some(conflict) => conflict,
+            None => return Ok(()),
+        };
+        let conflict = Conflict {
+            kind,
+            range,
+            conflicts,
+        };
+        match policy {
+            Policy::Raise => Err(RewriterError::Clobbering(conflict)),
+            _ => {
+                self.warnings.push(conflict);
+                Ok(())
+            }
+        }
+    }
+}
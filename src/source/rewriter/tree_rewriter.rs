@@ -0,0 +1,123 @@
+use super::action::Action;
+use super::policy::{Conflict, Enforcer, RewriterError, RewriterPolicy};
+use crate::source::buffer::Input;
+use crate::source::Range;
+
+/// Rewrites source code by applying a set of actions keyed by `Range`.
+///
+/// A port of `Parser::Source::TreeRewriter` from whitequark/parser:
+/// actions are combined into a tree where nested ranges become children,
+/// so wrapping/replacing a node and rewriting its children can be done in any order.
+/// Clashing actions are handled according to `RewriterPolicy`.
+///
+/// ```text
+/// let mut rewriter = TreeRewriter::new(&result.input);
+/// rewriter.wrap(node.expression(), "(", ")")?;
+/// let new_source: Vec<u8> = rewriter.process();
+/// ```
+#[derive(Debug)]
+pub struct TreeRewriter<'a> {
+    input: &'a Input,
+    root: Action,
+    enforcer: Enforcer,
+}
+
+impl<'a> TreeRewriter<'a> {
+    /// Constructs a rewriter with a default policy
+    /// (crossing deletions are accepted, other conflicts are rejected)
+    pub fn new(input: &'a Input) -> Self {
+        Self::with_policy(input, RewriterPolicy::default())
+    }
+
+    /// Constructs a rewriter with a given policy
+    pub fn with_policy(input: &'a Input, policy: RewriterPolicy) -> Self {
+        Self {
+            input,
+            root: Action::new(Range::new(0, input.len()), vec![], None, vec![]),
+            enforcer: Enforcer::new(policy),
+        }
+    }
+
+    /// Replaces the code of the range with `content`
+    pub fn replace(&mut self, range: &Range, content: &str) -> Result<(), RewriterError> {
+        self.combine(range, vec![], Some(content.as_bytes().to_vec()), vec![])
+    }
+
+    /// Inserts the given strings before and after the given range
+    pub fn wrap(&mut self, range: &Range, before: &str, after: &str) -> Result<(), RewriterError> {
+        self.combine(
+            range,
+            before.as_bytes().to_vec(),
+            None,
+            after.as_bytes().to_vec(),
+        )
+    }
+
+    /// Removes the source range
+    pub fn remove(&mut self, range: &Range) -> Result<(), RewriterError> {
+        self.replace(range, "")
+    }
+
+    /// Inserts `content` before the given range
+    pub fn insert_before(&mut self, range: &Range, content: &str) -> Result<(), RewriterError> {
+        self.wrap(range, content, "")
+    }
+
+    /// Inserts `content` after the given range
+    pub fn insert_after(&mut self, range: &Range, content: &str) -> Result<(), RewriterError> {
+        self.wrap(range, "", content)
+    }
+
+    /// Returns true if there are no registered actions
+    pub fn is_empty(&self) -> bool {
+        self.root.is_empty()
+    }
+
+    /// Returns conflicts that were merged under `Policy::Warn`
+    pub fn warnings(&self) -> &[Conflict] {
+        &self.enforcer.warnings
+    }
+
+    /// Applies all actions to the input and returns rewritten source
+    pub fn process(&self) -> Vec<u8> {
+        let source = &self.input.bytes;
+        let mut result = Vec::with_capacity(source.len());
+        let mut last_end = 0;
+        for (range, replacement) in self.root.ordered_replacements() {
+            result.extend_from_slice(&source[last_end..range.begin_pos]);
+            result.extend_from_slice(replacement);
+            last_end = range.end_pos;
+        }
+        result.extend_from_slice(&source[last_end..]);
+        result
+    }
+
+    fn combine(
+        &mut self,
+        range: &Range,
+        insert_before: Vec<u8>,
+        replacement: Option<Vec<u8>>,
+        insert_after: Vec<u8>,
+    ) -> Result<(), RewriterError> {
+        if range.is_synthetic()
+            || range.begin_pos > range.end_pos
+            || range.end_pos > self.input.len()
+        {
+            return Err(RewriterError::OutOfBounds(range.clone()));
+        }
+        let action = Action::new(range.clone(), insert_before, replacement, insert_after);
+
+        // rejected action must not leave any traces
+        let warnings_count = self.enforcer.warnings.len();
+        match self.root.clone().combine(action, &mut self.enforcer) {
+            Ok(root) => {
+                self.root = root;
+                Ok(())
+            }
+            Err(err) => {
+                self.enforcer.warnings.truncate(warnings_count);
+                Err(err)
+            }
+        }
+    }
+}
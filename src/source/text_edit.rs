@@ -0,0 +1,46 @@
+use crate::source::Range;
+
+/// A single edit of the source: replaces bytes in `range` with `replacement`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub range: Range,
+    pub replacement: Vec<u8>,
+}
+
+impl TextEdit {
+    pub fn new(range: Range, replacement: &[u8]) -> Self {
+        Self {
+            range,
+            replacement: replacement.to_vec(),
+        }
+    }
+
+    pub fn insert(pos: usize, content: &[u8]) -> Self {
+        Self::new(Range::new(pos, pos), content)
+    }
+
+    pub fn remove(range: Range) -> Self {
+        Self::new(range, b"")
+    }
+
+    /// Returns a difference between the length of the source after and before the edit
+    pub fn delta(&self) -> isize {
+        self.replacement.len() as isize - self.range.size() as isize
+    }
+
+    /// Returns `source` with the edit applied
+    pub fn apply(&self, source: &[u8]) -> Vec<u8> {
+        assert!(
+            self.range.begin_pos <= self.range.end_pos && self.range.end_pos <= source.len(),
+            "edit range {:?} is out of bounds (source length = {})",
+            self.range,
+            source.len()
+        );
+
+        let mut result = Vec::with_capacity(source.len() + self.replacement.len());
+        result.extend_from_slice(&source[..self.range.begin_pos]);
+        result.extend_from_slice(&self.replacement);
+        result.extend_from_slice(&source[self.range.end_pos..]);
+        result
+    }
+}
@@ -10,6 +10,27 @@ pub struct StaticEnvironment {
 
 const FORWARD_ARGS: &str = "FORWARD_ARGS";
 
+/// Anonymous parameter that can be forwarded with a bare `*`, `**` or `&`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AnonymousArg {
+    Restarg,
+    Kwrestarg,
+    Blockarg,
+}
+
+impl AnonymousArg {
+    fn marker(self, in_block: bool) -> &'static str {
+        match (self, in_block) {
+            (AnonymousArg::Restarg, false) => "ANONYMOUS_RESTARG",
+            (AnonymousArg::Restarg, true) => "ANONYMOUS_RESTARG_IN_BLOCK",
+            (AnonymousArg::Kwrestarg, false) => "ANONYMOUS_KWRESTARG",
+            (AnonymousArg::Kwrestarg, true) => "ANONYMOUS_KWRESTARG_IN_BLOCK",
+            (AnonymousArg::Blockarg, false) => "ANONYMOUS_BLOCKARG",
+            (AnonymousArg::Blockarg, true) => "ANONYMOUS_BLOCKARG_IN_BLOCK",
+        }
+    }
+}
+
 impl StaticEnvironment {
     pub fn new() -> Self {
         Self {
@@ -58,4 +79,16 @@ impl StaticEnvironment {
     pub(crate) fn is_forward_args_declared(&self) -> bool {
         self.is_declared(FORWARD_ARGS)
     }
+
+    pub(crate) fn declare_anonymous_arg(&self, arg: AnonymousArg, in_block: bool) {
+        self.declare(arg.marker(in_block));
+    }
+
+    pub(crate) fn is_anonymous_arg_declared(&self, arg: AnonymousArg) -> bool {
+        self.is_declared(arg.marker(false))
+    }
+
+    pub(crate) fn is_anonymous_arg_declared_in_block(&self, arg: AnonymousArg) -> bool {
+        self.is_declared(arg.marker(true))
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{parser::TokenValue, Token};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StringValue {
     pub valid: bool,
     pub bytes: Vec<u8>,
@@ -39,3 +40,12 @@ impl StringValue {
         &self.bytes
     }
 }
+
+impl From<&str> for StringValue {
+    fn from(s: &str) -> Self {
+        StringValue {
+            valid: true,
+            bytes: s.as_bytes().to_owned(),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+// Token, TokenValue and Loc are generated by bison, so serde impls are written by hand
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::parser::TokenValue;
+use crate::{token_name, Loc, Token};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "Loc")]
+struct LocDef {
+    begin: usize,
+    end: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "TokenValue")]
+enum TokenValueDef {
+    String(String),
+    InvalidString(Vec<u8>),
+}
+
+#[derive(Serialize)]
+struct SerializedToken<'a> {
+    token_type: i32,
+    token_name: String,
+    #[serde(with = "TokenValueDef")]
+    token_value: &'a TokenValue,
+    #[serde(with = "LocDef")]
+    loc: &'a Loc,
+}
+
+// token_name is ignored on deserialization
+#[derive(Deserialize)]
+struct DeserializedToken {
+    token_type: i32,
+    #[serde(with = "TokenValueDef")]
+    token_value: TokenValue,
+    #[serde(with = "LocDef")]
+    loc: Loc,
+}
+
+impl Serialize for Token {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedToken {
+            token_type: self.token_type,
+            token_name: token_name(self.token_type).to_owned(),
+            token_value: &self.token_value,
+            loc: &self.loc,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Token {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let DeserializedToken {
+            token_type,
+            token_value,
+            loc,
+        } = DeserializedToken::deserialize(deserializer)?;
+
+        Ok(Token {
+            token_type,
+            token_value,
+            loc,
+        })
+    }
+}
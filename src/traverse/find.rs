@@ -238,6 +238,10 @@ impl<'a> Visitor<Option<Node>> for Find {
         }
     }
 
+    fn on_anonymous_blockarg(&mut self, _: &AnonymousBlockarg) -> Option<Node> {
+        None
+    }
+
     fn on_arg(&mut self, _: &Arg) -> Option<Node> {
         None
     }
@@ -491,6 +495,18 @@ impl<'a> Visitor<Option<Node>> for Find {
         None
     }
 
+    fn on_forwarded_blockarg(&mut self, _: &ForwardedBlockarg) -> Option<Node> {
+        None
+    }
+
+    fn on_forwarded_kwrestarg(&mut self, _: &ForwardedKwrestarg) -> Option<Node> {
+        None
+    }
+
+    fn on_forwarded_restarg(&mut self, _: &ForwardedRestarg) -> Option<Node> {
+        None
+    }
+
     fn on_gvar(&mut self, _: &Gvar) -> Option<Node> {
         None
     }
@@ -611,6 +627,14 @@ impl<'a> Visitor<Option<Node>> for Find {
         }
     }
 
+    fn on_itblock(&mut self, node: &Itblock) -> Option<Node> {
+        match self.current_pattern() {
+            PatternItem::Call => self.find(&node.call),
+            PatternItem::Body => self.find(&node.body),
+            _ => None,
+        }
+    }
+
     fn on_ivar(&mut self, _: &Ivar) -> Option<Node> {
         None
     }
@@ -709,6 +733,14 @@ impl<'a> Visitor<Option<Node>> for Find {
         None
     }
 
+    fn on_match_pattern(&mut self, node: &MatchPattern) -> Option<Node> {
+        match self.current_pattern() {
+            PatternItem::Value => self.find(&node.value),
+            PatternItem::Pattern => self.find(&node.pattern),
+            _ => None,
+        }
+    }
+
     fn on_match_rest(&mut self, node: &MatchRest) -> Option<Node> {
         match self.current_pattern() {
             PatternItem::Name => self.maybe_find(&node.name),
@@ -0,0 +1,983 @@
+use crate::nodes::*;
+use crate::Node;
+
+pub trait Fold {
+    fn fold_all(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+        nodes.into_iter().map(|node| self.fold(node)).collect()
+    }
+
+    fn maybe_fold(&mut self, node: Option<Node>) -> Option<Node> {
+        node.map(|node| self.fold(node))
+    }
+
+    fn fold_alias(&mut self, node: Alias) -> Node {
+        Node::Alias(Box::new(Alias {
+            to: self.fold(node.to),
+            from: self.fold(node.from),
+            ..node
+        }))
+    }
+
+    fn fold_and(&mut self, node: And) -> Node {
+        Node::And(Box::new(And {
+            lhs: self.fold(node.lhs),
+            rhs: self.fold(node.rhs),
+            ..node
+        }))
+    }
+
+    fn fold_and_asgn(&mut self, node: AndAsgn) -> Node {
+        Node::AndAsgn(Box::new(AndAsgn {
+            recv: self.fold(node.recv),
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_anonymous_blockarg(&mut self, node: AnonymousBlockarg) -> Node {
+        Node::AnonymousBlockarg(Box::new(node))
+    }
+
+    fn fold_arg(&mut self, node: Arg) -> Node {
+        Node::Arg(Box::new(node))
+    }
+
+    fn fold_args(&mut self, node: Args) -> Node {
+        Node::Args(Box::new(Args {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_array(&mut self, node: Array) -> Node {
+        Node::Array(Box::new(Array {
+            elements: self.fold_all(node.elements),
+            ..node
+        }))
+    }
+
+    fn fold_array_pattern(&mut self, node: ArrayPattern) -> Node {
+        Node::ArrayPattern(Box::new(ArrayPattern {
+            elements: self.fold_all(node.elements),
+            ..node
+        }))
+    }
+
+    fn fold_array_pattern_with_tail(&mut self, node: ArrayPatternWithTail) -> Node {
+        Node::ArrayPatternWithTail(Box::new(ArrayPatternWithTail {
+            elements: self.fold_all(node.elements),
+            ..node
+        }))
+    }
+
+    fn fold_back_ref(&mut self, node: BackRef) -> Node {
+        Node::BackRef(Box::new(node))
+    }
+
+    fn fold_begin(&mut self, node: Begin) -> Node {
+        Node::Begin(Box::new(Begin {
+            statements: self.fold_all(node.statements),
+            ..node
+        }))
+    }
+
+    fn fold_block(&mut self, node: Block) -> Node {
+        Node::Block(Box::new(Block {
+            call: self.fold(node.call),
+            args: self.maybe_fold(node.args),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_blockarg(&mut self, node: Blockarg) -> Node {
+        Node::Blockarg(Box::new(node))
+    }
+
+    fn fold_block_pass(&mut self, node: BlockPass) -> Node {
+        Node::BlockPass(Box::new(BlockPass {
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_break(&mut self, node: Break) -> Node {
+        Node::Break(Box::new(Break {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_case(&mut self, node: Case) -> Node {
+        Node::Case(Box::new(Case {
+            expr: self.maybe_fold(node.expr),
+            when_bodies: self.fold_all(node.when_bodies),
+            else_body: self.maybe_fold(node.else_body),
+            ..node
+        }))
+    }
+
+    fn fold_case_match(&mut self, node: CaseMatch) -> Node {
+        Node::CaseMatch(Box::new(CaseMatch {
+            expr: self.fold(node.expr),
+            in_bodies: self.fold_all(node.in_bodies),
+            else_body: self.maybe_fold(node.else_body),
+            ..node
+        }))
+    }
+
+    fn fold_casgn(&mut self, node: Casgn) -> Node {
+        Node::Casgn(Box::new(Casgn {
+            scope: self.maybe_fold(node.scope),
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_cbase(&mut self, node: Cbase) -> Node {
+        Node::Cbase(Box::new(node))
+    }
+
+    fn fold_class(&mut self, node: Class) -> Node {
+        Node::Class(Box::new(Class {
+            name: self.fold(node.name),
+            superclass: self.maybe_fold(node.superclass),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_complex(&mut self, node: Complex) -> Node {
+        Node::Complex(Box::new(node))
+    }
+
+    fn fold_const(&mut self, node: Const) -> Node {
+        Node::Const(Box::new(Const {
+            scope: self.maybe_fold(node.scope),
+            ..node
+        }))
+    }
+
+    fn fold_const_pattern(&mut self, node: ConstPattern) -> Node {
+        Node::ConstPattern(Box::new(ConstPattern {
+            const_: self.fold(node.const_),
+            pattern: self.fold(node.pattern),
+            ..node
+        }))
+    }
+
+    fn fold_csend(&mut self, node: CSend) -> Node {
+        Node::CSend(Box::new(CSend {
+            recv: self.fold(node.recv),
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_cvar(&mut self, node: Cvar) -> Node {
+        Node::Cvar(Box::new(node))
+    }
+
+    fn fold_cvasgn(&mut self, node: Cvasgn) -> Node {
+        Node::Cvasgn(Box::new(Cvasgn {
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_def(&mut self, node: Def) -> Node {
+        Node::Def(Box::new(Def {
+            args: self.maybe_fold(node.args),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_defined(&mut self, node: Defined) -> Node {
+        Node::Defined(Box::new(Defined {
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_defs(&mut self, node: Defs) -> Node {
+        Node::Defs(Box::new(Defs {
+            definee: self.fold(node.definee),
+            args: self.maybe_fold(node.args),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_dstr(&mut self, node: Dstr) -> Node {
+        Node::Dstr(Box::new(Dstr {
+            parts: self.fold_all(node.parts),
+            ..node
+        }))
+    }
+
+    fn fold_dsym(&mut self, node: Dsym) -> Node {
+        Node::Dsym(Box::new(Dsym {
+            parts: self.fold_all(node.parts),
+            ..node
+        }))
+    }
+
+    fn fold_eflipflop(&mut self, node: EFlipFlop) -> Node {
+        Node::EFlipFlop(Box::new(EFlipFlop {
+            left: self.maybe_fold(node.left),
+            right: self.maybe_fold(node.right),
+            ..node
+        }))
+    }
+
+    fn fold_empty_else(&mut self, node: EmptyElse) -> Node {
+        Node::EmptyElse(Box::new(node))
+    }
+
+    fn fold_encoding(&mut self, node: Encoding) -> Node {
+        Node::Encoding(Box::new(node))
+    }
+
+    fn fold_ensure(&mut self, node: Ensure) -> Node {
+        Node::Ensure(Box::new(Ensure {
+            body: self.maybe_fold(node.body),
+            ensure: self.maybe_fold(node.ensure),
+            ..node
+        }))
+    }
+
+    fn fold_erange(&mut self, node: Erange) -> Node {
+        Node::Erange(Box::new(Erange {
+            left: self.maybe_fold(node.left),
+            right: self.maybe_fold(node.right),
+            ..node
+        }))
+    }
+
+    fn fold_error(&mut self, node: Error) -> Node {
+        Node::Error(Box::new(node))
+    }
+
+    fn fold_false(&mut self, node: False) -> Node {
+        Node::False(Box::new(node))
+    }
+
+    fn fold_file(&mut self, node: File) -> Node {
+        Node::File(Box::new(node))
+    }
+
+    fn fold_find_pattern(&mut self, node: FindPattern) -> Node {
+        Node::FindPattern(Box::new(FindPattern {
+            elements: self.fold_all(node.elements),
+            ..node
+        }))
+    }
+
+    fn fold_float(&mut self, node: Float) -> Node {
+        Node::Float(Box::new(node))
+    }
+
+    fn fold_for(&mut self, node: For) -> Node {
+        Node::For(Box::new(For {
+            iterator: self.fold(node.iterator),
+            iteratee: self.fold(node.iteratee),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_forward_arg(&mut self, node: ForwardArg) -> Node {
+        Node::ForwardArg(Box::new(node))
+    }
+
+    fn fold_forwarded_args(&mut self, node: ForwardedArgs) -> Node {
+        Node::ForwardedArgs(Box::new(node))
+    }
+
+    fn fold_forwarded_blockarg(&mut self, node: ForwardedBlockarg) -> Node {
+        Node::ForwardedBlockarg(Box::new(node))
+    }
+
+    fn fold_forwarded_kwrestarg(&mut self, node: ForwardedKwrestarg) -> Node {
+        Node::ForwardedKwrestarg(Box::new(node))
+    }
+
+    fn fold_forwarded_restarg(&mut self, node: ForwardedRestarg) -> Node {
+        Node::ForwardedRestarg(Box::new(node))
+    }
+
+    fn fold_gvar(&mut self, node: Gvar) -> Node {
+        Node::Gvar(Box::new(node))
+    }
+
+    fn fold_gvasgn(&mut self, node: Gvasgn) -> Node {
+        Node::Gvasgn(Box::new(Gvasgn {
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_hash(&mut self, node: Hash) -> Node {
+        Node::Hash(Box::new(Hash {
+            pairs: self.fold_all(node.pairs),
+            ..node
+        }))
+    }
+
+    fn fold_hash_pattern(&mut self, node: HashPattern) -> Node {
+        Node::HashPattern(Box::new(HashPattern {
+            elements: self.fold_all(node.elements),
+            ..node
+        }))
+    }
+
+    fn fold_heredoc(&mut self, node: Heredoc) -> Node {
+        Node::Heredoc(Box::new(Heredoc {
+            parts: self.fold_all(node.parts),
+            ..node
+        }))
+    }
+
+    fn fold_if(&mut self, node: If) -> Node {
+        Node::If(Box::new(If {
+            cond: self.fold(node.cond),
+            if_true: self.maybe_fold(node.if_true),
+            if_false: self.maybe_fold(node.if_false),
+            ..node
+        }))
+    }
+
+    fn fold_if_guard(&mut self, node: IfGuard) -> Node {
+        Node::IfGuard(Box::new(IfGuard {
+            cond: self.fold(node.cond),
+            ..node
+        }))
+    }
+
+    fn fold_iflipflop(&mut self, node: IFlipFlop) -> Node {
+        Node::IFlipFlop(Box::new(IFlipFlop {
+            left: self.maybe_fold(node.left),
+            right: self.maybe_fold(node.right),
+            ..node
+        }))
+    }
+
+    fn fold_if_mod(&mut self, node: IfMod) -> Node {
+        Node::IfMod(Box::new(IfMod {
+            cond: self.fold(node.cond),
+            if_true: self.maybe_fold(node.if_true),
+            if_false: self.maybe_fold(node.if_false),
+            ..node
+        }))
+    }
+
+    fn fold_if_ternary(&mut self, node: IfTernary) -> Node {
+        Node::IfTernary(Box::new(IfTernary {
+            cond: self.fold(node.cond),
+            if_true: self.fold(node.if_true),
+            if_false: self.fold(node.if_false),
+            ..node
+        }))
+    }
+
+    fn fold_index(&mut self, node: Index) -> Node {
+        Node::Index(Box::new(Index {
+            recv: self.fold(node.recv),
+            indexes: self.fold_all(node.indexes),
+            ..node
+        }))
+    }
+
+    fn fold_index_asgn(&mut self, node: IndexAsgn) -> Node {
+        Node::IndexAsgn(Box::new(IndexAsgn {
+            recv: self.fold(node.recv),
+            indexes: self.fold_all(node.indexes),
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_in_match(&mut self, node: InMatch) -> Node {
+        Node::InMatch(Box::new(InMatch {
+            value: self.fold(node.value),
+            pattern: self.fold(node.pattern),
+            ..node
+        }))
+    }
+
+    fn fold_in_pattern(&mut self, node: InPattern) -> Node {
+        Node::InPattern(Box::new(InPattern {
+            pattern: self.fold(node.pattern),
+            guard: self.maybe_fold(node.guard),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_int(&mut self, node: Int) -> Node {
+        Node::Int(Box::new(node))
+    }
+
+    fn fold_irange(&mut self, node: Irange) -> Node {
+        Node::Irange(Box::new(Irange {
+            left: self.maybe_fold(node.left),
+            right: self.maybe_fold(node.right),
+            ..node
+        }))
+    }
+
+    fn fold_itblock(&mut self, node: Itblock) -> Node {
+        Node::Itblock(Box::new(Itblock {
+            call: self.fold(node.call),
+            body: self.fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_ivar(&mut self, node: Ivar) -> Node {
+        Node::Ivar(Box::new(node))
+    }
+
+    fn fold_ivasgn(&mut self, node: Ivasgn) -> Node {
+        Node::Ivasgn(Box::new(Ivasgn {
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_kwarg(&mut self, node: Kwarg) -> Node {
+        Node::Kwarg(Box::new(node))
+    }
+
+    fn fold_kwbegin(&mut self, node: KwBegin) -> Node {
+        Node::KwBegin(Box::new(KwBegin {
+            statements: self.fold_all(node.statements),
+            ..node
+        }))
+    }
+
+    fn fold_kwnilarg(&mut self, node: Kwnilarg) -> Node {
+        Node::Kwnilarg(Box::new(node))
+    }
+
+    fn fold_kwoptarg(&mut self, node: Kwoptarg) -> Node {
+        Node::Kwoptarg(Box::new(Kwoptarg {
+            default: self.fold(node.default),
+            ..node
+        }))
+    }
+
+    fn fold_kwrestarg(&mut self, node: Kwrestarg) -> Node {
+        Node::Kwrestarg(Box::new(node))
+    }
+
+    fn fold_kwsplat(&mut self, node: Kwsplat) -> Node {
+        Node::Kwsplat(Box::new(Kwsplat {
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_lambda(&mut self, node: Lambda) -> Node {
+        Node::Lambda(Box::new(node))
+    }
+
+    fn fold_line(&mut self, node: Line) -> Node {
+        Node::Line(Box::new(node))
+    }
+
+    fn fold_lvar(&mut self, node: Lvar) -> Node {
+        Node::Lvar(Box::new(node))
+    }
+
+    fn fold_lvasgn(&mut self, node: Lvasgn) -> Node {
+        Node::Lvasgn(Box::new(Lvasgn {
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_masgn(&mut self, node: Masgn) -> Node {
+        Node::Masgn(Box::new(Masgn {
+            lhs: self.fold(node.lhs),
+            rhs: self.fold(node.rhs),
+            ..node
+        }))
+    }
+
+    fn fold_match_alt(&mut self, node: MatchAlt) -> Node {
+        Node::MatchAlt(Box::new(MatchAlt {
+            lhs: self.fold(node.lhs),
+            rhs: self.fold(node.rhs),
+            ..node
+        }))
+    }
+
+    fn fold_match_as(&mut self, node: MatchAs) -> Node {
+        Node::MatchAs(Box::new(MatchAs {
+            value: self.fold(node.value),
+            as_: self.fold(node.as_),
+            ..node
+        }))
+    }
+
+    fn fold_match_current_line(&mut self, node: MatchCurrentLine) -> Node {
+        Node::MatchCurrentLine(Box::new(MatchCurrentLine {
+            re: self.fold(node.re),
+            ..node
+        }))
+    }
+
+    fn fold_match_nil_pattern(&mut self, node: MatchNilPattern) -> Node {
+        Node::MatchNilPattern(Box::new(node))
+    }
+
+    fn fold_match_pattern(&mut self, node: MatchPattern) -> Node {
+        Node::MatchPattern(Box::new(MatchPattern {
+            value: self.fold(node.value),
+            pattern: self.fold(node.pattern),
+            ..node
+        }))
+    }
+
+    fn fold_match_rest(&mut self, node: MatchRest) -> Node {
+        Node::MatchRest(Box::new(MatchRest {
+            name: self.maybe_fold(node.name),
+            ..node
+        }))
+    }
+
+    fn fold_match_var(&mut self, node: MatchVar) -> Node {
+        Node::MatchVar(Box::new(node))
+    }
+
+    fn fold_match_with_lvasgn(&mut self, node: MatchWithLvasgn) -> Node {
+        Node::MatchWithLvasgn(Box::new(MatchWithLvasgn {
+            re: self.fold(node.re),
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_mlhs(&mut self, node: Mlhs) -> Node {
+        Node::Mlhs(Box::new(Mlhs {
+            items: self.fold_all(node.items),
+            ..node
+        }))
+    }
+
+    fn fold_module(&mut self, node: Module) -> Node {
+        Node::Module(Box::new(Module {
+            name: self.fold(node.name),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_next(&mut self, node: Next) -> Node {
+        Node::Next(Box::new(Next {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_nil(&mut self, node: Nil) -> Node {
+        Node::Nil(Box::new(node))
+    }
+
+    fn fold_nth_ref(&mut self, node: NthRef) -> Node {
+        Node::NthRef(Box::new(node))
+    }
+
+    fn fold_numblock(&mut self, node: Numblock) -> Node {
+        Node::Numblock(Box::new(Numblock {
+            call: self.fold(node.call),
+            body: self.fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_op_asgn(&mut self, node: OpAsgn) -> Node {
+        Node::OpAsgn(Box::new(OpAsgn {
+            recv: self.fold(node.recv),
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_optarg(&mut self, node: Optarg) -> Node {
+        Node::Optarg(Box::new(Optarg {
+            default: self.fold(node.default),
+            ..node
+        }))
+    }
+
+    fn fold_or(&mut self, node: Or) -> Node {
+        Node::Or(Box::new(Or {
+            lhs: self.fold(node.lhs),
+            rhs: self.fold(node.rhs),
+            ..node
+        }))
+    }
+
+    fn fold_or_asgn(&mut self, node: OrAsgn) -> Node {
+        Node::OrAsgn(Box::new(OrAsgn {
+            recv: self.fold(node.recv),
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_pair(&mut self, node: Pair) -> Node {
+        Node::Pair(Box::new(Pair {
+            key: self.fold(node.key),
+            value: self.fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_pin(&mut self, node: Pin) -> Node {
+        Node::Pin(Box::new(Pin {
+            var: self.fold(node.var),
+            ..node
+        }))
+    }
+
+    fn fold_postexe(&mut self, node: Postexe) -> Node {
+        Node::Postexe(Box::new(Postexe {
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_preexe(&mut self, node: Preexe) -> Node {
+        Node::Preexe(Box::new(Preexe {
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_procarg0(&mut self, node: Procarg0) -> Node {
+        Node::Procarg0(Box::new(Procarg0 {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_rational(&mut self, node: Rational) -> Node {
+        Node::Rational(Box::new(node))
+    }
+
+    fn fold_redo(&mut self, node: Redo) -> Node {
+        Node::Redo(Box::new(node))
+    }
+
+    fn fold_regexp(&mut self, node: Regexp) -> Node {
+        Node::Regexp(Box::new(Regexp {
+            parts: self.fold_all(node.parts),
+            options: self.maybe_fold(node.options),
+            ..node
+        }))
+    }
+
+    fn fold_regopt(&mut self, node: RegOpt) -> Node {
+        Node::RegOpt(Box::new(node))
+    }
+
+    fn fold_rescue(&mut self, node: Rescue) -> Node {
+        Node::Rescue(Box::new(Rescue {
+            body: self.maybe_fold(node.body),
+            rescue_bodies: self.fold_all(node.rescue_bodies),
+            else_: self.maybe_fold(node.else_),
+            ..node
+        }))
+    }
+
+    fn fold_rescue_body(&mut self, node: RescueBody) -> Node {
+        Node::RescueBody(Box::new(RescueBody {
+            exc_list: self.maybe_fold(node.exc_list),
+            exc_var: self.maybe_fold(node.exc_var),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_restarg(&mut self, node: Restarg) -> Node {
+        Node::Restarg(Box::new(node))
+    }
+
+    fn fold_retry(&mut self, node: Retry) -> Node {
+        Node::Retry(Box::new(node))
+    }
+
+    fn fold_return(&mut self, node: Return) -> Node {
+        Node::Return(Box::new(Return {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_sclass(&mut self, node: SClass) -> Node {
+        Node::SClass(Box::new(SClass {
+            expr: self.fold(node.expr),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_self_(&mut self, node: Self_) -> Node {
+        Node::Self_(Box::new(node))
+    }
+
+    fn fold_send(&mut self, node: Send) -> Node {
+        Node::Send(Box::new(Send {
+            recv: self.maybe_fold(node.recv),
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_shadowarg(&mut self, node: Shadowarg) -> Node {
+        Node::Shadowarg(Box::new(node))
+    }
+
+    fn fold_splat(&mut self, node: Splat) -> Node {
+        Node::Splat(Box::new(Splat {
+            value: self.maybe_fold(node.value),
+            ..node
+        }))
+    }
+
+    fn fold_str(&mut self, node: Str) -> Node {
+        Node::Str(Box::new(node))
+    }
+
+    fn fold_super(&mut self, node: Super) -> Node {
+        Node::Super(Box::new(Super {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_sym(&mut self, node: Sym) -> Node {
+        Node::Sym(Box::new(node))
+    }
+
+    fn fold_true(&mut self, node: True) -> Node {
+        Node::True(Box::new(node))
+    }
+
+    fn fold_undef(&mut self, node: Undef) -> Node {
+        Node::Undef(Box::new(Undef {
+            names: self.fold_all(node.names),
+            ..node
+        }))
+    }
+
+    fn fold_unless_guard(&mut self, node: UnlessGuard) -> Node {
+        Node::UnlessGuard(Box::new(UnlessGuard {
+            cond: self.fold(node.cond),
+            ..node
+        }))
+    }
+
+    fn fold_until(&mut self, node: Until) -> Node {
+        Node::Until(Box::new(Until {
+            cond: self.fold(node.cond),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_until_post(&mut self, node: UntilPost) -> Node {
+        Node::UntilPost(Box::new(UntilPost {
+            cond: self.fold(node.cond),
+            body: self.fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_when(&mut self, node: When) -> Node {
+        Node::When(Box::new(When {
+            patterns: self.fold_all(node.patterns),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_while(&mut self, node: While) -> Node {
+        Node::While(Box::new(While {
+            cond: self.fold(node.cond),
+            body: self.maybe_fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_while_post(&mut self, node: WhilePost) -> Node {
+        Node::WhilePost(Box::new(WhilePost {
+            cond: self.fold(node.cond),
+            body: self.fold(node.body),
+            ..node
+        }))
+    }
+
+    fn fold_xheredoc(&mut self, node: XHeredoc) -> Node {
+        Node::XHeredoc(Box::new(XHeredoc {
+            parts: self.fold_all(node.parts),
+            ..node
+        }))
+    }
+
+    fn fold_xstr(&mut self, node: Xstr) -> Node {
+        Node::Xstr(Box::new(Xstr {
+            parts: self.fold_all(node.parts),
+            ..node
+        }))
+    }
+
+    fn fold_yield(&mut self, node: Yield) -> Node {
+        Node::Yield(Box::new(Yield {
+            args: self.fold_all(node.args),
+            ..node
+        }))
+    }
+
+    fn fold_zsuper(&mut self, node: ZSuper) -> Node {
+        Node::ZSuper(Box::new(node))
+    }
+
+    fn fold(&mut self, node: Node) -> Node {
+        match node {
+            Node::Alias(inner) => self.fold_alias(*inner),
+            Node::And(inner) => self.fold_and(*inner),
+            Node::AndAsgn(inner) => self.fold_and_asgn(*inner),
+            Node::AnonymousBlockarg(inner) => self.fold_anonymous_blockarg(*inner),
+            Node::Arg(inner) => self.fold_arg(*inner),
+            Node::Args(inner) => self.fold_args(*inner),
+            Node::Array(inner) => self.fold_array(*inner),
+            Node::ArrayPattern(inner) => self.fold_array_pattern(*inner),
+            Node::ArrayPatternWithTail(inner) => self.fold_array_pattern_with_tail(*inner),
+            Node::BackRef(inner) => self.fold_back_ref(*inner),
+            Node::Begin(inner) => self.fold_begin(*inner),
+            Node::Block(inner) => self.fold_block(*inner),
+            Node::Blockarg(inner) => self.fold_blockarg(*inner),
+            Node::BlockPass(inner) => self.fold_block_pass(*inner),
+            Node::Break(inner) => self.fold_break(*inner),
+            Node::Case(inner) => self.fold_case(*inner),
+            Node::CaseMatch(inner) => self.fold_case_match(*inner),
+            Node::Casgn(inner) => self.fold_casgn(*inner),
+            Node::Cbase(inner) => self.fold_cbase(*inner),
+            Node::Class(inner) => self.fold_class(*inner),
+            Node::Complex(inner) => self.fold_complex(*inner),
+            Node::Const(inner) => self.fold_const(*inner),
+            Node::ConstPattern(inner) => self.fold_const_pattern(*inner),
+            Node::CSend(inner) => self.fold_csend(*inner),
+            Node::Cvar(inner) => self.fold_cvar(*inner),
+            Node::Cvasgn(inner) => self.fold_cvasgn(*inner),
+            Node::Def(inner) => self.fold_def(*inner),
+            Node::Defined(inner) => self.fold_defined(*inner),
+            Node::Defs(inner) => self.fold_defs(*inner),
+            Node::Dstr(inner) => self.fold_dstr(*inner),
+            Node::Dsym(inner) => self.fold_dsym(*inner),
+            Node::EFlipFlop(inner) => self.fold_eflipflop(*inner),
+            Node::EmptyElse(inner) => self.fold_empty_else(*inner),
+            Node::Encoding(inner) => self.fold_encoding(*inner),
+            Node::Ensure(inner) => self.fold_ensure(*inner),
+            Node::Erange(inner) => self.fold_erange(*inner),
+            Node::Error(inner) => self.fold_error(*inner),
+            Node::False(inner) => self.fold_false(*inner),
+            Node::File(inner) => self.fold_file(*inner),
+            Node::FindPattern(inner) => self.fold_find_pattern(*inner),
+            Node::Float(inner) => self.fold_float(*inner),
+            Node::For(inner) => self.fold_for(*inner),
+            Node::ForwardArg(inner) => self.fold_forward_arg(*inner),
+            Node::ForwardedArgs(inner) => self.fold_forwarded_args(*inner),
+            Node::ForwardedBlockarg(inner) => self.fold_forwarded_blockarg(*inner),
+            Node::ForwardedKwrestarg(inner) => self.fold_forwarded_kwrestarg(*inner),
+            Node::ForwardedRestarg(inner) => self.fold_forwarded_restarg(*inner),
+            Node::Gvar(inner) => self.fold_gvar(*inner),
+            Node::Gvasgn(inner) => self.fold_gvasgn(*inner),
+            Node::Hash(inner) => self.fold_hash(*inner),
+            Node::HashPattern(inner) => self.fold_hash_pattern(*inner),
+            Node::Heredoc(inner) => self.fold_heredoc(*inner),
+            Node::If(inner) => self.fold_if(*inner),
+            Node::IfGuard(inner) => self.fold_if_guard(*inner),
+            Node::IFlipFlop(inner) => self.fold_iflipflop(*inner),
+            Node::IfMod(inner) => self.fold_if_mod(*inner),
+            Node::IfTernary(inner) => self.fold_if_ternary(*inner),
+            Node::Index(inner) => self.fold_index(*inner),
+            Node::IndexAsgn(inner) => self.fold_index_asgn(*inner),
+            Node::InMatch(inner) => self.fold_in_match(*inner),
+            Node::InPattern(inner) => self.fold_in_pattern(*inner),
+            Node::Int(inner) => self.fold_int(*inner),
+            Node::Irange(inner) => self.fold_irange(*inner),
+            Node::Itblock(inner) => self.fold_itblock(*inner),
+            Node::Ivar(inner) => self.fold_ivar(*inner),
+            Node::Ivasgn(inner) => self.fold_ivasgn(*inner),
+            Node::Kwarg(inner) => self.fold_kwarg(*inner),
+            Node::KwBegin(inner) => self.fold_kwbegin(*inner),
+            Node::Kwnilarg(inner) => self.fold_kwnilarg(*inner),
+            Node::Kwoptarg(inner) => self.fold_kwoptarg(*inner),
+            Node::Kwrestarg(inner) => self.fold_kwrestarg(*inner),
+            Node::Kwsplat(inner) => self.fold_kwsplat(*inner),
+            Node::Lambda(inner) => self.fold_lambda(*inner),
+            Node::Line(inner) => self.fold_line(*inner),
+            Node::Lvar(inner) => self.fold_lvar(*inner),
+            Node::Lvasgn(inner) => self.fold_lvasgn(*inner),
+            Node::Masgn(inner) => self.fold_masgn(*inner),
+            Node::MatchAlt(inner) => self.fold_match_alt(*inner),
+            Node::MatchAs(inner) => self.fold_match_as(*inner),
+            Node::MatchCurrentLine(inner) => self.fold_match_current_line(*inner),
+            Node::MatchNilPattern(inner) => self.fold_match_nil_pattern(*inner),
+            Node::MatchPattern(inner) => self.fold_match_pattern(*inner),
+            Node::MatchRest(inner) => self.fold_match_rest(*inner),
+            Node::MatchVar(inner) => self.fold_match_var(*inner),
+            Node::MatchWithLvasgn(inner) => self.fold_match_with_lvasgn(*inner),
+            Node::Mlhs(inner) => self.fold_mlhs(*inner),
+            Node::Module(inner) => self.fold_module(*inner),
+            Node::Next(inner) => self.fold_next(*inner),
+            Node::Nil(inner) => self.fold_nil(*inner),
+            Node::NthRef(inner) => self.fold_nth_ref(*inner),
+            Node::Numblock(inner) => self.fold_numblock(*inner),
+            Node::OpAsgn(inner) => self.fold_op_asgn(*inner),
+            Node::Optarg(inner) => self.fold_optarg(*inner),
+            Node::Or(inner) => self.fold_or(*inner),
+            Node::OrAsgn(inner) => self.fold_or_asgn(*inner),
+            Node::Pair(inner) => self.fold_pair(*inner),
+            Node::Pin(inner) => self.fold_pin(*inner),
+            Node::Postexe(inner) => self.fold_postexe(*inner),
+            Node::Preexe(inner) => self.fold_preexe(*inner),
+            Node::Procarg0(inner) => self.fold_procarg0(*inner),
+            Node::Rational(inner) => self.fold_rational(*inner),
+            Node::Redo(inner) => self.fold_redo(*inner),
+            Node::Regexp(inner) => self.fold_regexp(*inner),
+            Node::RegOpt(inner) => self.fold_regopt(*inner),
+            Node::Rescue(inner) => self.fold_rescue(*inner),
+            Node::RescueBody(inner) => self.fold_rescue_body(*inner),
+            Node::Restarg(inner) => self.fold_restarg(*inner),
+            Node::Retry(inner) => self.fold_retry(*inner),
+            Node::Return(inner) => self.fold_return(*inner),
+            Node::SClass(inner) => self.fold_sclass(*inner),
+            Node::Self_(inner) => self.fold_self_(*inner),
+            Node::Send(inner) => self.fold_send(*inner),
+            Node::Shadowarg(inner) => self.fold_shadowarg(*inner),
+            Node::Splat(inner) => self.fold_splat(*inner),
+            Node::Str(inner) => self.fold_str(*inner),
+            Node::Super(inner) => self.fold_super(*inner),
+            Node::Sym(inner) => self.fold_sym(*inner),
+            Node::True(inner) => self.fold_true(*inner),
+            Node::Undef(inner) => self.fold_undef(*inner),
+            Node::UnlessGuard(inner) => self.fold_unless_guard(*inner),
+            Node::Until(inner) => self.fold_until(*inner),
+            Node::UntilPost(inner) => self.fold_until_post(*inner),
+            Node::When(inner) => self.fold_when(*inner),
+            Node::While(inner) => self.fold_while(*inner),
+            Node::WhilePost(inner) => self.fold_while_post(*inner),
+            Node::XHeredoc(inner) => self.fold_xheredoc(*inner),
+            Node::Xstr(inner) => self.fold_xstr(*inner),
+            Node::Yield(inner) => self.fold_yield(*inner),
+            Node::ZSuper(inner) => self.fold_zsuper(*inner),
+        }
+    }
+}
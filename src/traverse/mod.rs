@@ -1,5 +1,11 @@
 mod visitor;
 pub use visitor::Visitor;
 
+mod visitor_mut;
+pub use visitor_mut::VisitorMut;
+
+mod fold;
+pub use fold::Fold;
+
 mod find;
 pub use find::Find;
@@ -31,6 +31,11 @@ pub trait Visitor<T: Default = ()> {
         self.visit(&node.value)
     }
 
+    #[allow(unused_variables)]
+    fn on_anonymous_blockarg(&mut self, node: &AnonymousBlockarg) -> T {
+        T::default()
+    }
+
     #[allow(unused_variables)]
     fn on_arg(&mut self, node: &Arg) -> T {
         T::default()
@@ -184,6 +189,11 @@ pub trait Visitor<T: Default = ()> {
         self.maybe_visit(&node.right)
     }
 
+    #[allow(unused_variables)]
+    fn on_error(&mut self, node: &Error) -> T {
+        T::default()
+    }
+
     #[allow(unused_variables)]
     fn on_false(&mut self, node: &False) -> T {
         T::default()
@@ -219,6 +229,21 @@ pub trait Visitor<T: Default = ()> {
         T::default()
     }
 
+    #[allow(unused_variables)]
+    fn on_forwarded_blockarg(&mut self, node: &ForwardedBlockarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_kwrestarg(&mut self, node: &ForwardedKwrestarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_restarg(&mut self, node: &ForwardedRestarg) -> T {
+        T::default()
+    }
+
     #[allow(unused_variables)]
     fn on_gvar(&mut self, node: &Gvar) -> T {
         T::default()
@@ -299,6 +324,11 @@ pub trait Visitor<T: Default = ()> {
         self.maybe_visit(&node.right)
     }
 
+    fn on_itblock(&mut self, node: &Itblock) -> T {
+        self.visit(&node.call);
+        self.visit(&node.body)
+    }
+
     #[allow(unused_variables)]
     fn on_ivar(&mut self, node: &Ivar) -> T {
         T::default()
@@ -378,6 +408,11 @@ pub trait Visitor<T: Default = ()> {
         T::default()
     }
 
+    fn on_match_pattern(&mut self, node: &MatchPattern) -> T {
+        self.visit(&node.value);
+        self.visit(&node.pattern)
+    }
+
     fn on_match_rest(&mut self, node: &MatchRest) -> T {
         self.maybe_visit(&node.name)
     }
@@ -599,10 +634,16 @@ pub trait Visitor<T: Default = ()> {
     }
 
     fn visit(&mut self, node: &Node) -> T {
+        self.walk(node)
+    }
+
+    /// Calls `on_*` handler of the given node, can be used by `visit` overrides
+    fn walk(&mut self, node: &Node) -> T {
         match node {
             Node::Alias(inner) => self.on_alias(inner),
             Node::And(inner) => self.on_and(inner),
             Node::AndAsgn(inner) => self.on_and_asgn(inner),
+            Node::AnonymousBlockarg(inner) => self.on_anonymous_blockarg(inner),
             Node::Arg(inner) => self.on_arg(inner),
             Node::Args(inner) => self.on_args(inner),
             Node::Array(inner) => self.on_array(inner),
@@ -635,6 +676,7 @@ pub trait Visitor<T: Default = ()> {
             Node::Encoding(inner) => self.on_encoding(inner),
             Node::Ensure(inner) => self.on_ensure(inner),
             Node::Erange(inner) => self.on_erange(inner),
+            Node::Error(inner) => self.on_error(inner),
             Node::False(inner) => self.on_false(inner),
             Node::File(inner) => self.on_file(inner),
             Node::FindPattern(inner) => self.on_find_pattern(inner),
@@ -642,6 +684,9 @@ pub trait Visitor<T: Default = ()> {
             Node::For(inner) => self.on_for(inner),
             Node::ForwardArg(inner) => self.on_forward_arg(inner),
             Node::ForwardedArgs(inner) => self.on_forwarded_args(inner),
+            Node::ForwardedBlockarg(inner) => self.on_forwarded_blockarg(inner),
+            Node::ForwardedKwrestarg(inner) => self.on_forwarded_kwrestarg(inner),
+            Node::ForwardedRestarg(inner) => self.on_forwarded_restarg(inner),
             Node::Gvar(inner) => self.on_gvar(inner),
             Node::Gvasgn(inner) => self.on_gvasgn(inner),
             Node::Hash(inner) => self.on_hash(inner),
@@ -658,6 +703,7 @@ pub trait Visitor<T: Default = ()> {
             Node::InPattern(inner) => self.on_in_pattern(inner),
             Node::Int(inner) => self.on_int(inner),
             Node::Irange(inner) => self.on_irange(inner),
+            Node::Itblock(inner) => self.on_itblock(inner),
             Node::Ivar(inner) => self.on_ivar(inner),
             Node::Ivasgn(inner) => self.on_ivasgn(inner),
             Node::Kwarg(inner) => self.on_kwarg(inner),
@@ -675,6 +721,7 @@ pub trait Visitor<T: Default = ()> {
             Node::MatchAs(inner) => self.on_match_as(inner),
             Node::MatchCurrentLine(inner) => self.on_match_current_line(inner),
             Node::MatchNilPattern(inner) => self.on_match_nil_pattern(inner),
+            Node::MatchPattern(inner) => self.on_match_pattern(inner),
             Node::MatchRest(inner) => self.on_match_rest(inner),
             Node::MatchVar(inner) => self.on_match_var(inner),
             Node::MatchWithLvasgn(inner) => self.on_match_with_lvasgn(inner),
@@ -0,0 +1,774 @@
+use crate::nodes::*;
+use crate::Node;
+
+pub trait VisitorMut<T: Default = ()> {
+    fn visit_all(&mut self, nodes: &mut [Node]) -> T {
+        for node in nodes {
+            self.visit(node);
+        }
+        T::default()
+    }
+
+    fn maybe_visit(&mut self, node: &mut Option<Node>) -> T {
+        if let Some(node) = node {
+            self.visit(node);
+        }
+        T::default()
+    }
+
+    fn on_alias(&mut self, node: &mut Alias) -> T {
+        self.visit(&mut node.to);
+        self.visit(&mut node.from)
+    }
+
+    fn on_and(&mut self, node: &mut And) -> T {
+        self.visit(&mut node.lhs);
+        self.visit(&mut node.rhs)
+    }
+
+    fn on_and_asgn(&mut self, node: &mut AndAsgn) -> T {
+        self.visit(&mut node.recv);
+        self.visit(&mut node.value)
+    }
+
+    #[allow(unused_variables)]
+    fn on_anonymous_blockarg(&mut self, node: &mut AnonymousBlockarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_arg(&mut self, node: &mut Arg) -> T {
+        T::default()
+    }
+
+    fn on_args(&mut self, node: &mut Args) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    fn on_array(&mut self, node: &mut Array) -> T {
+        self.visit_all(&mut node.elements)
+    }
+
+    fn on_array_pattern(&mut self, node: &mut ArrayPattern) -> T {
+        self.visit_all(&mut node.elements)
+    }
+
+    fn on_array_pattern_with_tail(&mut self, node: &mut ArrayPatternWithTail) -> T {
+        self.visit_all(&mut node.elements)
+    }
+
+    #[allow(unused_variables)]
+    fn on_back_ref(&mut self, node: &mut BackRef) -> T {
+        T::default()
+    }
+
+    fn on_begin(&mut self, node: &mut Begin) -> T {
+        self.visit_all(&mut node.statements)
+    }
+
+    fn on_block(&mut self, node: &mut Block) -> T {
+        self.visit(&mut node.call);
+        self.maybe_visit(&mut node.args);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_blockarg(&mut self, node: &mut Blockarg) -> T {
+        T::default()
+    }
+
+    fn on_block_pass(&mut self, node: &mut BlockPass) -> T {
+        self.visit(&mut node.value)
+    }
+
+    fn on_break(&mut self, node: &mut Break) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    fn on_case(&mut self, node: &mut Case) -> T {
+        self.maybe_visit(&mut node.expr);
+        self.visit_all(&mut node.when_bodies);
+        self.maybe_visit(&mut node.else_body)
+    }
+
+    fn on_case_match(&mut self, node: &mut CaseMatch) -> T {
+        self.visit(&mut node.expr);
+        self.visit_all(&mut node.in_bodies);
+        self.maybe_visit(&mut node.else_body)
+    }
+
+    fn on_casgn(&mut self, node: &mut Casgn) -> T {
+        self.maybe_visit(&mut node.scope);
+        self.maybe_visit(&mut node.value)
+    }
+
+    #[allow(unused_variables)]
+    fn on_cbase(&mut self, node: &mut Cbase) -> T {
+        T::default()
+    }
+
+    fn on_class(&mut self, node: &mut Class) -> T {
+        self.visit(&mut node.name);
+        self.maybe_visit(&mut node.superclass);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_complex(&mut self, node: &mut Complex) -> T {
+        T::default()
+    }
+
+    fn on_const(&mut self, node: &mut Const) -> T {
+        self.maybe_visit(&mut node.scope)
+    }
+
+    fn on_const_pattern(&mut self, node: &mut ConstPattern) -> T {
+        self.visit(&mut node.const_);
+        self.visit(&mut node.pattern)
+    }
+
+    fn on_csend(&mut self, node: &mut CSend) -> T {
+        self.visit(&mut node.recv);
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_cvar(&mut self, node: &mut Cvar) -> T {
+        T::default()
+    }
+
+    fn on_cvasgn(&mut self, node: &mut Cvasgn) -> T {
+        self.maybe_visit(&mut node.value)
+    }
+
+    fn on_def(&mut self, node: &mut Def) -> T {
+        self.maybe_visit(&mut node.args);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_defined(&mut self, node: &mut Defined) -> T {
+        self.visit(&mut node.value)
+    }
+
+    fn on_defs(&mut self, node: &mut Defs) -> T {
+        self.visit(&mut node.definee);
+        self.maybe_visit(&mut node.args);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_dstr(&mut self, node: &mut Dstr) -> T {
+        self.visit_all(&mut node.parts)
+    }
+
+    fn on_dsym(&mut self, node: &mut Dsym) -> T {
+        self.visit_all(&mut node.parts)
+    }
+
+    fn on_eflipflop(&mut self, node: &mut EFlipFlop) -> T {
+        self.maybe_visit(&mut node.left);
+        self.maybe_visit(&mut node.right)
+    }
+
+    #[allow(unused_variables)]
+    fn on_empty_else(&mut self, node: &mut EmptyElse) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_encoding(&mut self, node: &mut Encoding) -> T {
+        T::default()
+    }
+
+    fn on_ensure(&mut self, node: &mut Ensure) -> T {
+        self.maybe_visit(&mut node.body);
+        self.maybe_visit(&mut node.ensure)
+    }
+
+    fn on_erange(&mut self, node: &mut Erange) -> T {
+        self.maybe_visit(&mut node.left);
+        self.maybe_visit(&mut node.right)
+    }
+
+    #[allow(unused_variables)]
+    fn on_error(&mut self, node: &mut Error) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_false(&mut self, node: &mut False) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_file(&mut self, node: &mut File) -> T {
+        T::default()
+    }
+
+    fn on_find_pattern(&mut self, node: &mut FindPattern) -> T {
+        self.visit_all(&mut node.elements)
+    }
+
+    #[allow(unused_variables)]
+    fn on_float(&mut self, node: &mut Float) -> T {
+        T::default()
+    }
+
+    fn on_for(&mut self, node: &mut For) -> T {
+        self.visit(&mut node.iterator);
+        self.visit(&mut node.iteratee);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_forward_arg(&mut self, node: &mut ForwardArg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_args(&mut self, node: &mut ForwardedArgs) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_blockarg(&mut self, node: &mut ForwardedBlockarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_kwrestarg(&mut self, node: &mut ForwardedKwrestarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_forwarded_restarg(&mut self, node: &mut ForwardedRestarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_gvar(&mut self, node: &mut Gvar) -> T {
+        T::default()
+    }
+
+    fn on_gvasgn(&mut self, node: &mut Gvasgn) -> T {
+        self.maybe_visit(&mut node.value)
+    }
+
+    fn on_hash(&mut self, node: &mut Hash) -> T {
+        self.visit_all(&mut node.pairs)
+    }
+
+    fn on_hash_pattern(&mut self, node: &mut HashPattern) -> T {
+        self.visit_all(&mut node.elements)
+    }
+
+    fn on_heredoc(&mut self, node: &mut Heredoc) -> T {
+        self.visit_all(&mut node.parts)
+    }
+
+    fn on_if(&mut self, node: &mut If) -> T {
+        self.visit(&mut node.cond);
+        self.maybe_visit(&mut node.if_true);
+        self.maybe_visit(&mut node.if_false)
+    }
+
+    fn on_if_guard(&mut self, node: &mut IfGuard) -> T {
+        self.visit(&mut node.cond)
+    }
+
+    fn on_iflipflop(&mut self, node: &mut IFlipFlop) -> T {
+        self.maybe_visit(&mut node.left);
+        self.maybe_visit(&mut node.right)
+    }
+
+    fn on_if_mod(&mut self, node: &mut IfMod) -> T {
+        self.visit(&mut node.cond);
+        self.maybe_visit(&mut node.if_true);
+        self.maybe_visit(&mut node.if_false)
+    }
+
+    fn on_if_ternary(&mut self, node: &mut IfTernary) -> T {
+        self.visit(&mut node.cond);
+        self.visit(&mut node.if_true);
+        self.visit(&mut node.if_false)
+    }
+
+    fn on_index(&mut self, node: &mut Index) -> T {
+        self.visit(&mut node.recv);
+        self.visit_all(&mut node.indexes)
+    }
+
+    fn on_index_asgn(&mut self, node: &mut IndexAsgn) -> T {
+        self.visit(&mut node.recv);
+        self.visit_all(&mut node.indexes);
+        self.maybe_visit(&mut node.value)
+    }
+
+    fn on_in_match(&mut self, node: &mut InMatch) -> T {
+        self.visit(&mut node.value);
+        self.visit(&mut node.pattern)
+    }
+
+    fn on_in_pattern(&mut self, node: &mut InPattern) -> T {
+        self.visit(&mut node.pattern);
+        self.maybe_visit(&mut node.guard);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_int(&mut self, node: &mut Int) -> T {
+        T::default()
+    }
+
+    fn on_irange(&mut self, node: &mut Irange) -> T {
+        self.maybe_visit(&mut node.left);
+        self.maybe_visit(&mut node.right)
+    }
+
+    fn on_itblock(&mut self, node: &mut Itblock) -> T {
+        self.visit(&mut node.call);
+        self.visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_ivar(&mut self, node: &mut Ivar) -> T {
+        T::default()
+    }
+
+    fn on_ivasgn(&mut self, node: &mut Ivasgn) -> T {
+        self.maybe_visit(&mut node.value)
+    }
+
+    #[allow(unused_variables)]
+    fn on_kwarg(&mut self, node: &mut Kwarg) -> T {
+        T::default()
+    }
+
+    fn on_kwbegin(&mut self, node: &mut KwBegin) -> T {
+        self.visit_all(&mut node.statements)
+    }
+
+    #[allow(unused_variables)]
+    fn on_kwnilarg(&mut self, node: &mut Kwnilarg) -> T {
+        T::default()
+    }
+
+    fn on_kwoptarg(&mut self, node: &mut Kwoptarg) -> T {
+        self.visit(&mut node.default)
+    }
+
+    #[allow(unused_variables)]
+    fn on_kwrestarg(&mut self, node: &mut Kwrestarg) -> T {
+        T::default()
+    }
+
+    fn on_kwsplat(&mut self, node: &mut Kwsplat) -> T {
+        self.visit(&mut node.value)
+    }
+
+    #[allow(unused_variables)]
+    fn on_lambda(&mut self, node: &mut Lambda) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_line(&mut self, node: &mut Line) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_lvar(&mut self, node: &mut Lvar) -> T {
+        T::default()
+    }
+
+    fn on_lvasgn(&mut self, node: &mut Lvasgn) -> T {
+        self.maybe_visit(&mut node.value)
+    }
+
+    fn on_masgn(&mut self, node: &mut Masgn) -> T {
+        self.visit(&mut node.lhs);
+        self.visit(&mut node.rhs)
+    }
+
+    fn on_match_alt(&mut self, node: &mut MatchAlt) -> T {
+        self.visit(&mut node.lhs);
+        self.visit(&mut node.rhs)
+    }
+
+    fn on_match_as(&mut self, node: &mut MatchAs) -> T {
+        self.visit(&mut node.value);
+        self.visit(&mut node.as_)
+    }
+
+    fn on_match_current_line(&mut self, node: &mut MatchCurrentLine) -> T {
+        self.visit(&mut node.re)
+    }
+
+    #[allow(unused_variables)]
+    fn on_match_nil_pattern(&mut self, node: &mut MatchNilPattern) -> T {
+        T::default()
+    }
+
+    fn on_match_pattern(&mut self, node: &mut MatchPattern) -> T {
+        self.visit(&mut node.value);
+        self.visit(&mut node.pattern)
+    }
+
+    fn on_match_rest(&mut self, node: &mut MatchRest) -> T {
+        self.maybe_visit(&mut node.name)
+    }
+
+    #[allow(unused_variables)]
+    fn on_match_var(&mut self, node: &mut MatchVar) -> T {
+        T::default()
+    }
+
+    fn on_match_with_lvasgn(&mut self, node: &mut MatchWithLvasgn) -> T {
+        self.visit(&mut node.re);
+        self.visit(&mut node.value)
+    }
+
+    fn on_mlhs(&mut self, node: &mut Mlhs) -> T {
+        self.visit_all(&mut node.items)
+    }
+    fn on_module(&mut self, node: &mut Module) -> T {
+        self.visit(&mut node.name);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_next(&mut self, node: &mut Next) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_nil(&mut self, node: &mut Nil) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_nth_ref(&mut self, node: &mut NthRef) -> T {
+        T::default()
+    }
+
+    fn on_numblock(&mut self, node: &mut Numblock) -> T {
+        self.visit(&mut node.call);
+        self.visit(&mut node.body)
+    }
+
+    fn on_op_asgn(&mut self, node: &mut OpAsgn) -> T {
+        self.visit(&mut node.recv);
+        self.visit(&mut node.value)
+    }
+
+    fn on_optarg(&mut self, node: &mut Optarg) -> T {
+        self.visit(&mut node.default)
+    }
+
+    fn on_or(&mut self, node: &mut Or) -> T {
+        self.visit(&mut node.lhs);
+        self.visit(&mut node.rhs)
+    }
+
+    fn on_or_asgn(&mut self, node: &mut OrAsgn) -> T {
+        self.visit(&mut node.recv);
+        self.visit(&mut node.value)
+    }
+
+    fn on_pair(&mut self, node: &mut Pair) -> T {
+        self.visit(&mut node.key);
+        self.visit(&mut node.value)
+    }
+
+    fn on_pin(&mut self, node: &mut Pin) -> T {
+        self.visit(&mut node.var)
+    }
+
+    fn on_postexe(&mut self, node: &mut Postexe) -> T {
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_preexe(&mut self, node: &mut Preexe) -> T {
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_procarg0(&mut self, node: &mut Procarg0) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_rational(&mut self, node: &mut Rational) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_redo(&mut self, node: &mut Redo) -> T {
+        T::default()
+    }
+
+    fn on_regexp(&mut self, node: &mut Regexp) -> T {
+        self.visit_all(&mut node.parts);
+        self.maybe_visit(&mut node.options)
+    }
+
+    #[allow(unused_variables)]
+    fn on_regopt(&mut self, node: &mut RegOpt) -> T {
+        T::default()
+    }
+
+    fn on_rescue(&mut self, node: &mut Rescue) -> T {
+        self.maybe_visit(&mut node.body);
+        self.visit_all(&mut node.rescue_bodies);
+        self.maybe_visit(&mut node.else_)
+    }
+
+    fn on_rescue_body(&mut self, node: &mut RescueBody) -> T {
+        self.maybe_visit(&mut node.exc_list);
+        self.maybe_visit(&mut node.exc_var);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_restarg(&mut self, node: &mut Restarg) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_retry(&mut self, node: &mut Retry) -> T {
+        T::default()
+    }
+
+    fn on_return(&mut self, node: &mut Return) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    fn on_sclass(&mut self, node: &mut SClass) -> T {
+        self.visit(&mut node.expr);
+        self.maybe_visit(&mut node.body)
+    }
+
+    #[allow(unused_variables)]
+    fn on_self_(&mut self, node: &mut Self_) -> T {
+        T::default()
+    }
+
+    fn on_send(&mut self, node: &mut Send) -> T {
+        self.maybe_visit(&mut node.recv);
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_shadowarg(&mut self, node: &mut Shadowarg) -> T {
+        T::default()
+    }
+
+    fn on_splat(&mut self, node: &mut Splat) -> T {
+        self.maybe_visit(&mut node.value)
+    }
+
+    #[allow(unused_variables)]
+    fn on_str(&mut self, node: &mut Str) -> T {
+        T::default()
+    }
+
+    fn on_super(&mut self, node: &mut Super) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_sym(&mut self, node: &mut Sym) -> T {
+        T::default()
+    }
+
+    #[allow(unused_variables)]
+    fn on_true(&mut self, node: &mut True) -> T {
+        T::default()
+    }
+
+    fn on_undef(&mut self, node: &mut Undef) -> T {
+        self.visit_all(&mut node.names)
+    }
+
+    fn on_unless_guard(&mut self, node: &mut UnlessGuard) -> T {
+        self.visit(&mut node.cond)
+    }
+
+    fn on_until(&mut self, node: &mut Until) -> T {
+        self.visit(&mut node.cond);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_until_post(&mut self, node: &mut UntilPost) -> T {
+        self.visit(&mut node.cond);
+        self.visit(&mut node.body)
+    }
+
+    fn on_when(&mut self, node: &mut When) -> T {
+        self.visit_all(&mut node.patterns);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_while(&mut self, node: &mut While) -> T {
+        self.visit(&mut node.cond);
+        self.maybe_visit(&mut node.body)
+    }
+
+    fn on_while_post(&mut self, node: &mut WhilePost) -> T {
+        self.visit(&mut node.cond);
+        self.visit(&mut node.body)
+    }
+
+    fn on_xheredoc(&mut self, node: &mut XHeredoc) -> T {
+        self.visit_all(&mut node.parts)
+    }
+
+    fn on_xstr(&mut self, node: &mut Xstr) -> T {
+        self.visit_all(&mut node.parts)
+    }
+
+    fn on_yield(&mut self, node: &mut Yield) -> T {
+        self.visit_all(&mut node.args)
+    }
+
+    #[allow(unused_variables)]
+    fn on_zsuper(&mut self, node: &mut ZSuper) -> T {
+        T::default()
+    }
+
+    fn visit(&mut self, node: &mut Node) -> T {
+        self.walk(node)
+    }
+
+    /// Calls `on_*` handler of the given node, can be used by `visit` overrides
+    fn walk(&mut self, node: &mut Node) -> T {
+        match node {
+            Node::Alias(inner) => self.on_alias(inner),
+            Node::And(inner) => self.on_and(inner),
+            Node::AndAsgn(inner) => self.on_and_asgn(inner),
+            Node::AnonymousBlockarg(inner) => self.on_anonymous_blockarg(inner),
+            Node::Arg(inner) => self.on_arg(inner),
+            Node::Args(inner) => self.on_args(inner),
+            Node::Array(inner) => self.on_array(inner),
+            Node::ArrayPattern(inner) => self.on_array_pattern(inner),
+            Node::ArrayPatternWithTail(inner) => self.on_array_pattern_with_tail(inner),
+            Node::BackRef(inner) => self.on_back_ref(inner),
+            Node::Begin(inner) => self.on_begin(inner),
+            Node::Block(inner) => self.on_block(inner),
+            Node::Blockarg(inner) => self.on_blockarg(inner),
+            Node::BlockPass(inner) => self.on_block_pass(inner),
+            Node::Break(inner) => self.on_break(inner),
+            Node::Case(inner) => self.on_case(inner),
+            Node::CaseMatch(inner) => self.on_case_match(inner),
+            Node::Casgn(inner) => self.on_casgn(inner),
+            Node::Cbase(inner) => self.on_cbase(inner),
+            Node::Class(inner) => self.on_class(inner),
+            Node::Complex(inner) => self.on_complex(inner),
+            Node::Const(inner) => self.on_const(inner),
+            Node::ConstPattern(inner) => self.on_const_pattern(inner),
+            Node::CSend(inner) => self.on_csend(inner),
+            Node::Cvar(inner) => self.on_cvar(inner),
+            Node::Cvasgn(inner) => self.on_cvasgn(inner),
+            Node::Def(inner) => self.on_def(inner),
+            Node::Defined(inner) => self.on_defined(inner),
+            Node::Defs(inner) => self.on_defs(inner),
+            Node::Dstr(inner) => self.on_dstr(inner),
+            Node::Dsym(inner) => self.on_dsym(inner),
+            Node::EFlipFlop(inner) => self.on_eflipflop(inner),
+            Node::EmptyElse(inner) => self.on_empty_else(inner),
+            Node::Encoding(inner) => self.on_encoding(inner),
+            Node::Ensure(inner) => self.on_ensure(inner),
+            Node::Erange(inner) => self.on_erange(inner),
+            Node::Error(inner) => self.on_error(inner),
+            Node::False(inner) => self.on_false(inner),
+            Node::File(inner) => self.on_file(inner),
+            Node::FindPattern(inner) => self.on_find_pattern(inner),
+            Node::Float(inner) => self.on_float(inner),
+            Node::For(inner) => self.on_for(inner),
+            Node::ForwardArg(inner) => self.on_forward_arg(inner),
+            Node::ForwardedArgs(inner) => self.on_forwarded_args(inner),
+            Node::ForwardedBlockarg(inner) => self.on_forwarded_blockarg(inner),
+            Node::ForwardedKwrestarg(inner) => self.on_forwarded_kwrestarg(inner),
+            Node::ForwardedRestarg(inner) => self.on_forwarded_restarg(inner),
+            Node::Gvar(inner) => self.on_gvar(inner),
+            Node::Gvasgn(inner) => self.on_gvasgn(inner),
+            Node::Hash(inner) => self.on_hash(inner),
+            Node::HashPattern(inner) => self.on_hash_pattern(inner),
+            Node::Heredoc(inner) => self.on_heredoc(inner),
+            Node::If(inner) => self.on_if(inner),
+            Node::IfGuard(inner) => self.on_if_guard(inner),
+            Node::IFlipFlop(inner) => self.on_iflipflop(inner),
+            Node::IfMod(inner) => self.on_if_mod(inner),
+            Node::IfTernary(inner) => self.on_if_ternary(inner),
+            Node::Index(inner) => self.on_index(inner),
+            Node::IndexAsgn(inner) => self.on_index_asgn(inner),
+            Node::InMatch(inner) => self.on_in_match(inner),
+            Node::InPattern(inner) => self.on_in_pattern(inner),
+            Node::Int(inner) => self.on_int(inner),
+            Node::Irange(inner) => self.on_irange(inner),
+            Node::Itblock(inner) => self.on_itblock(inner),
+            Node::Ivar(inner) => self.on_ivar(inner),
+            Node::Ivasgn(inner) => self.on_ivasgn(inner),
+            Node::Kwarg(inner) => self.on_kwarg(inner),
+            Node::KwBegin(inner) => self.on_kwbegin(inner),
+            Node::Kwnilarg(inner) => self.on_kwnilarg(inner),
+            Node::Kwoptarg(inner) => self.on_kwoptarg(inner),
+            Node::Kwrestarg(inner) => self.on_kwrestarg(inner),
+            Node::Kwsplat(inner) => self.on_kwsplat(inner),
+            Node::Lambda(inner) => self.on_lambda(inner),
+            Node::Line(inner) => self.on_line(inner),
+            Node::Lvar(inner) => self.on_lvar(inner),
+            Node::Lvasgn(inner) => self.on_lvasgn(inner),
+            Node::Masgn(inner) => self.on_masgn(inner),
+            Node::MatchAlt(inner) => self.on_match_alt(inner),
+            Node::MatchAs(inner) => self.on_match_as(inner),
+            Node::MatchCurrentLine(inner) => self.on_match_current_line(inner),
+            Node::MatchNilPattern(inner) => self.on_match_nil_pattern(inner),
+            Node::MatchPattern(inner) => self.on_match_pattern(inner),
+            Node::MatchRest(inner) => self.on_match_rest(inner),
+            Node::MatchVar(inner) => self.on_match_var(inner),
+            Node::MatchWithLvasgn(inner) => self.on_match_with_lvasgn(inner),
+            Node::Mlhs(inner) => self.on_mlhs(inner),
+            Node::Module(inner) => self.on_module(inner),
+            Node::Next(inner) => self.on_next(inner),
+            Node::Nil(inner) => self.on_nil(inner),
+            Node::NthRef(inner) => self.on_nth_ref(inner),
+            Node::Numblock(inner) => self.on_numblock(inner),
+            Node::OpAsgn(inner) => self.on_op_asgn(inner),
+            Node::Optarg(inner) => self.on_optarg(inner),
+            Node::Or(inner) => self.on_or(inner),
+            Node::OrAsgn(inner) => self.on_or_asgn(inner),
+            Node::Pair(inner) => self.on_pair(inner),
+            Node::Pin(inner) => self.on_pin(inner),
+            Node::Postexe(inner) => self.on_postexe(inner),
+            Node::Preexe(inner) => self.on_preexe(inner),
+            Node::Procarg0(inner) => self.on_procarg0(inner),
+            Node::Rational(inner) => self.on_rational(inner),
+            Node::Redo(inner) => self.on_redo(inner),
+            Node::Regexp(inner) => self.on_regexp(inner),
+            Node::RegOpt(inner) => self.on_regopt(inner),
+            Node::Rescue(inner) => self.on_rescue(inner),
+            Node::RescueBody(inner) => self.on_rescue_body(inner),
+            Node::Restarg(inner) => self.on_restarg(inner),
+            Node::Retry(inner) => self.on_retry(inner),
+            Node::Return(inner) => self.on_return(inner),
+            Node::SClass(inner) => self.on_sclass(inner),
+            Node::Self_(inner) => self.on_self_(inner),
+            Node::Send(inner) => self.on_send(inner),
+            Node::Shadowarg(inner) => self.on_shadowarg(inner),
+            Node::Splat(inner) => self.on_splat(inner),
+            Node::Str(inner) => self.on_str(inner),
+            Node::Super(inner) => self.on_super(inner),
+            Node::Sym(inner) => self.on_sym(inner),
+            Node::True(inner) => self.on_true(inner),
+            Node::Undef(inner) => self.on_undef(inner),
+            Node::UnlessGuard(inner) => self.on_unless_guard(inner),
+            Node::Until(inner) => self.on_until(inner),
+            Node::UntilPost(inner) => self.on_until_post(inner),
+            Node::When(inner) => self.on_when(inner),
+            Node::While(inner) => self.on_while(inner),
+            Node::WhilePost(inner) => self.on_while_post(inner),
+            Node::XHeredoc(inner) => self.on_xheredoc(inner),
+            Node::Xstr(inner) => self.on_xstr(inner),
+            Node::Yield(inner) => self.on_yield(inner),
+            Node::ZSuper(inner) => self.on_zsuper(inner),
+        }
+    }
+}
@@ -0,0 +1,21 @@
+//! Printing AST back to Ruby source
+
+mod precedence;
+mod printer;
+mod strings;
+
+use crate::Node;
+use printer::Printer;
+
+/// Prints given AST as Ruby source.
+///
+/// Output is normalized (method calls always have parentheses, strings
+/// are double-quoted, heredocs use generated identifiers) and has no
+/// relation to the original formatting, but parsing it back gives the same
+/// AST (except for locations).
+///
+/// Local variables that are not assigned in the AST itself (i.e. declared
+/// via `Parser::static_env`) must be declared again before re-parsing.
+pub fn unparse(node: &Node) -> String {
+    Printer::new().print_program(node)
+}
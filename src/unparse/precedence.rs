@@ -0,0 +1,102 @@
+/// Binding power of printed expressions, from the loosest to the tightest.
+///
+/// A node is wrapped in parentheses when its precedence is lower
+/// than the precedence required by its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Prec {
+    /// Statement modifiers, `rescue` modifier, multiple assignment
+    Stmt,
+    /// `and`, `or`
+    Expr,
+    /// `not`, `return foo`, `foo in pattern`
+    Not,
+    /// `=`, `+=`
+    Assign,
+    /// `? :`
+    Ternary,
+    /// `..`, `...`
+    Range,
+    /// `||`
+    OrOp,
+    /// `&&`
+    AndOp,
+    /// `<=>`, `==`, `===`, `!=`, `=~`, `!~`
+    Equality,
+    /// `<`, `<=`, `>`, `>=`
+    Compare,
+    /// `|`, `^`
+    BitOr,
+    /// `&`
+    BitAnd,
+    /// `<<`, `>>`
+    Shift,
+    /// `+`, `-`
+    Additive,
+    /// `*`, `/`, `%`
+    Multiplicative,
+    /// `-foo`
+    UnaryMinus,
+    /// `**`
+    Pow,
+    /// `!foo`, `~foo`, `+foo`
+    Unary,
+    /// Literals, variables, method calls, keyword constructs with `end`
+    Primary,
+}
+
+impl Prec {
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Self::Stmt => Self::Expr,
+            Self::Expr => Self::Not,
+            Self::Not => Self::Assign,
+            Self::Assign => Self::Ternary,
+            Self::Ternary => Self::Range,
+            Self::Range => Self::OrOp,
+            Self::OrOp => Self::AndOp,
+            Self::AndOp => Self::Equality,
+            Self::Equality => Self::Compare,
+            Self::Compare => Self::BitOr,
+            Self::BitOr => Self::BitAnd,
+            Self::BitAnd => Self::Shift,
+            Self::Shift => Self::Additive,
+            Self::Additive => Self::Multiplicative,
+            Self::Multiplicative => Self::UnaryMinus,
+            Self::UnaryMinus => Self::Pow,
+            Self::Pow => Self::Unary,
+            Self::Unary | Self::Primary => Self::Primary,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Assoc {
+    Left,
+    Right,
+    None,
+}
+
+/// Returns precedence and associativity of a binary operator method
+pub(crate) fn binary_operator(method_name: &str) -> Option<(Prec, Assoc)> {
+    let result = match method_name {
+        "**" => (Prec::Pow, Assoc::Right),
+        "*" | "/" | "%" => (Prec::Multiplicative, Assoc::Left),
+        "+" | "-" => (Prec::Additive, Assoc::Left),
+        "<<" | ">>" => (Prec::Shift, Assoc::Left),
+        "&" => (Prec::BitAnd, Assoc::Left),
+        "|" | "^" => (Prec::BitOr, Assoc::Left),
+        "<" | "<=" | ">" | ">=" => (Prec::Compare, Assoc::Left),
+        "<=>" | "==" | "===" | "!=" | "=~" | "!~" => (Prec::Equality, Assoc::None),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Returns precedences required for the left and the right operand
+pub(crate) fn operands(prec: Prec, assoc: Assoc) -> (Prec, Prec) {
+    match assoc {
+        Assoc::Left => (prec, prec.next()),
+        Assoc::Right => (prec.next(), prec),
+        Assoc::None => (prec.next(), prec.next()),
+    }
+}
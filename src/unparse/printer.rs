@@ -0,0 +1,1577 @@
+use super::precedence::{binary_operator, operands, Assoc, Prec};
+use super::strings::{
+    continues_identifier, escape, escape_regexp, is_identifier, is_label, is_method_name,
+    is_plain_symbol, Quote,
+};
+use crate::nodes::*;
+use crate::Node;
+use crate::StringValue;
+
+enum SendForm {
+    /// `-foo`, `!foo`, `~foo`
+    Prefix(Prec),
+    /// `not foo`, used when operand binds looser than `!`
+    Not,
+    /// `foo + bar`
+    Binary(Prec, Assoc),
+    /// `foo.bar = baz`
+    Setter,
+    /// `foo.bar(baz)`
+    Call,
+}
+
+pub(crate) struct Printer {
+    out: String,
+    indent: usize,
+    at_bol: bool,
+    // bodies of heredocs started on the current line
+    heredocs: Vec<String>,
+}
+
+impl Printer {
+    pub(crate) fn new() -> Self {
+        Self {
+            out: String::new(),
+            indent: 0,
+            at_bol: false,
+            heredocs: vec![],
+        }
+    }
+
+    pub(crate) fn print_program(mut self, node: &Node) -> String {
+        match node {
+            Node::Begin(inner) if inner.begin_l.is_none() => {
+                for (idx, stmt) in inner.statements.iter().enumerate() {
+                    if idx > 0 {
+                        self.newline();
+                    }
+                    self.print(stmt, Prec::Stmt);
+                }
+            }
+            _ => self.print(node, Prec::Stmt),
+        }
+        self.finish()
+    }
+
+    fn finish(mut self) -> String {
+        if !self.heredocs.is_empty() {
+            self.newline();
+        }
+        self.out
+    }
+
+    //
+    // Output
+    //
+
+    fn write(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        if self.at_bol {
+            for _ in 0..self.indent {
+                self.out.push_str("  ");
+            }
+            self.at_bol = false;
+        }
+        self.out.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for body in std::mem::take(&mut self.heredocs) {
+            self.out.push_str(&body);
+        }
+        self.at_bol = true;
+    }
+
+    // Text with raw line breaks, bodies of nested heredocs go after each line
+    fn write_lines(&mut self, text: &str) {
+        for (idx, line) in text.split('\n').enumerate() {
+            if idx > 0 {
+                self.newline();
+            }
+            self.write(line);
+        }
+    }
+
+    fn print_list(&mut self, nodes: &[Node], ctx: Prec) {
+        for (idx, node) in nodes.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            self.print(node, ctx);
+        }
+    }
+
+    //
+    // Precedence
+    //
+
+    fn print(&mut self, node: &Node, ctx: Prec) {
+        if self.prec(node) < ctx {
+            self.write("(");
+            self.print_node(node, Prec::Stmt);
+            self.write(")");
+        } else {
+            self.print_node(node, ctx);
+        }
+    }
+
+    fn prec(&self, node: &Node) -> Prec {
+        match node {
+            Node::Send(inner) => match self.send_form(&inner.recv, &inner.method_name, &inner.args)
+            {
+                SendForm::Prefix(prec) | SendForm::Binary(prec, _) => prec,
+                SendForm::Not => Prec::Not,
+                SendForm::Setter => Prec::Assign,
+                SendForm::Call => Prec::Primary,
+            },
+            Node::CSend(inner) if is_setter(&inner.method_name, &inner.args) => Prec::Assign,
+            Node::And(inner) => self.logical_prec(&inner.lhs, &inner.rhs, Prec::AndOp),
+            Node::Or(inner) => self.logical_prec(&inner.lhs, &inner.rhs, Prec::OrOp),
+            Node::Lvasgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::Ivasgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::Gvasgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::Cvasgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::Casgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::IndexAsgn(inner) if inner.value.is_some() => Prec::Assign,
+            Node::OpAsgn(_) | Node::OrAsgn(_) | Node::AndAsgn(_) => Prec::Assign,
+            Node::IfTernary(_) => Prec::Ternary,
+            Node::Irange(_) | Node::Erange(_) | Node::IFlipFlop(_) | Node::EFlipFlop(_) => {
+                Prec::Range
+            }
+            Node::MatchWithLvasgn(_) => Prec::Equality,
+            Node::Return(inner) if !inner.args.is_empty() => Prec::Not,
+            Node::Break(inner) if !inner.args.is_empty() => Prec::Not,
+            Node::Next(inner) if !inner.args.is_empty() => Prec::Not,
+            Node::InMatch(_) | Node::MatchPattern(_) => Prec::Not,
+            Node::Rescue(inner) if is_rescue_mod(inner) => Prec::Stmt,
+            Node::Masgn(_)
+            | Node::WhilePost(_)
+            | Node::UntilPost(_)
+            | Node::Alias(_)
+            | Node::Undef(_)
+            | Node::Preexe(_)
+            | Node::Postexe(_) => Prec::Stmt,
+            // `if`/`while` modifiers fall back to the keyword form
+            _ => Prec::Primary,
+        }
+    }
+
+    fn logical_prec(&self, lhs: &Node, rhs: &Node, prec: Prec) -> Prec {
+        if self.prec(lhs) >= prec && self.prec(rhs) >= prec.next() {
+            prec
+        } else {
+            Prec::Expr
+        }
+    }
+
+    fn send_form(&self, recv: &Option<Node>, method_name: &str, args: &[Node]) -> SendForm {
+        let recv = match recv {
+            Some(recv) => recv,
+            None => return SendForm::Call,
+        };
+
+        if args.is_empty() {
+            return match method_name {
+                // `-1` is a literal, not a call
+                "-@" | "+@" if is_numeric(recv) => SendForm::Call,
+                "-@" => SendForm::Prefix(Prec::UnaryMinus),
+                "+@" | "~" => SendForm::Prefix(Prec::Unary),
+                "!" if self.prec(recv) < Prec::Unary => SendForm::Not,
+                "!" => SendForm::Prefix(Prec::Unary),
+                _ => SendForm::Call,
+            };
+        }
+
+        if let [arg] = args {
+            if is_plain_arg(arg) {
+                if let Some((prec, assoc)) = binary_operator(method_name) {
+                    return SendForm::Binary(prec, assoc);
+                }
+            }
+        }
+
+        if is_setter(method_name, args) {
+            return SendForm::Setter;
+        }
+
+        SendForm::Call
+    }
+
+    //
+    // Nodes
+    //
+
+    fn print_node(&mut self, node: &Node, ctx: Prec) {
+        match node {
+            Node::Alias(inner) => {
+                self.write("alias ");
+                self.print(&inner.to, Prec::Primary);
+                self.write(" ");
+                self.print(&inner.from, Prec::Primary);
+            }
+            Node::And(inner) => self.print_logical(&inner.lhs, &inner.rhs, Prec::AndOp),
+            Node::AndAsgn(inner) => self.print_op_asgn(&inner.recv, "&&", &inner.value),
+            Node::AnonymousBlockarg(_)
+            | Node::Arg(_)
+            | Node::Blockarg(_)
+            | Node::ForwardArg(_)
+            | Node::Kwarg(_)
+            | Node::Kwnilarg(_)
+            | Node::Kwoptarg(_)
+            | Node::Kwrestarg(_)
+            | Node::Optarg(_)
+            | Node::Procarg0(_)
+            | Node::Restarg(_)
+            | Node::Shadowarg(_) => self.print_arg(node),
+            Node::Args(inner) => self.print_arg_list(&inner.args),
+            Node::Array(inner) => {
+                self.write("[");
+                self.print_list(&inner.elements, Prec::Assign);
+                self.write("]");
+            }
+            Node::ArrayPattern(_)
+            | Node::ArrayPatternWithTail(_)
+            | Node::ConstPattern(_)
+            | Node::FindPattern(_)
+            | Node::HashPattern(_)
+            | Node::MatchAlt(_)
+            | Node::MatchAs(_)
+            | Node::MatchNilPattern(_)
+            | Node::MatchRest(_)
+            | Node::MatchVar(_)
+            | Node::Pin(_) => self.print_pattern(node),
+            Node::BackRef(inner) => self.write(&inner.name),
+            Node::Begin(inner) => {
+                self.write("(");
+                self.print_inline_statements(&inner.statements);
+                self.write(")");
+            }
+            Node::Block(inner) => {
+                self.print_block(&inner.call, inner.args.as_ref(), inner.body.as_ref())
+            }
+            Node::BlockPass(inner) => {
+                self.write("&");
+                self.print(&inner.value, Prec::Assign);
+            }
+            Node::Break(inner) => self.print_keyword_cmd("break", &inner.args),
+            Node::Case(inner) => self.print_case(inner),
+            Node::CaseMatch(inner) => self.print_case_match(inner),
+            Node::Casgn(inner) => {
+                self.print_const_path(&inner.scope, &inner.name);
+                self.print_asgn_value(&inner.value);
+            }
+            Node::Cbase(_) => {}
+            Node::Class(inner) => {
+                self.write("class ");
+                self.print(&inner.name, Prec::Primary);
+                if let Some(superclass) = &inner.superclass {
+                    self.write(" < ");
+                    self.print(superclass, Prec::Expr);
+                }
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::Complex(inner) => self.write(&inner.value),
+            Node::Const(inner) => self.print_const_path(&inner.scope, &inner.name),
+            Node::CSend(inner) => self.print_call(
+                Some(&inner.recv),
+                "&.",
+                &inner.method_name,
+                &inner.args,
+                false,
+            ),
+            Node::Cvar(inner) => self.write(&inner.name),
+            Node::Cvasgn(inner) => {
+                self.write(&inner.name);
+                self.print_asgn_value(&inner.value);
+            }
+            Node::Def(inner) => {
+                self.write("def ");
+                self.write(&inner.name);
+                self.print_def_args(inner.args.as_ref());
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::Defined(inner) => {
+                self.write("defined?(");
+                self.print(&inner.value, Prec::Expr);
+                self.write(")");
+            }
+            Node::Defs(inner) => {
+                self.write("def ");
+                self.print(&inner.definee, Prec::Primary);
+                self.write(".");
+                self.write(&inner.name);
+                self.print_def_args(inner.args.as_ref());
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::Dstr(inner) => {
+                if is_concatenation(&inner.parts) {
+                    for (idx, part) in inner.parts.iter().enumerate() {
+                        if idx > 0 {
+                            self.write(" ");
+                        }
+                        self.print(part, Prec::Primary);
+                    }
+                } else {
+                    self.write("\"");
+                    self.print_parts(&inner.parts, Quote::Double);
+                    self.write("\"");
+                }
+            }
+            Node::Dsym(inner) => {
+                self.write(":\"");
+                self.print_parts(&inner.parts, Quote::Double);
+                self.write("\"");
+            }
+            Node::EFlipFlop(inner) => self.print_range(&inner.left, "...", &inner.right),
+            Node::EmptyElse(_) => {}
+            Node::Encoding(_) => self.write("__ENCODING__"),
+            Node::Ensure(_) => self.print_kw_begin(std::slice::from_ref(node)),
+            Node::Erange(inner) => self.print_range(&inner.left, "...", &inner.right),
+            Node::Error(_) => {}
+            Node::False(_) => self.write("false"),
+            Node::File(_) => self.write("__FILE__"),
+            Node::Float(inner) => self.write(&inner.value),
+            Node::For(inner) => {
+                self.write("for ");
+                match &inner.iterator {
+                    Node::Mlhs(mlhs) => self.print_mlhs_items(&mlhs.items),
+                    iterator => self.print_lhs(iterator),
+                }
+                self.write(" in ");
+                self.print(&inner.iteratee, Prec::Expr);
+                self.print_statements(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::ForwardedArgs(_) => self.write("..."),
+            Node::ForwardedBlockarg(_) => self.write("&"),
+            Node::ForwardedKwrestarg(_) => self.write("**"),
+            Node::ForwardedRestarg(_) => self.write("*"),
+            Node::Gvar(inner) => self.write(&inner.name),
+            Node::Gvasgn(inner) => {
+                self.write(&inner.name);
+                self.print_asgn_value(&inner.value);
+            }
+            Node::Hash(inner) => {
+                if inner.pairs.is_empty() {
+                    self.write("{}");
+                } else {
+                    self.write("{ ");
+                    self.print_list(&inner.pairs, Prec::Assign);
+                    self.write(" }");
+                }
+            }
+            Node::Heredoc(inner) => self.print_heredoc(&inner.parts, false),
+            Node::If(inner) => {
+                self.print_if(&inner.cond, inner.if_true.as_ref(), inner.if_false.as_ref())
+            }
+            Node::IfGuard(inner) => {
+                self.write("if ");
+                self.print(&inner.cond, Prec::Expr);
+            }
+            Node::IFlipFlop(inner) => self.print_range(&inner.left, "..", &inner.right),
+            Node::IfMod(inner) => {
+                let (keyword, body) = match (&inner.if_true, &inner.if_false) {
+                    (Some(if_true), _) => ("if", if_true),
+                    (None, Some(if_false)) => ("unless", if_false),
+                    (None, None) => ("if", &inner.cond),
+                };
+                if ctx == Prec::Stmt {
+                    self.print(body, Prec::Stmt);
+                    self.write(" ");
+                    self.write(keyword);
+                    self.write(" ");
+                    self.print(&inner.cond, Prec::Expr);
+                } else {
+                    self.print_if(&inner.cond, inner.if_true.as_ref(), inner.if_false.as_ref())
+                }
+            }
+            Node::IfTernary(inner) => {
+                self.print(&inner.cond, Prec::Range);
+                self.write(" ? ");
+                self.print(&inner.if_true, Prec::Assign);
+                self.write(" : ");
+                self.print(&inner.if_false, Prec::Ternary);
+            }
+            Node::Index(inner) => {
+                self.print(&inner.recv, Prec::Primary);
+                self.write("[");
+                self.print_args(&inner.indexes);
+                self.write("]");
+            }
+            Node::IndexAsgn(inner) => {
+                self.print(&inner.recv, Prec::Primary);
+                self.write("[");
+                self.print_args(&inner.indexes);
+                self.write("]");
+                self.print_asgn_value(&inner.value);
+            }
+            Node::InMatch(inner) => {
+                self.print(&inner.value, Prec::Assign);
+                self.write(" in ");
+                self.print_pattern(&inner.pattern);
+            }
+            Node::MatchPattern(inner) => {
+                self.print(&inner.value, Prec::Assign);
+                self.write(" => ");
+                self.print_pattern(&inner.pattern);
+            }
+            Node::InPattern(inner) => self.print_in_pattern(inner),
+            Node::Int(inner) => self.write(&inner.value),
+            Node::Irange(inner) => self.print_range(&inner.left, "..", &inner.right),
+            Node::Ivar(inner) => self.write(&inner.name),
+            Node::Ivasgn(inner) => {
+                self.write(&inner.name);
+                self.print_asgn_value(&inner.value);
+            }
+            Node::Kwsplat(inner) => {
+                self.write("**");
+                self.print(&inner.value, Prec::Assign);
+            }
+            Node::KwBegin(inner) => self.print_kw_begin(&inner.statements),
+            Node::Lambda(_) => self.write("->"),
+            Node::Line(_) => self.write("__LINE__"),
+            Node::Lvar(inner) => self.write(&inner.name),
+            Node::Lvasgn(inner) => {
+                self.write(&inner.name);
+                self.print_asgn_value(&inner.value);
+            }
+            Node::Masgn(inner) => {
+                match &inner.lhs {
+                    Node::Mlhs(mlhs) => self.print_mlhs_items(&mlhs.items),
+                    lhs => self.print_lhs(lhs),
+                }
+                self.write(" = ");
+                self.print_value(&inner.rhs);
+            }
+            Node::MatchCurrentLine(inner) => self.print(&inner.re, Prec::Primary),
+            Node::MatchWithLvasgn(inner) => {
+                self.print(&inner.re, Prec::Primary);
+                self.write(" =~ ");
+                self.print(&inner.value, Prec::Equality.next());
+            }
+            Node::Mlhs(_) => self.print_lhs(node),
+            Node::Module(inner) => {
+                self.write("module ");
+                self.print(&inner.name, Prec::Primary);
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::Next(inner) => self.print_keyword_cmd("next", &inner.args),
+            Node::Nil(_) => self.write("nil"),
+            Node::NthRef(inner) => {
+                self.write("$");
+                self.write(&inner.name);
+            }
+            Node::Numblock(inner) => self.print_block(&inner.call, None, Some(&inner.body)),
+            Node::Itblock(inner) => self.print_block(&inner.call, None, Some(&inner.body)),
+            Node::OpAsgn(inner) => self.print_op_asgn(&inner.recv, &inner.operator, &inner.value),
+            Node::Or(inner) => self.print_logical(&inner.lhs, &inner.rhs, Prec::OrOp),
+            Node::OrAsgn(inner) => self.print_op_asgn(&inner.recv, "||", &inner.value),
+            Node::Pair(inner) => {
+                match &inner.key {
+                    Node::Sym(key) if key.name.valid && is_label(&key.name.to_string_lossy()) => {
+                        self.write(&key.name.to_string_lossy());
+                        self.write(":");
+                    }
+                    key => {
+                        self.print(key, Prec::Assign);
+                        self.write(" =>");
+                    }
+                }
+                self.write(" ");
+                self.print(&inner.value, Prec::Assign);
+            }
+            Node::Postexe(inner) => {
+                self.write("END {");
+                self.print_statements(inner.body.as_ref());
+                self.newline();
+                self.write("}");
+            }
+            Node::Preexe(inner) => {
+                self.write("BEGIN {");
+                self.print_statements(inner.body.as_ref());
+                self.newline();
+                self.write("}");
+            }
+            Node::Rational(inner) => self.write(&inner.value),
+            Node::Redo(_) => self.write("redo"),
+            Node::Regexp(inner) => {
+                self.write("/");
+                self.print_parts(&inner.parts, Quote::Regexp);
+                self.write("/");
+                if let Some(options) = &inner.options {
+                    self.print(options, Prec::Primary);
+                }
+            }
+            Node::RegOpt(inner) => {
+                let options: String = inner.options.iter().collect();
+                self.write(&options);
+            }
+            Node::Rescue(inner) => {
+                if is_rescue_mod(inner) {
+                    self.print_rescue_mod(inner, Prec::Expr)
+                } else {
+                    self.print_kw_begin(std::slice::from_ref(node))
+                }
+            }
+            Node::RescueBody(inner) => self.print_rescue_body(inner),
+            Node::Retry(_) => self.write("retry"),
+            Node::Return(inner) => self.print_keyword_cmd("return", &inner.args),
+            Node::SClass(inner) => {
+                self.write("class << ");
+                self.print(&inner.expr, Prec::Expr);
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("end");
+            }
+            Node::Self_(_) => self.write("self"),
+            Node::Send(inner) => {
+                let recv = inner.recv.as_ref();
+                match self.send_form(&inner.recv, &inner.method_name, &inner.args) {
+                    SendForm::Prefix(prec) => {
+                        let recv = recv.expect("prefix operator always has an operand");
+                        self.write(inner.method_name.trim_end_matches('@'));
+                        if starts_with_sign(recv) {
+                            self.write(" ");
+                        }
+                        self.print(recv, prec);
+                    }
+                    SendForm::Not => {
+                        self.write("not ");
+                        self.print(recv.expect("not always has an operand"), Prec::Not);
+                    }
+                    SendForm::Binary(prec, assoc) => {
+                        let recv = recv.expect("binary operator always has a receiver");
+                        let (lhs_prec, rhs_prec) = operands(prec, assoc);
+                        if inner.method_name == "**" && starts_with_sign(recv) {
+                            // `-2 ** 2` is `-(2 ** 2)`
+                            self.write("(");
+                            self.print(recv, Prec::Stmt);
+                            self.write(")");
+                        } else {
+                            self.print(recv, lhs_prec);
+                        }
+                        self.write(" ");
+                        self.write(&inner.method_name);
+                        self.write(" ");
+                        self.print(&inner.args[0], rhs_prec);
+                    }
+                    SendForm::Setter => {
+                        self.print_setter(recv, ".", &inner.method_name, &inner.args[0])
+                    }
+                    SendForm::Call => {
+                        self.print_call(recv, ".", &inner.method_name, &inner.args, false)
+                    }
+                }
+            }
+            Node::Splat(inner) => {
+                self.write("*");
+                if let Some(value) = &inner.value {
+                    self.print(value, Prec::Assign);
+                }
+            }
+            Node::Str(inner) => {
+                self.write("\"");
+                self.write(&escape(&inner.value, Quote::Double, true));
+                self.write("\"");
+            }
+            Node::Super(inner) => {
+                self.write("super(");
+                self.print_args(&inner.args);
+                self.write(")");
+            }
+            Node::Sym(inner) => self.print_sym(&inner.name),
+            Node::True(_) => self.write("true"),
+            Node::Undef(inner) => {
+                self.write("undef ");
+                self.print_list(&inner.names, Prec::Primary);
+            }
+            Node::UnlessGuard(inner) => {
+                self.write("unless ");
+                self.print(&inner.cond, Prec::Expr);
+            }
+            Node::Until(inner) => self.print_loop(
+                "until",
+                &inner.cond,
+                inner.body.as_ref(),
+                inner.begin_l.is_none() && ctx == Prec::Stmt,
+            ),
+            Node::UntilPost(inner) => {
+                self.print(&inner.body, Prec::Primary);
+                self.write(" until ");
+                self.print(&inner.cond, Prec::Expr);
+            }
+            Node::When(inner) => self.print_when(inner),
+            Node::While(inner) => self.print_loop(
+                "while",
+                &inner.cond,
+                inner.body.as_ref(),
+                inner.begin_l.is_none() && ctx == Prec::Stmt,
+            ),
+            Node::WhilePost(inner) => {
+                self.print(&inner.body, Prec::Primary);
+                self.write(" while ");
+                self.print(&inner.cond, Prec::Expr);
+            }
+            Node::XHeredoc(inner) => self.print_heredoc(&inner.parts, true),
+            Node::Xstr(inner) => {
+                self.write("`");
+                self.print_parts(&inner.parts, Quote::Backtick);
+                self.write("`");
+            }
+            Node::Yield(inner) => {
+                self.write("yield");
+                if !inner.args.is_empty() {
+                    self.write("(");
+                    self.print_args(&inner.args);
+                    self.write(")");
+                }
+            }
+            Node::ZSuper(_) => self.write("super"),
+        }
+    }
+
+    //
+    // Statements and bodies
+    //
+
+    // Statements of a body, each on its own indented line
+    fn print_statements(&mut self, body: Option<&Node>) {
+        match body {
+            Some(Node::Begin(inner)) if inner.begin_l.is_none() => {
+                self.print_indented(&inner.statements)
+            }
+            Some(node) => self.print_indented(std::slice::from_ref(node)),
+            None => {}
+        }
+    }
+
+    fn print_indented(&mut self, statements: &[Node]) {
+        self.indent += 1;
+        for stmt in statements {
+            self.newline();
+            self.print(stmt, Prec::Stmt);
+        }
+        self.indent -= 1;
+    }
+
+    fn print_inline_statements(&mut self, statements: &[Node]) {
+        for (idx, stmt) in statements.iter().enumerate() {
+            if idx > 0 {
+                self.write("; ");
+            }
+            self.print(stmt, Prec::Stmt);
+        }
+    }
+
+    // Body of `def`, `class`, `begin` or `do`-block that can have
+    // `rescue`/`else`/`ensure` clauses
+    fn print_body(&mut self, body: Option<&Node>) {
+        match body {
+            Some(Node::Rescue(inner)) => {
+                self.print_statements(inner.body.as_ref());
+                for rescue_body in inner.rescue_bodies.iter() {
+                    self.newline();
+                    self.print(rescue_body, Prec::Stmt);
+                }
+                if inner.else_.is_some() {
+                    self.newline();
+                    self.write("else");
+                    self.print_statements(inner.else_.as_ref());
+                }
+            }
+            Some(Node::Ensure(inner)) => {
+                self.print_body(inner.body.as_ref());
+                self.newline();
+                self.write("ensure");
+                self.print_statements(inner.ensure.as_ref());
+            }
+            _ => self.print_statements(body),
+        }
+    }
+
+    fn print_kw_begin(&mut self, statements: &[Node]) {
+        self.write("begin");
+        match statements {
+            [stmt] => self.print_body(Some(stmt)),
+            _ => self.print_indented(statements),
+        }
+        self.newline();
+        self.write("end");
+    }
+
+    fn print_rescue_body(&mut self, rescue_body: &RescueBody) {
+        self.write("rescue");
+        match &rescue_body.exc_list {
+            Some(Node::Array(exc_list)) if exc_list.begin_l.is_none() => {
+                self.write(" ");
+                self.print_list(&exc_list.elements, Prec::Assign);
+            }
+            Some(exc_list) => {
+                self.write(" ");
+                self.print(exc_list, Prec::Assign);
+            }
+            None => {}
+        }
+        if let Some(exc_var) = &rescue_body.exc_var {
+            self.write(" => ");
+            self.print_lhs(exc_var);
+        }
+        self.print_statements(rescue_body.body.as_ref());
+    }
+
+    // `ctx` is `Expr` for `stmt rescue stmt` and `Ternary` for `arg rescue arg`
+    fn print_rescue_mod(&mut self, rescue: &Rescue, ctx: Prec) {
+        if let Some(body) = &rescue.body {
+            match body {
+                // `a rescue b rescue c`
+                Node::Rescue(inner) if is_rescue_mod(inner) => self.print_rescue_mod(inner, ctx),
+                _ => self.print(body, ctx),
+            }
+        }
+        self.write(" rescue ");
+        if let Some(Node::RescueBody(rescue_body)) = rescue.rescue_bodies.first() {
+            if let Some(body) = &rescue_body.body {
+                self.print(body, Prec::Ternary);
+            }
+        }
+    }
+
+    //
+    // Control flow
+    //
+
+    fn print_if(&mut self, cond: &Node, if_true: Option<&Node>, if_false: Option<&Node>) {
+        self.write("if ");
+        self.print(cond, Prec::Expr);
+        self.print_statements(if_true);
+
+        let mut if_false = if_false;
+        while let Some(node) = if_false {
+            self.newline();
+            match node {
+                Node::If(inner) => {
+                    self.write("elsif ");
+                    self.print(&inner.cond, Prec::Expr);
+                    self.print_statements(inner.if_true.as_ref());
+                    if_false = inner.if_false.as_ref();
+                }
+                _ => {
+                    self.write("else");
+                    self.print_statements(Some(node));
+                    if_false = None;
+                }
+            }
+        }
+
+        self.newline();
+        self.write("end");
+    }
+
+    fn print_loop(&mut self, keyword: &str, cond: &Node, body: Option<&Node>, modifier: bool) {
+        match body {
+            // `begin; end while foo` is a different node
+            Some(body) if modifier && !matches!(body, Node::KwBegin(_)) => {
+                self.print(body, Prec::Stmt);
+                self.write(" ");
+                self.write(keyword);
+                self.write(" ");
+                self.print(cond, Prec::Expr);
+            }
+            _ => {
+                self.write(keyword);
+                self.write(" ");
+                self.print(cond, Prec::Expr);
+                self.print_statements(body);
+                self.newline();
+                self.write("end");
+            }
+        }
+    }
+
+    fn print_case(&mut self, case: &Case) {
+        self.write("case");
+        if let Some(expr) = &case.expr {
+            self.write(" ");
+            self.print(expr, Prec::Expr);
+        }
+        for when in case.when_bodies.iter() {
+            self.newline();
+            self.print(when, Prec::Stmt);
+        }
+        if case.else_body.is_some() {
+            self.newline();
+            self.write("else");
+            self.print_statements(case.else_body.as_ref());
+        }
+        self.newline();
+        self.write("end");
+    }
+
+    fn print_when(&mut self, when: &When) {
+        self.write("when ");
+        self.print_list(&when.patterns, Prec::Assign);
+        if when.patterns.last().map(is_endless_range).unwrap_or(false) {
+            self.write(" then");
+        }
+        self.print_statements(when.body.as_ref());
+    }
+
+    fn print_case_match(&mut self, case: &CaseMatch) {
+        self.write("case ");
+        self.print(&case.expr, Prec::Expr);
+        for in_body in case.in_bodies.iter() {
+            self.newline();
+            self.print(in_body, Prec::Stmt);
+        }
+        if let Some(else_body) = &case.else_body {
+            self.newline();
+            self.write("else");
+            if !matches!(else_body, Node::EmptyElse(_)) {
+                self.print_statements(Some(else_body));
+            }
+        }
+        self.newline();
+        self.write("end");
+    }
+
+    fn print_in_pattern(&mut self, in_pattern: &InPattern) {
+        self.write("in ");
+        self.print_pattern(&in_pattern.pattern);
+        if let Some(guard) = &in_pattern.guard {
+            self.write(" ");
+            self.print(guard, Prec::Stmt);
+        } else if is_endless_range(&in_pattern.pattern) {
+            // `in 1..` followed by a newline continues the range
+            self.write(" then");
+        }
+        self.print_statements(in_pattern.body.as_ref());
+    }
+
+    fn print_keyword_cmd(&mut self, keyword: &str, args: &[Node]) {
+        self.write(keyword);
+        if !args.is_empty() {
+            self.write(" ");
+            self.print_args(args);
+        }
+    }
+
+    //
+    // Operators
+    //
+
+    fn print_logical(&mut self, lhs: &Node, rhs: &Node, prec: Prec) {
+        if self.logical_prec(lhs, rhs, prec) == prec {
+            self.print(lhs, prec);
+            self.write(if prec == Prec::AndOp { " && " } else { " || " });
+            self.print(rhs, prec.next());
+        } else {
+            self.print(lhs, Prec::Expr);
+            self.write(if prec == Prec::AndOp { " and " } else { " or " });
+            self.print(rhs, Prec::Not);
+        }
+    }
+
+    fn print_range(&mut self, left: &Option<Node>, operator: &str, right: &Option<Node>) {
+        if let Some(left) = left {
+            self.print(left, Prec::OrOp);
+        }
+        self.write(operator);
+        if let Some(right) = right {
+            self.print(right, Prec::OrOp);
+        }
+    }
+
+    //
+    // Method calls
+    //
+
+    fn print_call(
+        &mut self,
+        recv: Option<&Node>,
+        dot: &str,
+        method_name: &str,
+        args: &[Node],
+        force_parens: bool,
+    ) {
+        if let Some(recv) = recv {
+            self.print(recv, Prec::Primary);
+            self.write(dot);
+        }
+        self.write(method_name);
+        if !args.is_empty() {
+            self.write("(");
+            self.print_args(args);
+            self.write(")");
+        } else if recv.is_none() || force_parens || !is_method_name(method_name) {
+            // `foo` could be read back as a local variable
+            self.write("()");
+        }
+    }
+
+    fn print_setter(&mut self, recv: Option<&Node>, dot: &str, method_name: &str, value: &Node) {
+        if let Some(recv) = recv {
+            self.print(recv, Prec::Primary);
+            self.write(dot);
+        }
+        self.write(method_name.trim_end_matches('='));
+        self.write(" = ");
+        self.print_value(value);
+    }
+
+    fn print_args(&mut self, args: &[Node]) {
+        for (idx, arg) in args.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            let is_last = args[idx + 1..]
+                .iter()
+                .all(|arg| matches!(arg, Node::BlockPass(_) | Node::ForwardedBlockarg(_)));
+            match arg {
+                // `foo(a: 1)`
+                Node::Hash(inner)
+                    if inner.begin_l.is_none() && !inner.pairs.is_empty() && is_last =>
+                {
+                    self.print_list(&inner.pairs, Prec::Assign)
+                }
+                _ => self.print(arg, Prec::Assign),
+            }
+        }
+    }
+
+    fn print_block(&mut self, call: &Node, args: Option<&Node>, body: Option<&Node>) {
+        let is_lambda = matches!(call, Node::Lambda(_));
+        match call {
+            Node::Send(inner) => self.print_call(
+                inner.recv.as_ref(),
+                ".",
+                &inner.method_name,
+                &inner.args,
+                false,
+            ),
+            Node::CSend(inner) => self.print_call(
+                Some(&inner.recv),
+                "&.",
+                &inner.method_name,
+                &inner.args,
+                false,
+            ),
+            _ => self.print(call, Prec::Primary),
+        }
+
+        if is_lambda {
+            if let Some(Node::Args(args)) = args {
+                self.write("(");
+                self.print_arg_list(&args.args);
+                self.write(")");
+            }
+        }
+
+        // only `do`-blocks can have `rescue` clauses
+        let has_clauses = matches!(body, Some(Node::Rescue(_)) | Some(Node::Ensure(_)));
+        self.write(if has_clauses { " do" } else { " {" });
+
+        if !is_lambda {
+            if let Some(Node::Args(args)) = args {
+                self.write(" |");
+                self.print_block_params(&args.args);
+                self.write("|");
+            }
+        }
+
+        if body.is_some() {
+            if has_clauses {
+                self.print_body(body);
+            } else {
+                self.print_statements(body);
+            }
+            self.newline();
+        } else {
+            self.write(" ");
+        }
+        self.write(if has_clauses { "end" } else { "}" });
+    }
+
+    //
+    // Assignment
+    //
+
+    fn print_asgn_value(&mut self, value: &Option<Node>) {
+        if let Some(value) = value {
+            self.write(" = ");
+            self.print_value(value);
+        }
+    }
+
+    // Right-hand side of an assignment, the only place where
+    // `rescue` modifier is allowed inside of an expression
+    fn print_value(&mut self, value: &Node) {
+        match value {
+            Node::Rescue(inner) if is_rescue_mod(inner) => {
+                self.print_rescue_mod(inner, Prec::Ternary)
+            }
+            _ => self.print(value, Prec::Assign),
+        }
+    }
+
+    fn print_op_asgn(&mut self, recv: &Node, operator: &str, value: &Node) {
+        self.print_lhs(recv);
+        self.write(" ");
+        self.write(operator);
+        self.write("= ");
+        self.print_value(value);
+    }
+
+    fn print_const_path(&mut self, scope: &Option<Node>, name: &str) {
+        match scope {
+            Some(Node::Cbase(_)) => self.write("::"),
+            Some(scope) => {
+                self.print(scope, Prec::Primary);
+                self.write("::");
+            }
+            None => {}
+        }
+        self.write(name);
+    }
+
+    // Assignment target without a value
+    fn print_lhs(&mut self, node: &Node) {
+        match node {
+            Node::Lvasgn(inner) => self.write(&inner.name),
+            Node::Ivasgn(inner) => self.write(&inner.name),
+            Node::Gvasgn(inner) => self.write(&inner.name),
+            Node::Cvasgn(inner) => self.write(&inner.name),
+            Node::Casgn(inner) => self.print_const_path(&inner.scope, &inner.name),
+            Node::Send(inner) => {
+                if let Some(recv) = &inner.recv {
+                    self.print(recv, Prec::Primary);
+                    self.write(".");
+                }
+                self.write(inner.method_name.trim_end_matches('='));
+            }
+            Node::CSend(inner) => {
+                self.print(&inner.recv, Prec::Primary);
+                self.write("&.");
+                self.write(inner.method_name.trim_end_matches('='));
+            }
+            Node::Index(inner) => {
+                self.print(&inner.recv, Prec::Primary);
+                self.write("[");
+                self.print_args(&inner.indexes);
+                self.write("]");
+            }
+            Node::IndexAsgn(inner) => {
+                self.print(&inner.recv, Prec::Primary);
+                self.write("[");
+                self.print_args(&inner.indexes);
+                self.write("]");
+            }
+            Node::Splat(inner) => {
+                self.write("*");
+                if let Some(value) = &inner.value {
+                    self.print_lhs(value);
+                }
+            }
+            Node::Mlhs(inner) => {
+                self.write("(");
+                self.print_mlhs_items(&inner.items);
+                self.write(")");
+            }
+            _ => self.print(node, Prec::Primary),
+        }
+    }
+
+    fn print_mlhs_items(&mut self, items: &[Node]) {
+        for (idx, item) in items.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            self.print_lhs(item);
+        }
+        // `a, = foo`
+        if let [item] = items {
+            if !matches!(item, Node::Splat(_)) {
+                self.write(",");
+            }
+        }
+    }
+
+    //
+    // Arguments
+    //
+
+    fn print_def_args(&mut self, args: Option<&Node>) {
+        if let Some(Node::Args(args)) = args {
+            self.write("(");
+            self.print_arg_list(&args.args);
+            self.write(")");
+        }
+    }
+
+    fn print_arg_list(&mut self, args: &[Node]) {
+        let (shadowargs, args): (Vec<&Node>, Vec<&Node>) = args
+            .iter()
+            .partition(|arg| matches!(arg, Node::Shadowarg(_)));
+
+        for (idx, arg) in args.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            self.print_arg(arg);
+        }
+        if !shadowargs.is_empty() {
+            self.write("; ");
+            for (idx, arg) in shadowargs.iter().enumerate() {
+                if idx > 0 {
+                    self.write(", ");
+                }
+                self.print_arg(arg);
+            }
+        }
+    }
+
+    fn print_block_params(&mut self, args: &[Node]) {
+        self.print_arg_list(args);
+        // `|a|` is `procarg0`, `|a,|` is a plain `arg`
+        let plain_args = args
+            .iter()
+            .filter(|arg| !matches!(arg, Node::Shadowarg(_)))
+            .collect::<Vec<_>>();
+        if let [Node::Arg(_)] = plain_args[..] {
+            if plain_args.len() == args.len() {
+                self.write(",");
+            }
+        }
+    }
+
+    fn print_arg(&mut self, node: &Node) {
+        match node {
+            Node::AnonymousBlockarg(_) => self.write("&"),
+            Node::Arg(inner) => self.write(&inner.name),
+            Node::Blockarg(inner) => {
+                self.write("&");
+                self.write(&inner.name);
+            }
+            Node::ForwardArg(_) => self.write("..."),
+            Node::Kwarg(inner) => {
+                self.write(&inner.name);
+                self.write(":");
+            }
+            Node::Kwnilarg(_) => self.write("**nil"),
+            Node::Kwoptarg(inner) => {
+                self.write(&inner.name);
+                self.write(": ");
+                self.print(&inner.default, Prec::Assign);
+            }
+            Node::Kwrestarg(inner) => {
+                self.write("**");
+                if let Some(name) = &inner.name {
+                    self.write(name);
+                }
+            }
+            Node::Mlhs(inner) => {
+                self.write("(");
+                for (idx, item) in inner.items.iter().enumerate() {
+                    if idx > 0 {
+                        self.write(", ");
+                    }
+                    self.print_arg(item);
+                }
+                self.write(")");
+            }
+            Node::Optarg(inner) => {
+                self.write(&inner.name);
+                self.write(" = ");
+                self.print(&inner.default, Prec::Assign);
+            }
+            Node::Procarg0(inner) => match &inner.args[..] {
+                [arg @ Node::Arg(_)] => self.print_arg(arg),
+                args => {
+                    self.write("(");
+                    for (idx, arg) in args.iter().enumerate() {
+                        if idx > 0 {
+                            self.write(", ");
+                        }
+                        self.print_arg(arg);
+                    }
+                    self.write(")");
+                }
+            },
+            Node::Restarg(inner) => {
+                self.write("*");
+                if let Some(name) = &inner.name {
+                    self.write(name);
+                }
+            }
+            Node::Shadowarg(inner) => self.write(&inner.name),
+            _ => self.print(node, Prec::Primary),
+        }
+    }
+
+    //
+    // Literals
+    //
+
+    fn print_sym(&mut self, name: &StringValue) {
+        let plain = name.valid && is_plain_symbol(&name.to_string_lossy());
+        if plain {
+            self.write(":");
+            self.write(&name.to_string_lossy());
+        } else {
+            self.write(":\"");
+            self.write(&escape(name, Quote::Double, true));
+            self.write("\"");
+        }
+    }
+
+    // Parts of an interpolated literal
+    fn print_parts(&mut self, parts: &[Node], quote: Quote) {
+        for (idx, part) in parts.iter().enumerate() {
+            let next = parts.get(idx + 1);
+            match part {
+                Node::Str(inner) => {
+                    let text = match quote {
+                        Quote::Regexp => escape_regexp(&inner.value),
+                        _ => {
+                            let mut text = escape(&inner.value, quote, next.is_none());
+                            // keep line break the lexer has split content on
+                            if quote != Quote::Heredoc
+                                && inner.value.as_bytes().ends_with(b"\n")
+                                && matches!(next, Some(Node::Str(_)))
+                            {
+                                text.truncate(text.len() - 2);
+                                text.push('\n');
+                            }
+                            text
+                        }
+                    };
+                    if quote == Quote::Heredoc {
+                        self.write_lines(&text);
+                    } else {
+                        self.write(&text);
+                    }
+                }
+                Node::Dstr(inner) if inner.begin_l.is_none() => {
+                    self.print_parts(&inner.parts, quote)
+                }
+                Node::Begin(inner) => {
+                    self.write("#{");
+                    self.print_inline_statements(&inner.statements);
+                    self.write("}");
+                }
+                Node::Ivar(_)
+                | Node::Cvar(_)
+                | Node::Gvar(_)
+                | Node::BackRef(_)
+                | Node::NthRef(_) => {
+                    let followed_by_name = match next {
+                        Some(Node::Str(next)) => continues_identifier(next.value.as_bytes()),
+                        _ => false,
+                    };
+                    if followed_by_name {
+                        self.write("#{");
+                        self.print(part, Prec::Stmt);
+                        self.write("}");
+                    } else {
+                        self.write("#");
+                        self.print(part, Prec::Primary);
+                    }
+                }
+                _ => {
+                    self.write("#{");
+                    self.print(part, Prec::Stmt);
+                    self.write("}");
+                }
+            }
+        }
+    }
+
+    fn print_heredoc(&mut self, parts: &[Node], xstr: bool) {
+        // `<<~` splits body into lines, `<<-` keeps it as a single string
+        let squiggly = !parts.iter().any(|part| match part {
+            Node::Str(inner) => {
+                let bytes = inner.value.as_bytes();
+                bytes
+                    .iter()
+                    .take(bytes.len().saturating_sub(1))
+                    .any(|byte| *byte == b'\n')
+            }
+            _ => false,
+        });
+
+        // interpolated code must not end up on the current line
+        let mut printer = Printer::new();
+        let mut at_line_start = true;
+        for (idx, part) in parts.iter().enumerate() {
+            match part {
+                Node::Str(inner) if squiggly && at_line_start => {
+                    let text = escape(&inner.value, Quote::Heredoc, idx + 1 == parts.len());
+                    // escaped leading space is not removed by dedenting
+                    match text.strip_prefix(' ') {
+                        Some(rest) => {
+                            printer.write("\\x20");
+                            printer.write_lines(rest);
+                        }
+                        None => printer.write_lines(&text),
+                    }
+                }
+                _ => printer.print_parts(std::slice::from_ref(part), Quote::Heredoc),
+            }
+            at_line_start = match part {
+                Node::Str(inner) => inner.value.as_bytes().ends_with(b"\n"),
+                _ => false,
+            };
+        }
+        let mut body = printer.finish();
+        if !body.ends_with('\n') {
+            body.push('\n');
+        }
+
+        let mut id = "HEREDOC".to_owned();
+        while body.lines().any(|line| line.trim() == id) {
+            id.push('_');
+        }
+
+        self.write(if squiggly { "<<~" } else { "<<-" });
+        if xstr {
+            self.write(&format!("`{}`", id));
+        } else {
+            self.write(&id);
+        }
+        self.heredocs.push(format!("{}{}\n", body, id));
+    }
+
+    //
+    // Pattern matching
+    //
+
+    fn print_pattern(&mut self, node: &Node) {
+        match node {
+            Node::ArrayPattern(inner) => {
+                self.write("[");
+                self.print_patterns(&inner.elements);
+                self.write("]");
+            }
+            Node::ArrayPatternWithTail(inner) => {
+                self.write("[");
+                self.print_patterns(&inner.elements);
+                self.write(",]");
+            }
+            Node::ConstPattern(inner) => {
+                self.print(&inner.const_, Prec::Primary);
+                self.write("(");
+                match &inner.pattern {
+                    Node::ArrayPattern(pattern) => self.print_patterns(&pattern.elements),
+                    Node::ArrayPatternWithTail(pattern) => {
+                        self.print_patterns(&pattern.elements);
+                        self.write(",");
+                    }
+                    Node::FindPattern(pattern) => self.print_patterns(&pattern.elements),
+                    Node::HashPattern(pattern) => self.print_hash_patterns(&pattern.elements),
+                    pattern => self.print_pattern(pattern),
+                }
+                self.write(")");
+            }
+            Node::FindPattern(inner) => {
+                self.write("[");
+                self.print_patterns(&inner.elements);
+                self.write("]");
+            }
+            Node::HashPattern(inner) => {
+                if inner.elements.is_empty() {
+                    self.write("{}");
+                } else {
+                    self.write("{ ");
+                    self.print_hash_patterns(&inner.elements);
+                    self.write(" }");
+                }
+            }
+            Node::MatchAlt(inner) => {
+                // parentheses in patterns don't create nodes
+                let lhs_parens = matches!(inner.lhs, Node::MatchAs(_));
+                let rhs_parens = matches!(inner.rhs, Node::MatchAs(_) | Node::MatchAlt(_));
+                self.print_pattern_in_parens(&inner.lhs, lhs_parens);
+                self.write(" | ");
+                self.print_pattern_in_parens(&inner.rhs, rhs_parens);
+            }
+            Node::MatchAs(inner) => {
+                let parens = matches!(inner.value, Node::MatchAs(_));
+                self.print_pattern_in_parens(&inner.value, parens);
+                self.write(" => ");
+                self.print_pattern(&inner.as_);
+            }
+            Node::MatchNilPattern(_) => self.write("**nil"),
+            Node::MatchRest(inner) => {
+                self.write("*");
+                if let Some(name) = &inner.name {
+                    self.print_pattern(name);
+                }
+            }
+            Node::MatchVar(inner) => self.write(&inner.name),
+            Node::Pin(inner) => {
+                self.write("^");
+                self.print(&inner.var, Prec::Primary);
+            }
+            Node::Irange(inner) => self.print_pattern_range(&inner.left, "..", &inner.right),
+            Node::Erange(inner) => self.print_pattern_range(&inner.left, "...", &inner.right),
+            _ => self.print(node, Prec::Primary),
+        }
+    }
+
+    fn print_pattern_in_parens(&mut self, node: &Node, parens: bool) {
+        if parens {
+            self.write("(");
+            self.print_pattern(node);
+            self.write(")");
+        } else {
+            self.print_pattern(node);
+        }
+    }
+
+    fn print_pattern_range(&mut self, left: &Option<Node>, operator: &str, right: &Option<Node>) {
+        if let Some(left) = left {
+            self.print(left, Prec::Primary);
+        }
+        self.write(operator);
+        if let Some(right) = right {
+            self.print(right, Prec::Primary);
+        }
+    }
+
+    fn print_patterns(&mut self, patterns: &[Node]) {
+        for (idx, pattern) in patterns.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            self.print_pattern(pattern);
+        }
+    }
+
+    fn print_hash_patterns(&mut self, patterns: &[Node]) {
+        for (idx, pattern) in patterns.iter().enumerate() {
+            if idx > 0 {
+                self.write(", ");
+            }
+            match pattern {
+                // `in { a: }`
+                Node::MatchVar(inner) => {
+                    self.write(&inner.name);
+                    self.write(":");
+                }
+                Node::Pair(inner) => {
+                    match &inner.key {
+                        Node::Sym(key) => self.print_label(&key.name),
+                        key => {
+                            self.print(key, Prec::Primary);
+                            self.write(":");
+                        }
+                    }
+                    self.write(" ");
+                    self.print_pattern(&inner.value);
+                }
+                Node::MatchRest(inner) => {
+                    self.write("**");
+                    if let Some(name) = &inner.name {
+                        self.print_pattern(name);
+                    }
+                }
+                _ => self.print_pattern(pattern),
+            }
+        }
+    }
+
+    fn print_label(&mut self, name: &StringValue) {
+        if name.valid && is_label(&name.to_string_lossy()) {
+            self.write(&name.to_string_lossy());
+        } else {
+            self.write("\"");
+            self.write(&escape(name, Quote::Double, true));
+            self.write("\"");
+        }
+        self.write(":");
+    }
+}
+
+fn is_numeric(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Int(_) | Node::Float(_) | Node::Rational(_) | Node::Complex(_)
+    )
+}
+
+// `- -1` and `- -foo` need a space between operators
+fn starts_with_sign(node: &Node) -> bool {
+    let value = match node {
+        Node::Int(inner) => &inner.value,
+        Node::Float(inner) => &inner.value,
+        Node::Rational(inner) => &inner.value,
+        Node::Complex(inner) => &inner.value,
+        Node::Send(inner) if inner.args.is_empty() && inner.recv.is_some() => {
+            return inner.method_name == "-@" || inner.method_name == "+@"
+        }
+        _ => return false,
+    };
+    value.starts_with('-') || value.starts_with('+')
+}
+
+fn is_endless_range(node: &Node) -> bool {
+    match node {
+        Node::Irange(inner) => inner.right.is_none(),
+        Node::Erange(inner) => inner.right.is_none(),
+        _ => false,
+    }
+}
+
+fn is_plain_arg(node: &Node) -> bool {
+    !matches!(
+        node,
+        Node::BlockPass(_)
+            | Node::ForwardedBlockarg(_)
+            | Node::ForwardedRestarg(_)
+            | Node::Splat(_)
+            | Node::Kwsplat(_)
+            | Node::ForwardedArgs(_)
+    )
+}
+
+fn is_setter(method_name: &str, args: &[Node]) -> bool {
+    match (method_name.strip_suffix('='), args) {
+        (Some(attr), [arg]) => is_identifier(attr) && is_plain_arg(arg),
+        _ => false,
+    }
+}
+
+// `foo rescue bar`
+fn is_rescue_mod(rescue: &Rescue) -> bool {
+    match &rescue.rescue_bodies[..] {
+        [Node::RescueBody(rescue_body)] => {
+            rescue.body.is_some()
+                && rescue.else_.is_none()
+                && rescue_body.exc_list.is_none()
+                && rescue_body.exc_var.is_none()
+                && rescue_body.body.is_some()
+        }
+        _ => false,
+    }
+}
+
+// `"a" "b"` is a `dstr` of two separate literals
+fn is_concatenation(parts: &[Node]) -> bool {
+    parts.iter().any(|part| match part {
+        Node::Str(inner) => inner.begin_l.is_some(),
+        Node::Dstr(inner) => inner.begin_l.is_some(),
+        Node::Heredoc(_) => true,
+        _ => false,
+    })
+}
@@ -0,0 +1,164 @@
+use crate::StringValue;
+
+/// Kind of the literal that content is printed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Quote {
+    /// `"..."` and `:"..."`
+    Double,
+    /// `` `...` ``
+    Backtick,
+    /// body of `<<-HEREDOC`, newlines are printed as is
+    Heredoc,
+    /// `/.../`, content is printed by `escape_regexp`
+    Regexp,
+}
+
+fn push_escaped_byte(out: &mut String, byte: u8) {
+    out.push_str(&format!("\\x{:02X}", byte))
+}
+
+/// Escapes content of an interpolated literal, so that lexing it back
+/// gives exactly the same bytes.
+///
+/// `last_part` is false for parts followed by another part of the same
+/// literal, a trailing `#` is escaped then to not start an interpolation.
+pub(crate) fn escape(value: &StringValue, quote: Quote, last_part: bool) -> String {
+    let bytes = value.as_bytes();
+    let mut out = String::with_capacity(bytes.len());
+
+    // non-UTF-8 content can't be written to the output as is
+    let text = if value.valid {
+        std::str::from_utf8(bytes).ok()
+    } else {
+        None
+    };
+
+    let mut idx = 0;
+    while idx < bytes.len() {
+        let byte = bytes[idx];
+        match byte {
+            b'\\' => out.push_str("\\\\"),
+            b'"' if quote == Quote::Double => out.push_str("\\\""),
+            b'`' if quote == Quote::Backtick => out.push_str("\\`"),
+            b'#' => match bytes.get(idx + 1) {
+                Some(b'{') | Some(b'@') | Some(b'$') => out.push_str("\\#"),
+                None if !last_part => out.push_str("\\#"),
+                _ => out.push('#'),
+            },
+            b'\n' if quote == Quote::Heredoc => out.push('\n'),
+            b'\n' => out.push_str("\\n"),
+            b'\t' => out.push_str("\\t"),
+            b'\r' => out.push_str("\\r"),
+            0x1b => out.push_str("\\e"),
+            0x00..=0x1f | 0x7f => push_escaped_byte(&mut out, byte),
+            0x80..=0xff => match text {
+                Some(text) => {
+                    // copy the whole multibyte char
+                    let len = text[idx..].chars().next().map(char::len_utf8).unwrap_or(1);
+                    out.push_str(&text[idx..idx + len]);
+                    idx += len;
+                    continue;
+                }
+                None => push_escaped_byte(&mut out, byte),
+            },
+            _ => out.push(byte as char),
+        }
+        idx += 1;
+    }
+
+    out
+}
+
+/// Prepares raw source of a regexp part for `/.../`
+pub(crate) fn escape_regexp(value: &StringValue) -> String {
+    let text = if value.valid {
+        value.to_string_lossy()
+    } else {
+        let mut out = String::new();
+        for byte in value.as_bytes() {
+            if *byte < 0x80 {
+                out.push(*byte as char)
+            } else {
+                push_escaped_byte(&mut out, *byte)
+            }
+        }
+        out
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                out.push(c);
+                if let Some(c) = chars.next() {
+                    out.push(c)
+                }
+            }
+            // lexer drops the backslash, so `\/` is read back as `/`
+            '/' => out.push_str("\\/"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_ident_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic() || !c.is_ascii()
+}
+
+fn is_ident_char(c: char) -> bool {
+    c == '_' || c.is_ascii_alphanumeric() || !c.is_ascii()
+}
+
+/// Returns true if `name` is a plain identifier or constant name
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) if is_ident_start(c) => chars.all(is_ident_char),
+        _ => false,
+    }
+}
+
+/// Returns true if `name` can be written after `def` or `.` as is
+pub(crate) fn is_method_name(name: &str) -> bool {
+    let plain = name
+        .strip_suffix(|c| c == '?' || c == '!' || c == '=')
+        .unwrap_or(name);
+    is_identifier(plain)
+}
+
+/// Returns true if `name` can be written as `name:` in a hash or a hash pattern
+pub(crate) fn is_label(name: &str) -> bool {
+    let plain = name.strip_suffix(|c| c == '?' || c == '!').unwrap_or(name);
+    is_identifier(plain)
+}
+
+const OPERATOR_SYMBOLS: &[&str] = &[
+    "+", "-", "*", "/", "%", "**", "==", "===", "!=", "=~", "!~", "<", "<=", ">", ">=", "<=>",
+    "<<", ">>", "&", "|", "^", "~", "!", "+@", "-@", "[]", "[]=", "`",
+];
+
+/// Returns true if a symbol with a given name can be written as `:name`
+pub(crate) fn is_plain_symbol(name: &str) -> bool {
+    if OPERATOR_SYMBOLS.contains(&name) {
+        return true;
+    }
+    let unprefixed = name
+        .strip_prefix("@@")
+        .or_else(|| name.strip_prefix('@'))
+        .or_else(|| name.strip_prefix('$'));
+    match unprefixed {
+        Some(var_name) => is_identifier(var_name),
+        None => is_method_name(name),
+    }
+}
+
+/// Returns true if interpolated variable (like `#@foo`) can't be followed
+/// by this text without changing its name
+pub(crate) fn continues_identifier(text: &[u8]) -> bool {
+    match text.first() {
+        Some(byte) => *byte == b'_' || byte.is_ascii_alphanumeric() || *byte >= 0x80,
+        None => false,
+    }
+}
@@ -0,0 +1,109 @@
+use lib_ruby_parser::analysis::{AstIndex, NodeId};
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> Node {
+    let options = ParserOptions {
+        buffer_name: "(ast_index_test)".to_owned(),
+        ..Default::default()
+    };
+    let ParserResult { ast, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    ast.unwrap()
+}
+
+fn types(index: &AstIndex, ids: impl Iterator<Item = NodeId>) -> Vec<&'static str> {
+    ids.map(|id| index.node(id).str_type()).collect()
+}
+
+#[test]
+fn test_pre_order_ids() {
+    let ast = parse("foo(1, 2); bar");
+    let index = AstIndex::build(&ast);
+
+    assert_eq!(
+        types(&index, index.nodes().map(|(id, _)| id)),
+        vec!["begin", "send", "int", "int", "send"]
+    );
+
+    let root = index.root();
+    assert_eq!(index.node(root), &ast);
+    assert_eq!(index.parent(root), None);
+    assert_eq!(index.depth(root), 0);
+    assert_eq!(
+        types(&index, index.children(root).iter().copied()),
+        vec!["send", "send"]
+    );
+
+    let foo = index.children(root)[0];
+    assert_eq!(index.id_of(index.node(foo)), Some(foo));
+    for child in index.children(foo) {
+        assert_eq!(index.parent(*child), Some(foo));
+        assert_eq!(index.depth(*child), 2);
+        assert!(*child > foo);
+    }
+}
+
+#[test]
+fn test_ancestors() {
+    let ast = parse("class A; def m; foo; end; end");
+    let index = AstIndex::build(&ast);
+
+    let send = index.node_at_offset(16).unwrap();
+    assert_eq!(index.node(send).str_type(), "send");
+    assert_eq!(types(&index, index.ancestors(send)), vec!["def", "class"]);
+    assert_eq!(index.ancestors(index.root()).count(), 0);
+}
+
+#[test]
+fn test_node_at_offset() {
+    let src = "a = [1, 22]";
+    let ast = parse(src);
+    let index = AstIndex::build(&ast);
+
+    let at = |pos: usize| {
+        index
+            .node_at_offset(pos)
+            .map(|id| index.node(id).str_type())
+    };
+    assert_eq!(at(0), Some("lvasgn"));
+    assert_eq!(at(4), Some("array"));
+    assert_eq!(at(5), Some("int"));
+    assert_eq!(at(9), Some("int"));
+    assert_eq!(at(src.len()), None);
+}
+
+#[test]
+fn test_nodes_in_range() {
+    let src = "foo(1, 2); bar";
+    let ast = parse(src);
+    let index = AstIndex::build(&ast);
+
+    let ids = index.nodes_in_range(&Range::new(0, 9));
+    assert_eq!(types(&index, ids.into_iter()), vec!["send", "int", "int"]);
+
+    let ids = index.nodes_in_range(&Range::new(5, 6));
+    assert!(ids.is_empty());
+}
+
+#[test]
+fn test_node_at_offset_in_heredoc_body() {
+    // heredoc body is located outside of `foo(...)`
+    let src = "foo(<<~HERE, bar)\n  text\nHERE\n";
+    let ast = parse(src);
+    let index = AstIndex::build(&ast);
+
+    let id = index.node_at_offset(src.find("text").unwrap()).unwrap();
+    assert_eq!(index.node(id).str_type(), "str");
+    assert_eq!(types(&index, index.ancestors(id)), vec!["heredoc", "send"]);
+}
+
+#[test]
+fn test_id_of_nodes_with_the_same_begin() {
+    let ast = parse("a.b.c.d");
+    let index = AstIndex::build(&ast);
+
+    for (id, node) in index.nodes() {
+        assert_eq!(index.id_of(node), Some(id));
+    }
+    assert_eq!(index.id_of(&parse("a.b.c.d")), None);
+}
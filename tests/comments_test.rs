@@ -0,0 +1,118 @@
+use lib_ruby_parser::analysis::AstIndex;
+use lib_ruby_parser::source::comments::{associate, associate_locations};
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(comments_test)".to_owned(),
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+fn associated_sources(src: &str) -> Vec<(String, Vec<String>)> {
+    let ParserResult {
+        ast,
+        comments,
+        input,
+        ..
+    } = parse(src);
+    let ast = ast.unwrap();
+    let mut mapping = associate_locations(&ast, &comments, &input)
+        .into_iter()
+        .map(|(range, comments)| {
+            (
+                range.source(&input).unwrap(),
+                comments
+                    .iter()
+                    .map(|comment| comment.location.source(&input).unwrap())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+    mapping.sort();
+    mapping
+}
+
+#[test]
+fn test_leading_comment_of_def() {
+    let src = "# Returns 42\n# @return [Integer]\ndef foo\n  42\nend\n";
+
+    assert_eq!(
+        associated_sources(src),
+        vec![(
+            "def foo\n  42\nend".to_owned(),
+            vec!["# Returns 42".to_owned(), "# @return [Integer]".to_owned()]
+        )]
+    );
+}
+
+#[test]
+fn test_leading_comments_of_class_and_method() {
+    let src = "# class doc\nclass Foo\n  # method doc\n  def bar; end\nend\n";
+
+    assert_eq!(
+        associated_sources(src),
+        vec![
+            (
+                "class Foo\n  # method doc\n  def bar; end\nend".to_owned(),
+                vec!["# class doc".to_owned()]
+            ),
+            ("def bar; end".to_owned(), vec!["# method doc".to_owned()]),
+        ]
+    );
+}
+
+#[test]
+fn test_trailing_comment() {
+    let src = "foo = 1 # one\nbar = 2\n";
+
+    assert_eq!(
+        associated_sources(src),
+        vec![("1".to_owned(), vec!["# one".to_owned()])]
+    );
+}
+
+#[test]
+fn test_directives_are_skipped() {
+    let src = "#!/usr/bin/env ruby\n# frozen_string_literal: true\n# encoding: utf-8\n# doc\nfoo\n";
+
+    assert_eq!(
+        associated_sources(src),
+        vec![("foo".to_owned(), vec!["# doc".to_owned()])]
+    );
+}
+
+#[test]
+fn test_associate_by_node_id() {
+    let src = "# first\nfoo\n# second\nfoo\n";
+    let ParserResult {
+        ast,
+        comments,
+        input,
+        ..
+    } = parse(src);
+    let ast = ast.unwrap();
+
+    let stmts = match &ast {
+        Node::Begin(begin) => &begin.statements,
+        other => panic!("expected begin node, got {:?}", other),
+    };
+
+    let index = AstIndex::build(&ast);
+    let mapping = associate(&ast, &comments, &input);
+    assert_eq!(mapping.len(), 2);
+    assert_eq!(
+        mapping[&index.id_of(&stmts[0]).unwrap()],
+        vec![comments[0].clone()]
+    );
+    assert_eq!(
+        mapping[&index.id_of(&stmts[1]).unwrap()],
+        vec![comments[1].clone()]
+    );
+
+    let mapping = associate_locations(&ast, &comments, &input);
+    assert_eq!(mapping[&Range::new(8, 11)], vec![comments[0].clone()]);
+    assert_eq!(mapping[&Range::new(21, 24)], vec![comments[1].clone()]);
+}
@@ -49,8 +49,10 @@ impl DiagnosticMatcher {
         }
 
         let level = match &level[..] {
-            "error" => ErrorLevel::Error,
+            "note" => ErrorLevel::Note,
             "warning" => ErrorLevel::Warning,
+            "error" => ErrorLevel::Error,
+            "fatal" => ErrorLevel::Fatal,
             other => return Err(format!("unknown error level {:?}", other)),
         };
 
@@ -0,0 +1,141 @@
+use lib_ruby_parser::{
+    source::buffer::*, source::CustomDecoder, source::Range, Diagnostic, DiagnosticMessage,
+    DiagnosticRenderer, ErrorLevel,
+};
+
+fn render(source: &str, diagnostic: Diagnostic, renderer: DiagnosticRenderer) -> String {
+    let buffer = Buffer::new(
+        "(test_render)",
+        source.as_bytes().to_vec(),
+        CustomDecoder::default(),
+    );
+    renderer
+        .render(&diagnostic, &buffer.input)
+        .expect("failed to render diagnostic")
+}
+
+#[test]
+fn it_renders_single_line() {
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Warning,
+        DiagnosticMessage::FractionAfterNumeric,
+        Range::new(12, 16),
+    );
+
+    assert_eq!(
+        render(
+            "line 1\nvery long line 2\n",
+            diagnostic,
+            DiagnosticRenderer::default()
+        ),
+        vec![
+            "warning[E0001]: unexpected fraction part after numeric literal",
+            " --> (test_render):2:6",
+            "  |",
+            "2 | very long line 2",
+            "  |      ^^^^",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn it_renders_labels() {
+    let source = "foo(<<HERE)\nbar\n";
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::UnterminatedHeredoc("HERE".to_owned()),
+        Range::new(4, 10),
+    )
+    .with_label(Range::new(0, 3), "in this call")
+    .with_label(Range::new(15, 16), "end of file is reached here");
+
+    assert_eq!(
+        render(source, diagnostic, DiagnosticRenderer::default()),
+        vec![
+            "error[E0015]: can't find string \"HERE\" anywhere before EOF",
+            " --> (test_render):1:5",
+            "  |",
+            "1 | foo(<<HERE)",
+            "  | --- ^^^^^^",
+            "  | |",
+            "  | in this call",
+            "2 | bar",
+            "  |    - end of file is reached here",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn it_renders_multiline_ranges() {
+    let source = "class Foo\n  def bar\n  end\n  baz\n  qux\nend\n";
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::ClassDefinitionInMethodBody,
+        Range::new(0, source.len() - 1),
+    )
+    .with_label(Range::new(12, 25), "method");
+
+    assert_eq!(
+        render(source, diagnostic, DiagnosticRenderer::default()),
+        vec![
+            "error[E0038]: class definition in method body",
+            " --> (test_render):1:1",
+            "  |",
+            "1 | /   class Foo",
+            "2 | |     def bar",
+            "  | |  ___-",
+            "3 | | |   end",
+            "  | | |_____- method",
+            "...",
+            "5 | |     qux",
+            "6 | |   end",
+            "  | |_____^",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn it_expands_tabs_and_wide_chars() {
+    let source = "\t\"日本\" + x\n";
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::VoidValueExpression,
+        Range::new(source.find('x').unwrap(), source.find('x').unwrap() + 1),
+    );
+    let renderer = DiagnosticRenderer {
+        tab_width: 2,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        render(source, diagnostic, renderer),
+        vec![
+            "error[E0070]: void value expression",
+            " --> (test_render):1:12",
+            "  |",
+            "1 |   \"日本\" + x",
+            "  |            ^",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn it_renders_colors() {
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::VoidValueExpression,
+        Range::new(0, 1),
+    );
+    let renderer = DiagnosticRenderer {
+        colors: true,
+        ..Default::default()
+    };
+    let output = render("x\n", diagnostic, renderer);
+
+    assert!(output.starts_with("\x1b[0m\x1b[1;31merror[E0070]\x1b[0m: void value expression"));
+    assert!(output.ends_with("\x1b[0m\x1b[1;31m^\x1b[0m"));
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "encodings")]
+
+use lib_ruby_parser::source::{encodings, CustomDecoder, InputError, RecognizedEncoding};
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+
+fn parse(src: &[u8]) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(encodings_test)".to_owned(),
+        ..Default::default()
+    };
+    Parser::new(src, options).do_parse()
+}
+
+fn assert_decodes(src: &[u8], expected: &str) {
+    let ParserResult {
+        ast,
+        input,
+        diagnostics,
+        ..
+    } = parse(src);
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    let ast = ast.expect("expected AST to be returned");
+    assert_eq!(ast.expression().source(&input), Some(expected.to_owned()));
+}
+
+#[test]
+fn it_decodes_euc_jp() {
+    assert_decodes(b"# encoding: euc-jp\n\"\xA4\xA2\"", "\"あ\"");
+}
+
+#[test]
+fn it_decodes_shift_jis() {
+    assert_decodes(b"# encoding: shift_jis\n\"\x82\xA0\"", "\"あ\"");
+    assert_decodes(b"# encoding: windows-31j\n\"\x82\xA0\"", "\"あ\"");
+}
+
+#[test]
+fn it_decodes_single_byte_encodings() {
+    assert_decodes(b"# encoding: koi8-r\n\"\xC1\"", "\"а\"");
+    assert_decodes(b"# encoding: windows-1251\n\"\xE0\"", "\"а\"");
+    assert_decodes(b"# encoding: iso-8859-1\n\"\xE9\"", "\"é\"");
+    assert_decodes(b"# encoding: us-ascii\n\"a\"", "\"a\"");
+}
+
+fn decode(encoding: RecognizedEncoding, input: &[u8]) -> String {
+    String::from_utf8(encodings::decode(encoding, input).unwrap()).unwrap()
+}
+
+#[test]
+fn it_decodes_c1_controls() {
+    const C1: &[u8] = b"\x80\x85\x9F";
+    const DECODED_C1: &str = "\u{80}\u{85}\u{9F}";
+
+    assert_eq!(decode(RecognizedEncoding::ISO_8859_1, C1), DECODED_C1);
+    assert_eq!(decode(RecognizedEncoding::ISO_8859_9, C1), DECODED_C1);
+    assert_eq!(decode(RecognizedEncoding::ISO_8859_11, C1), DECODED_C1);
+
+    assert_eq!(decode(RecognizedEncoding::ISO_8859_9, b"\xF0"), "ğ");
+    assert_eq!(decode(RecognizedEncoding::ISO_8859_11, b"\xA1"), "ก");
+    assert_eq!(decode(RecognizedEncoding::Windows_1254, b"\x80"), "€");
+}
+
+#[test]
+fn it_reports_invalid_byte_sequences() {
+    let ParserResult { diagnostics, .. } = parse(b"# encoding: euc-jp\n\"\xA4\"");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].is_fatal());
+    assert_eq!(
+        diagnostics[0].render_message(),
+        "encoding error: DecodingError(\"invalid byte sequence in EUC-JP\")"
+    );
+}
+
+#[test]
+fn it_prefers_custom_decoder() {
+    fn decode(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+        match encoding {
+            RecognizedEncoding::KOI8_R => Ok(b"# encoding: koi8-r\ncustom".to_vec()),
+            encoding => encodings::decode(encoding, input),
+        }
+    }
+
+    let options = ParserOptions {
+        decoder: CustomDecoder {
+            f: Some(Box::new(decode)),
+        },
+        ..Default::default()
+    };
+    let ParserResult { ast, input, .. } =
+        Parser::new(b"# encoding: koi8-r\nfoo", options).do_parse();
+
+    assert_eq!(
+        ast.unwrap().expression().source(&input),
+        Some("custom".to_owned())
+    );
+}
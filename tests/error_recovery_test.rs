@@ -0,0 +1,21 @@
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+
+// Recovered ASTs are tested by fixtures in tests/fixtures/parser/error_recovery
+
+fn parse(src: &str, error_recovery: bool) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(error_recovery_test)".to_owned(),
+        debug: false,
+        error_recovery,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+#[test]
+fn test_valid_code_is_not_affected() {
+    let recovered = parse("foo; bar(1, 2); baz", true);
+    let regular = parse("foo; bar(1, 2); baz", false);
+    assert_eq!(recovered.ast, regular.ast);
+    assert!(recovered.diagnostics.is_empty());
+}
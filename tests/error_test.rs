@@ -1,6 +1,6 @@
 use lib_ruby_parser::{
     source::buffer::*, source::CustomDecoder, source::Range, Diagnostic, DiagnosticMessage,
-    ErrorLevel,
+    ErrorLevel, Parser, ParserOptions, ParserResult,
 };
 
 #[test]
@@ -30,3 +30,116 @@ fn it_renders() {
         .join("\n")
     );
 }
+
+#[test]
+fn it_has_stable_codes() {
+    let message = DiagnosticMessage::UnterminatedHeredoc("HERE".to_owned());
+    assert_eq!(message.code(), "E0015");
+    assert_eq!(message.name(), "unterminated_heredoc");
+
+    let message = DiagnosticMessage::AmbiguousFirstArgument { operator: b'-' };
+    assert_eq!(message.code(), "W0007");
+    assert_eq!(message.name(), "ambiguous_first_argument");
+    assert!(message.is_identified_by("W0007"));
+    assert!(message.is_identified_by("ambiguous_first_argument"));
+    assert!(!message.is_identified_by("E0007"));
+}
+
+fn parse_with_options(src: &str, options: ParserOptions) -> ParserResult {
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+#[test]
+fn it_reports_warnings_by_default() {
+    let ParserResult { diagnostics, .. } = parse_with_options("foo -1", ParserOptions::default());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].level, ErrorLevel::Warning);
+    assert_eq!(diagnostics[0].name(), "ambiguous_first_argument");
+}
+
+#[test]
+fn it_suppresses_warnings() {
+    let options = ParserOptions {
+        suppressed_warnings: vec!["ambiguous_first_argument".to_owned()],
+        ..Default::default()
+    };
+    let ParserResult {
+        ast, diagnostics, ..
+    } = parse_with_options("foo -1", options);
+
+    assert!(ast.is_some());
+    assert!(diagnostics.is_empty());
+}
+
+#[test]
+fn it_promotes_warnings() {
+    let options = ParserOptions {
+        promoted_warnings: vec!["W0007".to_owned()],
+        ..Default::default()
+    };
+    let ParserResult {
+        ast, diagnostics, ..
+    } = parse_with_options("foo -1", options);
+
+    assert!(ast.is_some());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].level, ErrorLevel::Error);
+    assert!(diagnostics[0].is_error());
+}
+
+#[test]
+fn it_reports_unsupported_encoding_as_fatal() {
+    let ParserResult { diagnostics, .. } =
+        parse_with_options("# encoding: emacs-mule\nfoo", ParserOptions::default());
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].is_fatal());
+    assert!(diagnostics[0].is_error());
+    assert_eq!(diagnostics[0].code(), diagnostics[0].message.code());
+}
+
+#[test]
+fn it_renders_expected_tokens() {
+    let message = DiagnosticMessage::UnexpectedTokenExpected {
+        got: "END_OF_INPUT".to_owned(),
+        expected: vec!["kEND".to_owned()],
+    };
+    assert_eq!(message.code(), "E0078");
+    assert_eq!(message.name(), "unexpected_token_expected");
+    assert_eq!(message.render(), "unexpected end-of-input, expected `end'");
+
+    let message = DiagnosticMessage::UnexpectedTokenExpected {
+        got: "tINTEGER".to_owned(),
+        expected: vec![
+            "kEND".to_owned(),
+            "kRESCUE".to_owned(),
+            "kRESCUE_MOD".to_owned(),
+            "kENSURE".to_owned(),
+        ],
+    };
+    assert_eq!(
+        message.render(),
+        "unexpected integer literal, expected `end', `rescue' or `ensure'"
+    );
+}
+
+#[test]
+fn it_reports_expected_tokens_if_requested() {
+    let ParserResult { diagnostics, .. } =
+        parse_with_options("alias $a 1", ParserOptions::default());
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].render_message(), "unexpected tINTEGER");
+
+    let options = ParserOptions {
+        expected_tokens: true,
+        ..Default::default()
+    };
+    let ParserResult { diagnostics, .. } = parse_with_options("alias $a 1", options);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].name(), "unexpected_token_expected");
+    assert_eq!(
+        diagnostics[0].render_message(),
+        "unexpected integer literal, expected global variable, numbered reference or back reference"
+    );
+}
@@ -0,0 +1,143 @@
+use lib_ruby_parser::{ContextItem, Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str, locals: &[&[&str]], context: Vec<ContextItem>) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(eval_context_test)".to_owned(),
+        locals: locals
+            .iter()
+            .map(|scope| scope.iter().map(|name| name.to_string()).collect())
+            .collect(),
+        context,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+fn diagnostic_names(result: &ParserResult) -> Vec<&'static str> {
+    result.diagnostics.iter().map(|d| d.name()).collect()
+}
+
+#[test]
+fn it_uses_known_locals() {
+    let ast = parse("foo", &[&["foo"]], vec![]).ast.unwrap();
+    assert!(matches!(ast, Node::Lvar(_)), "{:?}", ast);
+
+    let ast = parse("foo", &[], vec![]).ast.unwrap();
+    assert!(matches!(ast, Node::Send(_)), "{:?}", ast);
+}
+
+#[test]
+fn it_uses_known_locals_from_all_scopes() {
+    let ast = parse("[foo, bar]", &[&["foo"], &["bar"]], vec![])
+        .ast
+        .unwrap();
+    let elements = match ast {
+        Node::Array(array) => array.elements,
+        other => panic!("expected array, got {:?}", other),
+    };
+    assert!(elements.iter().all(|node| matches!(node, Node::Lvar(_))));
+}
+
+#[test]
+fn it_uses_known_locals_in_lexer() {
+    // `foo -1` is a binary operation if `foo` is a local variable
+    let result = parse("foo -1", &[&["foo"]], vec![]);
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+    match result.ast.unwrap() {
+        Node::Send(send) => assert_eq!(send.method_name, "-"),
+        other => panic!("expected send, got {:?}", other),
+    }
+}
+
+#[test]
+fn it_validates_class_definition_in_def() {
+    let result = parse("class Foo; end", &[], vec![ContextItem::Def]);
+    assert!(result.ast.is_none());
+    assert_eq!(
+        diagnostic_names(&result),
+        vec!["class_definition_in_method_body"]
+    );
+
+    // but it's allowed in a singleton class inside of a method
+    let result = parse(
+        "class Foo; end",
+        &[],
+        vec![ContextItem::Def, ContextItem::Sclass],
+    );
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn it_validates_return_in_class_body() {
+    let result = parse("return", &[], vec![ContextItem::Class]);
+    assert!(result.ast.is_none());
+    assert_eq!(
+        diagnostic_names(&result),
+        vec!["invalid_return_in_class_or_module_body"]
+    );
+
+    let result = parse("return", &[], vec![ContextItem::Class, ContextItem::Def]);
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn it_validates_numbered_params_in_block() {
+    let ast = parse("_1", &[], vec![ContextItem::Block]).ast.unwrap();
+    assert!(matches!(ast, Node::Lvar(_)), "{:?}", ast);
+
+    let ast = parse("_1", &[], vec![ContextItem::Def]).ast.unwrap();
+    assert!(matches!(ast, Node::Send(_)), "{:?}", ast);
+
+    let result = parse("it + _1", &[], vec![ContextItem::Block]);
+    assert!(result.ast.is_none());
+    assert_eq!(
+        diagnostic_names(&result),
+        vec!["numparam_not_allowed_with_it"]
+    );
+}
+
+#[test]
+fn it_validates_yield_in_class_body() {
+    let result = parse("yield", &[], vec![ContextItem::Module]);
+    assert!(result.ast.is_none());
+    assert_eq!(diagnostic_names(&result), vec!["invalid_yield"]);
+
+    // the error doesn't abort the rule
+    let options = ParserOptions {
+        context: vec![ContextItem::Module],
+        error_recovery: true,
+        ..Default::default()
+    };
+    let result = Parser::new(b"foo; yield; bar", options).do_parse();
+    assert_eq!(diagnostic_names(&result), vec!["invalid_yield"]);
+    assert_eq!(
+        result.ast.map(|ast| ast.inspect(0)).as_deref(),
+        Some(
+            r#"s(:begin,
+  s(:send, nil, "foo"),
+  s(:yield),
+  s(:send, nil, "bar"))"#
+        )
+    );
+
+    // blocks don't change the enclosing scope
+    let result = parse("yield", &[], vec![ContextItem::Class, ContextItem::Block]);
+    assert_eq!(diagnostic_names(&result), vec!["invalid_yield"]);
+
+    let result = parse(
+        "yield",
+        &[],
+        vec![ContextItem::Class, ContextItem::Def, ContextItem::Block],
+    );
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+}
+
+#[test]
+fn it_allows_super_outside_of_method() {
+    // it's a runtime error, not a syntax error
+    for src in ["super", "super()", "super 1"].iter() {
+        let result = parse(src, &[], vec![ContextItem::Sclass]);
+        assert!(result.ast.is_some());
+        assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+    }
+}
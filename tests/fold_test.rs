@@ -0,0 +1,141 @@
+use lib_ruby_parser::nodes::{If, IfMod, Lvar, OpAsgn, Send};
+use lib_ruby_parser::traverse::Fold;
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> Node {
+    let options = ParserOptions {
+        buffer_name: "(fold_test)".to_owned(),
+        debug: false,
+        ..Default::default()
+    };
+    let parser = Parser::new(src.as_bytes(), options);
+    let ParserResult { ast, .. } = parser.do_parse();
+    ast.expect("expected AST")
+}
+
+fn method_name(node: &Node) -> &str {
+    match node {
+        Node::Send(send) => &send.method_name,
+        other => panic!("expected send, got {:?}", other),
+    }
+}
+
+// Rewrites `foo if bar` to `if bar then foo end`
+struct IfModNormalizer;
+
+impl Fold for IfModNormalizer {
+    fn fold_if_mod(&mut self, node: IfMod) -> Node {
+        Node::If(Box::new(If {
+            cond: self.fold(node.cond),
+            if_true: self.maybe_fold(node.if_true),
+            if_false: self.maybe_fold(node.if_false),
+            begin_l: node.keyword_l.end(),
+            keyword_l: node.keyword_l,
+            else_l: None,
+            end_l: None,
+            expression_l: node.expression_l,
+        }))
+    }
+}
+
+// Rewrites `a += 1` to `a = a + 1` (only for local variables)
+struct OpAsgnExpander;
+
+impl Fold for OpAsgnExpander {
+    fn fold_op_asgn(&mut self, node: OpAsgn) -> Node {
+        let OpAsgn {
+            recv,
+            value,
+            operator,
+            operator_l,
+            expression_l,
+        } = node;
+        let value = self.fold(value);
+
+        match recv {
+            Node::Lvasgn(mut lvasgn) => {
+                let lvar = Node::Lvar(Box::new(Lvar {
+                    name: lvasgn.name.clone(),
+                    expression_l: lvasgn.name_l.clone(),
+                }));
+                let send_l = lvasgn.name_l.join(value.expression());
+                lvasgn.value = Some(Node::Send(Box::new(Send {
+                    recv: Some(lvar),
+                    method_name: operator,
+                    args: vec![value],
+                    dot_l: None,
+                    selector_l: Some(operator_l.adjust_end(-1)),
+                    begin_l: None,
+                    end_l: None,
+                    operator_l: None,
+                    expression_l: send_l,
+                })));
+                lvasgn.operator_l = Some(operator_l);
+                lvasgn.expression_l = expression_l;
+                Node::Lvasgn(lvasgn)
+            }
+            recv => Node::OpAsgn(Box::new(OpAsgn {
+                recv,
+                value,
+                operator,
+                operator_l,
+                expression_l,
+            })),
+        }
+    }
+}
+
+#[test]
+fn it_replaces_nodes() {
+    let ast = IfModNormalizer.fold(parse("foo if bar"));
+
+    match ast {
+        Node::If(if_) => {
+            assert_eq!(method_name(&if_.cond), "bar");
+            assert_eq!(method_name(if_.if_true.as_ref().unwrap()), "foo");
+            assert_eq!(if_.if_false, None);
+        }
+        other => panic!("expected if, got {:?}", other),
+    }
+}
+
+#[test]
+fn it_replaces_nested_nodes() {
+    let ast = IfModNormalizer.fold(parse("def m; [(foo if bar)]; end"));
+    let def = match ast {
+        Node::Def(def) => def,
+        other => panic!("expected def, got {:?}", other),
+    };
+    let array = match def.body {
+        Some(Node::Array(array)) => array,
+        other => panic!("expected array, got {:?}", other),
+    };
+    match &array.elements[..] {
+        [Node::Begin(begin)] => match &begin.statements[..] {
+            [Node::If(_)] => {}
+            other => panic!("expected if, got {:?}", other),
+        },
+        other => panic!("expected (begin), got {:?}", other),
+    }
+}
+
+#[test]
+fn it_expands_op_asgn() {
+    let ast = OpAsgnExpander.fold(parse("a = 1; a += 2"));
+    let stmts = match ast {
+        Node::Begin(begin) => begin.statements,
+        other => panic!("expected begin, got {:?}", other),
+    };
+    let lvasgn = match &stmts[1] {
+        Node::Lvasgn(lvasgn) => lvasgn,
+        other => panic!("expected lvasgn, got {:?}", other),
+    };
+    let send = match &lvasgn.value {
+        Some(Node::Send(send)) => send,
+        other => panic!("expected send, got {:?}", other),
+    };
+    assert_eq!(lvasgn.name, "a");
+    assert_eq!(send.method_name, "+");
+    assert!(matches!(send.recv, Some(Node::Lvar(_))));
+    assert!(matches!(send.args[..], [Node::Int(_)]));
+}
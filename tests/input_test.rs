@@ -0,0 +1,163 @@
+use lib_ruby_parser::source::buffer::{Buffer, Input};
+use lib_ruby_parser::source::{CustomDecoder, InputError, Range, RecognizedEncoding};
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+
+// ISO-8859-1 maps bytes to the first 256 codepoints
+fn decode_latin1(encoding: RecognizedEncoding, input: &[u8]) -> Result<Vec<u8>, InputError> {
+    match encoding {
+        RecognizedEncoding::ISO_8859_1 => Ok(input
+            .iter()
+            .map(|byte| *byte as char)
+            .collect::<String>()
+            .into_bytes()),
+        _ => Err(InputError::DecodingError("unsupported".to_owned())),
+    }
+}
+
+fn parse(
+    src: &[u8],
+    f: fn(RecognizedEncoding, &[u8]) -> Result<Vec<u8>, InputError>,
+) -> ParserResult {
+    let options = ParserOptions {
+        decoder: CustomDecoder {
+            f: Some(Box::new(f)),
+        },
+        ..Default::default()
+    };
+    Parser::new(src, options).do_parse()
+}
+
+const LATIN1_SRC: &[u8] = b"# encoding: iso-8859-1\n\"\xE9t\xE9\" + foo";
+
+#[test]
+fn it_keeps_original_bytes() {
+    let ParserResult { input, .. } = parse(LATIN1_SRC, decode_latin1);
+
+    assert_eq!(input.original_bytes, Some(LATIN1_SRC.to_vec()));
+    assert_eq!(
+        input.bytes,
+        "# encoding: iso-8859-1\n\"été\" + foo".as_bytes().to_vec()
+    );
+}
+
+#[test]
+fn it_maps_offsets() {
+    let ParserResult { input, .. } = parse(LATIN1_SRC, decode_latin1);
+
+    // "foo"
+    assert_eq!(input.original_offset(33), Some(31));
+    assert_eq!(input.decoded_offset(31), Some(33));
+    assert_eq!(
+        input.original_range(&Range::new(33, 36)),
+        Some(Range::new(31, 34))
+    );
+
+    // "é" is 2 bytes in UTF-8 and 1 byte in ISO-8859-1
+    assert_eq!(input.original_offset(24), Some(24));
+    assert_eq!(input.original_offset(25), None);
+    assert_eq!(input.original_offset(26), Some(25));
+    assert_eq!(input.decoded_offset(25), Some(26));
+
+    // ASCII prefix is the same
+    assert_eq!(input.original_offset(10), Some(10));
+
+    // EOF
+    assert_eq!(input.original_offset(36), Some(34));
+    assert_eq!(input.original_offset(37), None);
+    assert_eq!(input.decoded_offset(35), None);
+}
+
+#[test]
+fn it_maps_offsets_of_utf8_source_to_themselves() {
+    let ParserResult { input, .. } = Parser::new(b"foo", ParserOptions::default()).do_parse();
+
+    assert_eq!(input.original_bytes, None);
+    assert_eq!(input.original_offset(2), Some(2));
+    assert_eq!(input.decoded_offset(3), Some(3));
+    assert_eq!(input.original_offset(4), None);
+}
+
+#[test]
+fn it_doesnt_map_offsets_if_decoder_changes_source() {
+    fn decode(_: RecognizedEncoding, _: &[u8]) -> Result<Vec<u8>, InputError> {
+        Ok(b"# encoding: iso-8859-1\nbar".to_vec())
+    }
+    let ParserResult { input, .. } = parse(b"# encoding: iso-8859-1\nfoo", decode);
+
+    assert_eq!(
+        input.original_bytes,
+        Some(b"# encoding: iso-8859-1\nfoo".to_vec())
+    );
+    assert_eq!(input.original_offset(0), None);
+    assert_eq!(input.decoded_offset(24), None);
+}
+
+fn input(src: &[u8]) -> Input {
+    Buffer::new("(input_test)", src.to_vec(), CustomDecoder::default()).input
+}
+
+// "あ" is 3 bytes (1 UTF-16 code unit), "😀" is 4 bytes (2 UTF-16 code units)
+const MULTIBYTE_SRC: &str = "foo\nあ😀b\n";
+
+#[test]
+fn it_converts_pos_to_line_col() {
+    let input = input(MULTIBYTE_SRC.as_bytes());
+
+    assert_eq!(input.line_col_for_pos(0), Some((0, 0)));
+    assert_eq!(input.line_col_for_pos(3), Some((0, 3)));
+    assert_eq!(input.line_col_for_pos(4), Some((1, 0)));
+    assert_eq!(input.line_col_for_pos(11), Some((1, 7)));
+    assert_eq!(input.line_col_for_pos(13), Some((2, 0)));
+    assert_eq!(input.line_col_for_pos(14), None);
+
+    assert_eq!(input.pos_for_line_col(0, 3), Some(3));
+    assert_eq!(input.pos_for_line_col(0, 4), None);
+    assert_eq!(input.pos_for_line_col(1, 7), Some(11));
+    assert_eq!(input.pos_for_line_col(2, 0), Some(13));
+    assert_eq!(input.pos_for_line_col(3, 0), None);
+}
+
+#[test]
+fn it_has_no_lines_in_default_input() {
+    let input = Input::default();
+
+    assert_eq!(input.line_for_pos(0), None);
+    assert_eq!(input.line_col_for_pos(0), None);
+    assert_eq!(input.line_char_col_for_pos(0), None);
+    assert_eq!(input.line_utf16_col_for_pos(0), None);
+}
+
+#[test]
+fn it_converts_pos_to_line_char_col() {
+    let input = input(MULTIBYTE_SRC.as_bytes());
+
+    assert_eq!(input.line_char_col_for_pos(7), Some((1, 1)));
+    assert_eq!(input.line_char_col_for_pos(11), Some((1, 2)));
+    assert_eq!(input.line_char_col_for_pos(5), None);
+
+    assert_eq!(input.pos_for_line_char_col(1, 2), Some(11));
+    assert_eq!(input.pos_for_line_char_col(1, 3), Some(12));
+    assert_eq!(input.pos_for_line_char_col(1, 4), None);
+}
+
+#[test]
+fn it_converts_pos_to_line_utf16_col() {
+    let input = input(MULTIBYTE_SRC.as_bytes());
+
+    assert_eq!(input.line_utf16_col_for_pos(7), Some((1, 1)));
+    assert_eq!(input.line_utf16_col_for_pos(11), Some((1, 3)));
+    assert_eq!(input.line_utf16_col_for_pos(12), Some((1, 4)));
+
+    assert_eq!(input.pos_for_line_utf16_col(1, 1), Some(7));
+    assert_eq!(input.pos_for_line_utf16_col(1, 2), None);
+    assert_eq!(input.pos_for_line_utf16_col(1, 3), Some(11));
+}
+
+#[test]
+fn it_counts_invalid_bytes_as_chars() {
+    let input = input(b"\xFF\xE3a");
+
+    assert_eq!(input.line_char_col_for_pos(2), Some((0, 2)));
+    assert_eq!(input.line_utf16_col_for_pos(3), Some((0, 3)));
+    assert_eq!(input.pos_for_line_char_col(0, 2), Some(2));
+}
@@ -87,6 +87,7 @@ impl LocName {
                 Node::InPattern(inner) => Some(inner.begin_l.clone()),
                 Node::Index(inner) => Some(inner.begin_l.clone()),
                 Node::IndexAsgn(inner) => Some(inner.begin_l.clone()),
+                Node::Itblock(inner) => Some(inner.begin_l.clone()),
                 Node::Numblock(inner) => Some(inner.begin_l.clone()),
                 Node::Postexe(inner) => Some(inner.begin_l.clone()),
                 Node::Preexe(inner) => Some(inner.begin_l.clone()),
@@ -131,6 +132,7 @@ impl LocName {
                 Node::For(inner) => Some(inner.end_l.clone()),
                 Node::Index(inner) => Some(inner.end_l.clone()),
                 Node::IndexAsgn(inner) => Some(inner.end_l.clone()),
+                Node::Itblock(inner) => Some(inner.end_l.clone()),
                 Node::Module(inner) => Some(inner.end_l.clone()),
                 Node::Numblock(inner) => Some(inner.end_l.clone()),
                 Node::Postexe(inner) => Some(inner.end_l.clone()),
@@ -238,6 +240,7 @@ impl LocName {
                 Node::Masgn(inner) => Some(inner.operator_l.clone()),
                 Node::MatchAlt(inner) => Some(inner.operator_l.clone()),
                 Node::MatchAs(inner) => Some(inner.operator_l.clone()),
+                Node::MatchPattern(inner) => Some(inner.operator_l.clone()),
                 Node::MatchNilPattern(inner) => Some(inner.operator_l.clone()),
                 Node::MatchRest(inner) => Some(inner.operator_l.clone()),
                 Node::MatchWithLvasgn(inner) => Some(inner.operator_l.clone()),
@@ -0,0 +1,173 @@
+use lib_ruby_parser::nodes::NodeFactory;
+use lib_ruby_parser::source::buffer::Buffer;
+use lib_ruby_parser::source::rewriter::{RewriterError, TreeRewriter};
+use lib_ruby_parser::source::{CustomDecoder, Range};
+use lib_ruby_parser::unparse::unparse;
+use lib_ruby_parser::{
+    Diagnostic, DiagnosticMessage, DiagnosticRenderer, ErrorLevel, Node, Parser, ParserOptions,
+    ParserResult,
+};
+
+fn parse(src: &str) -> Node {
+    let options = ParserOptions {
+        buffer_name: "(node_factory_test)".to_owned(),
+        ..Default::default()
+    };
+    let ParserResult { ast, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    ast.unwrap()
+}
+
+fn assert_same_as_parsed(node: &Node, src: &str) {
+    assert_eq!(node.inspect(0), parse(src).inspect(0), "for {:?}", src);
+    assert_eq!(parse(&unparse(node)).inspect(0), node.inspect(0));
+}
+
+#[test]
+fn test_synthetic_range() {
+    let range = Range::synthetic();
+    assert!(range.is_synthetic());
+    assert!(!Range::new(0, 0).is_synthetic());
+    assert_eq!(format!("{:?}", range), "synthetic");
+
+    // synthetic ranges don't affect parsed ones
+    assert_eq!(range.join(&Range::new(1, 2)), Range::new(1, 2));
+    assert_eq!(Range::new(1, 2).join(&range), Range::new(1, 2));
+}
+
+#[test]
+fn test_synthetic_range_has_no_source() {
+    let range = Range::synthetic();
+    let input = Buffer::new(
+        "(node_factory_test)",
+        b"foo".to_vec(),
+        CustomDecoder::default(),
+    )
+    .input;
+
+    assert_eq!(range.source(&input), None);
+    assert_eq!(range.begin_line_col(&input), None);
+    assert_eq!(range.end_line_col(&input), None);
+    assert_eq!(range.expand_to_line(&input), None);
+    assert!(range.adjust_begin(1).is_synthetic());
+    assert!(range.adjust_end(-1).is_synthetic());
+    assert!(range.resize(1).is_synthetic());
+
+    let mut rewriter = TreeRewriter::new(&input);
+    match rewriter.replace(&range, "bar") {
+        Err(RewriterError::OutOfBounds(_)) => {}
+        other => panic!("expected out of bounds error, got {:?}", other),
+    }
+
+    let diagnostic = Diagnostic::new(ErrorLevel::Error, DiagnosticMessage::InvalidYield, range);
+    assert_eq!(
+        DiagnosticRenderer::default().render(&diagnostic, &input),
+        None
+    );
+    assert_eq!(diagnostic.render(&input), None);
+}
+
+#[test]
+fn test_nodes_are_synthetic() {
+    let f = NodeFactory::new();
+    let node = f.send(Some(f.lvar("foo")), "bar", vec![f.int(1)]);
+    assert!(node.is_synthetic());
+
+    let f = NodeFactory::at(Range::new(3, 5));
+    let node = f.nil();
+    assert!(!node.is_synthetic());
+    assert_eq!(node.expression(), &Range::new(3, 5));
+
+    assert!(!parse("foo").is_synthetic());
+}
+
+#[test]
+fn test_negative_numbers_have_operator() {
+    let f = NodeFactory::at(Range::new(0, 2));
+    match (f.int(-5), parse("-5")) {
+        (Node::Int(synthetic), Node::Int(parsed)) => {
+            assert!(synthetic.operator_l.is_some());
+            assert!(parsed.operator_l.is_some());
+        }
+        other => panic!("expected ints, got {:?}", other),
+    }
+    match f.int(5) {
+        Node::Int(int) => assert_eq!(int.operator_l, None),
+        other => panic!("expected int, got {:?}", other),
+    }
+    match f.float(-1.5) {
+        Node::Float(float) => assert_eq!(float.operator_l, Some(Range::new(0, 2))),
+        other => panic!("expected float, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_literals() {
+    let f = NodeFactory::new();
+    assert_same_as_parsed(&f.int(42), "42");
+    assert_same_as_parsed(&f.int(-5), "-5");
+    assert_same_as_parsed(&f.float(-1.5), "-1.5");
+    assert_same_as_parsed(&f.float(1.5), "1.5");
+    assert_same_as_parsed(&f.str("a\"b"), "\"a\\\"b\"");
+    assert_same_as_parsed(&f.sym("foo="), ":foo=");
+    assert_same_as_parsed(
+        &f.dstr(vec![f.str("a"), f.ivar("@b"), f.send(None, "c", vec![])]),
+        "\"a#{@b}#{c}\"",
+    );
+    assert_same_as_parsed(&f.regexp("a+", "xi"), "/a+/ix");
+    assert_same_as_parsed(
+        &f.hash(vec![
+            f.pair(f.sym("a"), f.nil()),
+            f.pair(f.int(1), f.true_()),
+        ]),
+        "{ a: nil, 1 => true }",
+    );
+    assert_same_as_parsed(&f.array(vec![f.irange(Some(f.int(1)), None)]), "[1..]");
+}
+
+#[test]
+fn test_calls() {
+    let f = NodeFactory::new();
+    assert_same_as_parsed(&f.send(Some(f.int(1)), "+", vec![f.int(2)]), "1 + 2");
+    assert_same_as_parsed(
+        &f.block(
+            f.csend(f.self_(), "each", vec![]),
+            vec![f.arg("x")],
+            vec![f.yield_(vec![f.lvar("x")])],
+        ),
+        "self&.each { |x| yield(x) }",
+    );
+    assert_same_as_parsed(
+        &f.lambda(vec![f.arg("a"), f.arg("b")], vec![f.lvar("a")]),
+        "->(a, b) { a }",
+    );
+}
+
+#[test]
+fn test_definitions() {
+    let f = NodeFactory::new();
+    let def = f.def(
+        "foo",
+        vec![
+            f.arg("a"),
+            f.optarg("b", f.int(1)),
+            f.restarg(None),
+            f.kwarg("c"),
+            f.blockarg("d"),
+        ],
+        vec![
+            f.lvasgn("x", Some(f.send(None, "bar", vec![]))),
+            f.if_(f.lvar("x"), vec![f.return_(vec![f.lvar("a")])], vec![]),
+        ],
+    );
+    assert_same_as_parsed(
+        &def,
+        "def foo(a, b = 1, *, c:, &d); x = bar; if x; return a; end; end",
+    );
+
+    let class = f.class(
+        f.const_(Some(f.cbase()), "Foo"),
+        Some(f.const_(None, "Bar")),
+        vec![f.defs(f.self_(), "baz", vec![], vec![f.zsuper()])],
+    );
+    assert_same_as_parsed(&class, "class ::Foo < Bar; def self.baz; super; end; end");
+}
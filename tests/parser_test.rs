@@ -145,13 +145,14 @@ enum TestResult {
     Failure(String),
 }
 
-fn test_file(fixture_path: &str) -> TestResult {
+fn test_file(fixture_path: &str, error_recovery: bool) -> TestResult {
     let result = panic::catch_unwind(|| {
         let test_case = Fixture::new(fixture_path);
 
         let options = ParserOptions {
             buffer_name: format!("(test {})", fixture_path),
             debug: false,
+            error_recovery,
             ..Default::default()
         };
         let parser = Parser::new(test_case.input.as_bytes(), options);
@@ -172,7 +173,7 @@ fn test_file(fixture_path: &str) -> TestResult {
     }
 }
 
-fn test_dir(dir: &str) {
+fn test_dir(dir: &str, error_recovery: bool) {
     eprintln!("Running parser tests {}\n", dir);
 
     let mut passed: usize = 0;
@@ -181,7 +182,7 @@ fn test_dir(dir: &str) {
 
     for filename in files_under_dir(dir) {
         eprint!("test {} ... ", filename);
-        match test_file(&filename) {
+        match test_file(&filename, error_recovery) {
             TestResult::Segfault => {
                 eprintln!("SEG");
                 segfaults += 1;
@@ -213,10 +214,15 @@ fn test_dir(dir: &str) {
 
 #[test]
 fn test_gen() {
-    test_dir("tests/fixtures/parser/gen")
+    test_dir("tests/fixtures/parser/gen", false)
 }
 
 #[test]
 fn test_manual() {
-    test_dir("tests/fixtures/parser/manual")
+    test_dir("tests/fixtures/parser/manual", false)
+}
+
+#[test]
+fn test_error_recovery() {
+    test_dir("tests/fixtures/parser/error_recovery", true)
 }
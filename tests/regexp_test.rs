@@ -0,0 +1,136 @@
+// Without "onig" feature static regexps are validated by a built-in parser
+#![cfg(not(feature = "onig"))]
+
+use lib_ruby_parser::{Diagnostic, DiagnosticMessage, Parser, ParserOptions, ParserResult};
+
+// Parses a regexp with the given source, the source is interpolated
+// as a single-quoted string to pass it to the regexp parser without lexer escaping
+fn parse(source: &str, options: &str) -> Vec<Diagnostic> {
+    let escaped = source.replace('\\', "\\\\").replace('\'', "\\'");
+    let src = format!("/#{{'{}'}}/{}", escaped, options);
+    let options = ParserOptions {
+        buffer_name: "(regexp_test)".to_owned(),
+        ..Default::default()
+    };
+    let ParserResult { diagnostics, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    diagnostics
+}
+
+const ACCEPTED: &[(&str, &str)] = &[
+    // named groups and references
+    ("(?<foo>a)\\k<foo>", ""),
+    ("(?'foo'a)\\g'foo'", ""),
+    ("(?<foo>a)|(?<foo>b)", ""),
+    ("(?<foo>a)\\k<foo+0>", ""),
+    ("(?<ц>a)\\k<ц>", ""),
+    ("(a)\\1\\k<1>\\g<1>\\g<0>", ""),
+    // octal char code, there's no 10th group
+    ("(a)\\101", ""),
+    // lookaround, atomic and absent groups
+    ("(?<=a)b", ""),
+    ("(?<!a)b", ""),
+    ("a(?=b)", ""),
+    ("a(?!b)", ""),
+    ("(?>a)(?~a)", ""),
+    // comments and options
+    ("a(?#comment)b", ""),
+    ("(?i)a(?mi-x:b)", ""),
+    ("a # (\n b", "x"),
+    ("(?x: a # (\n )", ""),
+    // conditions
+    ("(a)?(?(1)b|c)", ""),
+    ("(?<n>a)?(?(<n>)b)", ""),
+    // character properties
+    ("\\p{Alpha}\\p{^Alpha}\\P{Greek}", ""),
+    ("[\\p{Digit}a-z]", ""),
+    // POSIX brackets
+    ("[[:alpha:]]", ""),
+    ("[[:^space:]]", ""),
+    ("[[:alpha:][:digit:]_]", ""),
+    // nested class that starts with `:`
+    ("[[:a]]", ""),
+    // quantifiers
+    ("a{2}b{2,}c{,3}d{2,3}", ""),
+    ("a{2,3}?b{2}+c*?d++e??", ""),
+    // literal `{`
+    ("a{", ""),
+    ("a{,}", ""),
+    ("a{x}", ""),
+    // escapes and ranges inside of classes
+    ("[\\]\\-\\\\]", ""),
+    ("[\\d\\s\\h]", ""),
+    ("[\\x41-\\x5A]", ""),
+    ("[\\u0041-\\u{5A}]", ""),
+    ("[\\t\\n\\e]", ""),
+    ("[a-z&&[^aeiou]]", ""),
+    ("[\\\\\\[]", ""),
+    ("[]a]", ""),
+    ("[a-]", ""),
+    // control and meta chars
+    ("\\cA\\C-a\\M-a\\M-\\C-a", ""),
+];
+
+#[test]
+fn it_accepts_valid_syntax() {
+    for (source, options) in ACCEPTED {
+        let diagnostics = parse(source, options);
+        assert!(diagnostics.is_empty(), "{:?}: {:?}", source, diagnostics);
+    }
+}
+
+// source, error message
+const REJECTED: &[(&str, &str)] = &[
+    ("[a-\\d]", "char-class value at end of range"),
+    ("[]", "empty char-class"),
+    ("[z-a]", "empty range in char class"),
+    ("\\c", "end pattern at control"),
+    ("\\", "end pattern at escape"),
+    ("\\M-", "end pattern at meta"),
+    ("(?", "end pattern in group"),
+    ("(?#comment", "end pattern in group"),
+    ("(a", "end pattern with unmatched parenthesis"),
+    ("(?<>a)", "group name is empty"),
+    ("\\k<>", "group name is empty"),
+    ("[[:foo:]]", "invalid POSIX bracket type"),
+    ("\\k", "invalid backref number/name"),
+    ("(a)\\k<2>", "invalid backref number/name"),
+    ("\\k<-1>", "invalid backref number/name"),
+    ("(?(0)a)", "invalid backref number/name"),
+    ("(?<a-b>c)", "invalid char in group name <a-b>"),
+    ("\\g<a!>", "invalid char in group name <a!>"),
+    ("\\p{@}", "invalid character property name {@}"),
+    ("\\pL", "invalid character property name {p}"),
+    ("(?()a)", "invalid conditional pattern"),
+    ("(?(a!)b)", "invalid conditional pattern"),
+    ("\\Ca", "invalid control-code syntax"),
+    ("\\gx", "invalid group name <>"),
+    ("(?<1a>b)", "invalid group name <1a>"),
+    ("(?<a", "invalid group name <a>"),
+    ("\\Ma", "invalid meta-code syntax"),
+    ("[a", "premature end of char-class"),
+    ("[a-", "premature end of char-class"),
+    ("^*", "target of repeat operator is invalid"),
+    ("(?=a)+", "target of repeat operator is invalid"),
+    ("*a", "target of repeat operator is not specified"),
+    ("a|{1}", "target of repeat operator is not specified"),
+    ("a{100001}", "too big number for repeat range"),
+    ("\\g<1>", "undefined group <1> reference"),
+    ("(?z)", "undefined group option"),
+    ("(?i+)", "undefined group option"),
+    ("\\k<foo>", "undefined name <foo> reference"),
+    ("(?(<foo>)a)", "undefined name <foo> reference"),
+    ("a)", "unmatched close parenthesis"),
+    ("a{3,2}", "upper is smaller than lower in repeat range"),
+];
+
+#[test]
+fn it_rejects_invalid_syntax() {
+    for (source, message) in REJECTED {
+        let diagnostics = parse(source, "");
+        assert_eq!(diagnostics.len(), 1, "{:?}: {:?}", source, diagnostics);
+        match &diagnostics[0].message {
+            DiagnosticMessage::RegexError(actual) => assert_eq!(actual, message, "{:?}", source),
+            other => panic!("{:?}: expected regex error, got {:?}", source, other),
+        }
+    }
+}
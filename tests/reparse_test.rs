@@ -0,0 +1,179 @@
+use lib_ruby_parser::source::{Range, TextEdit};
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult, RubyVersion};
+
+fn options() -> ParserOptions {
+    ParserOptions {
+        buffer_name: "(reparse_test)".to_owned(),
+        ..Default::default()
+    }
+}
+
+fn parse(src: &[u8]) -> ParserResult {
+    Parser::new(src, options()).do_parse()
+}
+
+fn assert_same_as_full_parse(src: &str, edit: TextEdit) {
+    assert_same_as_full_parse_with(src, edit, options)
+}
+
+fn assert_same_as_full_parse_with(src: &str, edit: TextEdit, options: fn() -> ParserOptions) {
+    let previous = Parser::new(src.as_bytes(), options()).do_parse();
+    let expected = Parser::new(&edit.apply(src.as_bytes()), options()).do_parse();
+    let actual = Parser::reparse(&previous, edit, options());
+
+    assert_eq!(actual.input.bytes, expected.input.bytes);
+    assert_eq!(actual.ast, expected.ast);
+    assert_eq!(
+        format!("{:?}", actual.tokens),
+        format!("{:?}", expected.tokens)
+    );
+    assert_eq!(
+        format!("{:?}", actual.diagnostics),
+        format!("{:?}", expected.diagnostics)
+    );
+    assert_eq!(
+        format!("{:?}", actual.comments),
+        format!("{:?}", expected.comments)
+    );
+    assert_eq!(
+        format!("{:?}", actual.magic_comments),
+        format!("{:?}", expected.magic_comments)
+    );
+}
+
+const SRC: &str = "# frozen_string_literal: true
+foo = 1
+bar = 2
+
+def m(a)
+  a + 1 # comment
+end
+
+baz(foo, bar)
+";
+
+#[test]
+fn test_replace_in_the_middle() {
+    // "a + 1" -> "a + 42"
+    assert_same_as_full_parse(SRC, TextEdit::new(Range::new(62, 63), b"42"));
+}
+
+#[test]
+fn test_replace_first_statement() {
+    assert_same_as_full_parse(SRC, TextEdit::new(Range::new(36, 37), b"100"));
+}
+
+#[test]
+fn test_insert_statement() {
+    assert_same_as_full_parse(SRC, TextEdit::insert(47, b"qux = [1, 2, 3]\n"));
+}
+
+#[test]
+fn test_remove_statement() {
+    assert_same_as_full_parse(SRC, TextEdit::remove(Range::new(38, 47)));
+}
+
+#[test]
+fn test_edit_last_statement() {
+    assert_same_as_full_parse(SRC, TextEdit::insert(93, b"baz(1)\n"));
+}
+
+#[test]
+fn test_edit_that_declares_a_local_variable() {
+    // "baz(foo, bar)" depends on whether "bar" is a local variable
+    assert_same_as_full_parse(SRC, TextEdit::new(Range::new(38, 41), b"xyz"));
+}
+
+#[test]
+fn test_edit_that_joins_statements() {
+    assert_same_as_full_parse("foo\nbar\nbaz\n", TextEdit::new(Range::new(3, 4), b" + "));
+}
+
+#[test]
+fn test_edit_that_breaks_code() {
+    assert_same_as_full_parse(SRC, TextEdit::remove(Range::new(74, 77)));
+}
+
+#[test]
+fn test_edit_that_fixes_code() {
+    let src = "foo = 1\nbar(\nbaz = 3\n";
+    assert_same_as_full_parse(src, TextEdit::insert(12, b")"));
+}
+
+#[test]
+fn test_heredocs() {
+    let src = "foo(<<~A); bar\n  a\nA\nbaz\nqux\n";
+    assert_same_as_full_parse(src, TextEdit::new(Range::new(17, 18), b"xyz"));
+    assert_same_as_full_parse(src, TextEdit::new(Range::new(21, 24), b"quux"));
+}
+
+#[test]
+fn test_statements_on_one_line() {
+    let src = "a = 1; b = 2; c = a + b";
+    assert_same_as_full_parse(src, TextEdit::new(Range::new(11, 12), b"3"));
+}
+
+#[test]
+fn test_reuses_statements_after_the_edit() {
+    let src = "foo = 1\nbar = 2\nbaz = 3\nqux = 4\n";
+    let mut previous = parse(src.as_bytes());
+
+    // statements that are not re-parsed are taken from `previous` as is,
+    // so a modified node must survive reparsing
+    match &mut previous.ast {
+        Some(Node::Begin(begin)) => match &mut begin.statements[3] {
+            Node::Lvasgn(lvasgn) => lvasgn.name = "reused".to_owned(),
+            other => panic!("expected lvasgn, got {:?}", other),
+        },
+        other => panic!("expected begin, got {:?}", other),
+    }
+
+    let result = Parser::reparse(&previous, TextEdit::new(Range::new(6, 7), b"42"), options());
+    match result.ast {
+        Some(Node::Begin(begin)) => match &begin.statements[3] {
+            Node::Lvasgn(lvasgn) => assert_eq!(lvasgn.name, "reused"),
+            other => panic!("expected lvasgn, got {:?}", other),
+        },
+        other => panic!("expected begin, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_keeps_options() {
+    fn with_locals() -> ParserOptions {
+        ParserOptions {
+            locals: vec![vec!["baz".to_owned()]],
+            ..options()
+        }
+    }
+    // "baz" is a local variable only if `locals` are passed to the snippet parser
+    assert_same_as_full_parse_with(
+        "foo = 1\nbar\nbaz\n",
+        TextEdit::insert(11, b"1"),
+        with_locals,
+    );
+
+    fn with_ruby_version() -> ParserOptions {
+        ParserOptions {
+            ruby_version: RubyVersion::new(2, 7),
+            ..options()
+        }
+    }
+    assert_same_as_full_parse_with(
+        "foo = 1\nbar = 2\nbaz = 3\n",
+        TextEdit::new(Range::new(14, 15), b"bar(baz:)"),
+        with_ruby_version,
+    );
+
+    fn with_error_recovery() -> ParserOptions {
+        ParserOptions {
+            error_recovery: true,
+            ..options()
+        }
+    }
+    assert_same_as_full_parse_with(
+        "foo = 1\nbar = 2\nbaz = 3\n",
+        TextEdit::new(Range::new(14, 15), b"(1, ;"),
+        with_error_recovery,
+    );
+}
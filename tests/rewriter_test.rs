@@ -0,0 +1,197 @@
+use lib_ruby_parser::source::buffer::*;
+use lib_ruby_parser::source::rewriter::*;
+use lib_ruby_parser::source::{CustomDecoder, Range};
+
+const SOURCE: &str = "puts(:hello, :world)";
+
+fn input() -> Input {
+    Buffer::new(
+        "(rewriter_test)",
+        SOURCE.as_bytes().to_vec(),
+        CustomDecoder::default(),
+    )
+    .input
+}
+
+fn hello() -> Range {
+    Range::new(5, 11)
+}
+
+fn comma_space() -> Range {
+    Range::new(11, 13)
+}
+
+fn world() -> Range {
+    Range::new(13, 19)
+}
+
+fn args() -> Range {
+    Range::new(5, 19)
+}
+
+fn processed(rewriter: &TreeRewriter) -> String {
+    String::from_utf8(rewriter.process()).unwrap()
+}
+
+fn clobbering_kind(err: RewriterError) -> ConflictKind {
+    match err {
+        RewriterError::Clobbering(conflict) => conflict.kind,
+        other => panic!("expected clobbering error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_no_actions() {
+    let input = input();
+    let rewriter = TreeRewriter::new(&input);
+    assert!(rewriter.is_empty());
+    assert_eq!(processed(&rewriter), SOURCE);
+}
+
+#[test]
+fn test_nested_actions_in_any_order() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.wrap(&hello(), "[", "]").unwrap();
+    rewriter.replace(&comma_space(), " => ").unwrap();
+    rewriter.wrap(&args(), "{", "}").unwrap();
+    rewriter.replace(&world(), ":everybody").unwrap();
+    rewriter.wrap(&world(), "[", "]").unwrap();
+
+    assert_eq!(processed(&rewriter), "puts({[:hello] => [:everybody]})");
+}
+
+#[test]
+fn test_insertions_on_the_same_range() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.insert_before(&hello(), "1").unwrap();
+    rewriter.insert_before(&hello(), "2").unwrap();
+    rewriter.insert_after(&hello(), "3").unwrap();
+    rewriter.insert_after(&hello(), "4").unwrap();
+
+    assert_eq!(processed(&rewriter), "puts(21:hello34, :world)");
+}
+
+#[test]
+fn test_insertions_at_empty_ranges() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.insert_after(&Range::new(20, 20), ";").unwrap();
+    rewriter.insert_before(&Range::new(0, 0), "self.").unwrap();
+    rewriter.remove(&Range::new(4, 5)).unwrap();
+    rewriter.replace(&Range::new(19, 20), "").unwrap();
+    rewriter.insert_before(&Range::new(4, 4), " ").unwrap();
+
+    assert_eq!(processed(&rewriter), "self.puts :hello, :world;");
+}
+
+#[test]
+fn test_different_replacements() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.replace(&hello(), ":hi").unwrap();
+    rewriter.replace(&hello(), ":hi").unwrap();
+
+    let err = rewriter.replace(&hello(), ":bye").unwrap_err();
+    assert_eq!(clobbering_kind(err), ConflictKind::DifferentReplacements);
+    assert_eq!(processed(&rewriter), "puts(:hi, :world)");
+
+    let policy = RewriterPolicy {
+        different_replacements: Policy::Accept,
+        ..Default::default()
+    };
+    let mut rewriter = TreeRewriter::with_policy(&input, policy);
+    rewriter.replace(&hello(), ":hi").unwrap();
+    rewriter.replace(&hello(), ":bye").unwrap();
+    assert_eq!(processed(&rewriter), "puts(:bye, :world)");
+}
+
+#[test]
+fn test_swallowed_insertions() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.wrap(&hello(), "[", "]").unwrap();
+
+    let err = rewriter.replace(&args(), "*args").unwrap_err();
+    assert_eq!(clobbering_kind(err), ConflictKind::SwallowedInsertions);
+    assert_eq!(processed(&rewriter), "puts([:hello], :world)");
+
+    let policy = RewriterPolicy {
+        swallowed_insertions: Policy::Accept,
+        ..Default::default()
+    };
+    let mut rewriter = TreeRewriter::with_policy(&input, policy);
+    rewriter.wrap(&hello(), "[", "]").unwrap();
+    rewriter.replace(&args(), "*args").unwrap();
+    rewriter.wrap(&world(), "[", "]").unwrap();
+    assert_eq!(processed(&rewriter), "puts(*args)");
+}
+
+#[test]
+fn test_crossing_deletions() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    rewriter.remove(&Range::new(5, 13)).unwrap();
+    rewriter.remove(&Range::new(11, 19)).unwrap();
+    assert_eq!(processed(&rewriter), "puts()");
+
+    let policy = RewriterPolicy {
+        crossing_deletions: Policy::Raise,
+        ..Default::default()
+    };
+    let mut rewriter = TreeRewriter::with_policy(&input, policy);
+    rewriter.remove(&Range::new(5, 13)).unwrap();
+    let err = rewriter.remove(&Range::new(11, 19)).unwrap_err();
+    assert_eq!(clobbering_kind(err), ConflictKind::CrossingDeletions);
+    assert_eq!(processed(&rewriter), "puts(:world)");
+}
+
+#[test]
+fn test_crossing_insertions() {
+    let input = input();
+    let policy = RewriterPolicy {
+        crossing_deletions: Policy::Accept,
+        different_replacements: Policy::Accept,
+        swallowed_insertions: Policy::Accept,
+    };
+    let mut rewriter = TreeRewriter::with_policy(&input, policy);
+    rewriter.wrap(&Range::new(5, 13), "(", ")").unwrap();
+
+    let err = rewriter.wrap(&Range::new(11, 19), "[", "]").unwrap_err();
+    assert_eq!(clobbering_kind(err), ConflictKind::CrossingInsertions);
+    assert_eq!(processed(&rewriter), "puts((:hello, ):world)");
+}
+
+#[test]
+fn test_warn_policy() {
+    let input = input();
+    let policy = RewriterPolicy {
+        different_replacements: Policy::Warn,
+        ..Default::default()
+    };
+    let mut rewriter = TreeRewriter::with_policy(&input, policy);
+    rewriter.replace(&world(), ":all").unwrap();
+    rewriter.replace(&world(), ":everyone").unwrap();
+
+    assert_eq!(
+        rewriter.warnings(),
+        &[Conflict {
+            kind: ConflictKind::DifferentReplacements,
+            range: world(),
+            conflicts: vec![world()]
+        }]
+    );
+    assert_eq!(processed(&rewriter), "puts(:hello, :everyone)");
+}
+
+#[test]
+fn test_out_of_bounds() {
+    let input = input();
+    let mut rewriter = TreeRewriter::new(&input);
+    assert_eq!(
+        rewriter.remove(&Range::new(15, 25)),
+        Err(RewriterError::OutOfBounds(Range::new(15, 25)))
+    );
+    assert!(rewriter.is_empty());
+}
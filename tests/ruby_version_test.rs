@@ -0,0 +1,121 @@
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult, RubyVersion};
+
+fn parse(src: &str, ruby_version: RubyVersion) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(ruby_version_test)".to_owned(),
+        ruby_version,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+// source, rejected syntax, the version that introduced it and the previous version
+const CASES: &[(&str, &str, (u8, u8), (u8, u8))] = &[
+    ("def foo = 42", "endless method definition", (3, 0), (2, 7)),
+    (
+        "def self.foo = 42",
+        "endless method definition",
+        (3, 0),
+        (2, 7),
+    ),
+    ("foo => bar", "rightward pattern matching", (3, 0), (2, 7)),
+    ("foo in [*, 1, *]", "find pattern", (3, 0), (2, 7)),
+    ("foo => ^(1 + 2)", "pinning of expressions", (3, 1), (3, 0)),
+    (
+        "foo in ^@bar",
+        "pinning of instance, class and global variables",
+        (3, 1),
+        (3, 0),
+    ),
+    ("foo(bar:)", "hash shorthand", (3, 1), (3, 0)),
+    (
+        "def foo(&) = bar(&)",
+        "anonymous block forwarding",
+        (3, 1),
+        (3, 0),
+    ),
+    (
+        "def foo(&); end",
+        "anonymous block forwarding",
+        (3, 1),
+        (3, 0),
+    ),
+    (
+        "def foo(*) = bar(*)",
+        "anonymous rest forwarding",
+        (3, 2),
+        (3, 1),
+    ),
+    (
+        "def foo(**) = bar(**)",
+        "anonymous keyword rest forwarding",
+        (3, 2),
+        (3, 1),
+    ),
+];
+
+#[test]
+fn it_rejects_syntax_from_newer_versions() {
+    for (src, syntax, _, (major, minor)) in CASES {
+        let ruby_version = RubyVersion::new(*major, *minor);
+        let ParserResult {
+            ast, diagnostics, ..
+        } = parse(src, ruby_version);
+
+        assert!(
+            ast.is_none(),
+            "{:?} must not be parsed in {}",
+            src,
+            ruby_version
+        );
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].name(), "syntax_not_available");
+        assert_eq!(
+            diagnostics[0].render_message(),
+            format!("{} is not available in Ruby {}", syntax, ruby_version)
+        );
+    }
+}
+
+#[test]
+fn it_accepts_syntax_since_its_version() {
+    for (src, _, (major, minor), _) in CASES {
+        let ParserResult {
+            ast, diagnostics, ..
+        } = parse(src, RubyVersion::new(*major, *minor));
+
+        assert!(ast.is_some(), "{:?}: {:?}", src, diagnostics);
+        assert!(diagnostics.is_empty(), "{:?}: {:?}", src, diagnostics);
+    }
+}
+
+#[test]
+fn it_treats_numbered_params_as_identifiers_before_2_7() {
+    let result = parse("foo { _1 }", RubyVersion::new(2, 6));
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+    match result.ast.unwrap() {
+        Node::Block(block) => assert!(matches!(block.body, Some(Node::Send(_)))),
+        other => panic!("expected block, got {:?}", other),
+    }
+
+    let result = parse("foo { _1 = 1; _1 }", RubyVersion::new(2, 6));
+    assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+
+    let ast = parse("foo { _1 }", RubyVersion::new(2, 7)).ast.unwrap();
+    assert!(matches!(ast, Node::Numblock(_)), "{:?}", ast);
+}
+
+#[test]
+fn it_treats_it_as_a_method_call_before_3_4() {
+    let ast = parse("foo { it }", RubyVersion::new(3, 3)).ast.unwrap();
+    assert!(matches!(ast, Node::Block(_)), "{:?}", ast);
+
+    let ast = parse("foo { it }", RubyVersion::new(3, 4)).ast.unwrap();
+    assert!(matches!(ast, Node::Itblock(_)), "{:?}", ast);
+}
+
+#[test]
+fn it_defaults_to_the_latest_version() {
+    assert_eq!(ParserOptions::default().ruby_version, RubyVersion::LATEST);
+    assert_eq!(RubyVersion::LATEST.to_string(), "3.4");
+}
@@ -0,0 +1,170 @@
+use lib_ruby_parser::analysis::{ScopeGraph, ScopeKind, VariableKind};
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+
+fn build(src: &str) -> ScopeGraph {
+    let options = ParserOptions {
+        buffer_name: "(scopes_test)".to_owned(),
+        ..Default::default()
+    };
+    let ParserResult { ast, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    ScopeGraph::build(&ast.unwrap())
+}
+
+// Range of the n-th occurrence of `name` in `src`
+fn nth(src: &str, name: &str, n: usize) -> Range {
+    let begin_pos = src.match_indices(name).nth(n).unwrap().0;
+    Range::new(begin_pos, begin_pos + name.len())
+}
+
+#[test]
+fn test_reference_to_local() {
+    let src = "a = 1; a = 2; a";
+    let graph = build(src);
+
+    assert_eq!(
+        graph.definitions_of(&nth(src, "a", 2)),
+        Some(&[nth(src, "a", 0), nth(src, "a", 1)][..])
+    );
+
+    let id = graph.variable_at(&nth(src, "a", 2)).unwrap();
+    let variable = graph.variable(id);
+    assert_eq!(variable.name, "a");
+    assert_eq!(variable.kind, VariableKind::Local);
+    assert_eq!(variable.scope, graph.root());
+    assert_eq!(variable.references, vec![nth(src, "a", 2)]);
+}
+
+#[test]
+fn test_def_starts_a_new_scope() {
+    let src = "a = 1; def m(a, b = a); a; end";
+    let graph = build(src);
+
+    let outer = graph.variable_at(&nth(src, "a", 0)).unwrap();
+    let arg = graph.variable_at(&nth(src, "a", 1)).unwrap();
+    assert_ne!(outer, arg);
+    assert_eq!(graph.variable(arg).kind, VariableKind::Argument);
+    assert_eq!(graph.variable_at(&nth(src, "a", 2)), Some(arg));
+    assert_eq!(graph.variable_at(&nth(src, "a", 3)), Some(arg));
+    assert!(graph.variable(outer).references.is_empty());
+
+    let def_scope = graph.variable(arg).scope;
+    assert_eq!(graph.scope(def_scope).kind, ScopeKind::Def);
+    assert_eq!(graph.scope(def_scope).parent, Some(graph.root()));
+    assert_eq!(graph.scope(graph.root()).children, vec![def_scope]);
+}
+
+#[test]
+fn test_block_sees_outer_locals() {
+    let src = "a = 1; foo { |b; c| a = b; c = a }";
+    let graph = build(src);
+
+    let a = graph.variable_at(&nth(src, "a", 0)).unwrap();
+    assert_eq!(graph.variable_at(&nth(src, "a", 1)), Some(a));
+    assert_eq!(graph.variable_at(&nth(src, "a", 2)), Some(a));
+    assert_eq!(graph.variable(a).definitions.len(), 2);
+
+    let b = graph.variable_at(&nth(src, "b", 0)).unwrap();
+    let block_scope = graph.variable(b).scope;
+    assert_eq!(graph.scope(block_scope).kind, ScopeKind::Block);
+    assert_eq!(graph.scope_at(nth(src, "c", 1).begin_pos), block_scope);
+
+    let c = graph.variable_at(&nth(src, "c", 1)).unwrap();
+    assert_eq!(graph.variable(c).scope, block_scope);
+    assert_eq!(graph.variable(c).kind, VariableKind::Local);
+}
+
+#[test]
+fn test_block_local_is_not_visible_outside() {
+    let src = "foo { a = 1 }; a = 2";
+    let graph = build(src);
+
+    assert_ne!(
+        graph.variable_at(&nth(src, "a", 0)),
+        graph.variable_at(&nth(src, "a", 1))
+    );
+}
+
+#[test]
+fn test_lambda() {
+    let src = "->(x) { x }";
+    let graph = build(src);
+
+    let x = graph.variable_at(&nth(src, "x", 1)).unwrap();
+    assert_eq!(graph.variable(x).definitions, vec![nth(src, "x", 0)]);
+    assert_eq!(graph.scope(graph.variable(x).scope).kind, ScopeKind::Lambda);
+}
+
+#[test]
+fn test_numbered_params() {
+    let src = "foo { _1 + _2 }";
+    let graph = build(src);
+
+    let id = graph.variable_at(&nth(src, "_2", 0)).unwrap();
+    let variable = graph.variable(id);
+    assert_eq!(variable.kind, VariableKind::NumberedParam);
+    assert!(variable.definitions.is_empty());
+    assert_eq!(graph.scope(variable.scope).kind, ScopeKind::Numblock);
+    assert_eq!(graph.scope(variable.scope).variables.len(), 2);
+}
+
+#[test]
+fn test_it_param() {
+    let src = "foo { it + it }";
+    let graph = build(src);
+
+    let id = graph.variable_at(&nth(src, "it", 1)).unwrap();
+    let variable = graph.variable(id);
+    assert_eq!(variable.kind, VariableKind::ItParam);
+    assert!(variable.definitions.is_empty());
+    assert_eq!(graph.scope(variable.scope).kind, ScopeKind::Itblock);
+}
+
+#[test]
+fn test_pattern_variables() {
+    let src = "case foo; in [x, *y] then x + y; end";
+    let graph = build(src);
+
+    let x = graph.variable_at(&nth(src, "x", 1)).unwrap();
+    assert_eq!(graph.variable(x).kind, VariableKind::PatternVariable);
+    assert_eq!(graph.variable(x).definitions, vec![nth(src, "x", 0)]);
+    assert!(graph.variable_at(&nth(src, "y", 1)).is_some());
+}
+
+#[test]
+fn test_class_body_does_not_see_outer_locals() {
+    let src = "v = 1; class Foo; v; end";
+    let graph = build(src);
+
+    // `v` in the class body is a method call
+    assert_eq!(graph.variable_at(&nth(src, "v", 1)), None);
+    assert!(graph.unresolved().is_empty());
+    assert_eq!(graph.scopes().count(), 2);
+}
+
+#[test]
+fn test_named_captures() {
+    let src = "/(?<foo>.)(?<Bar>.)/ =~ s; foo; Bar";
+    let graph = build(src);
+
+    assert_eq!(
+        graph.definitions_of(&nth(src, "foo", 1)),
+        Some(&[nth(src, "foo", 0)][..])
+    );
+    // not a local variable name
+    assert!(graph
+        .variables()
+        .all(|(_, variable)| variable.name != "Bar"));
+}
+
+#[test]
+fn test_named_captures_are_parsed_as_regexp() {
+    // escaped group, group in a character class, lookbehind and a comment
+    let src = "/\\(?<foo>.\\)[(?<bar>)](?<=baz) # (?<qux>.)\n/x =~ s; [foo, bar, baz, qux]";
+    let graph = build(src);
+
+    assert_eq!(graph.variables().count(), 0);
+    for name in ["foo", "bar", "baz", "qux"].iter() {
+        assert!(graph.unresolved().contains(&nth(src, name, 1)), "{}", name);
+    }
+}
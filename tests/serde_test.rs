@@ -0,0 +1,105 @@
+#![cfg(feature = "serde")]
+
+use lib_ruby_parser::source::Range;
+use lib_ruby_parser::{
+    Diagnostic, DiagnosticMessage, ErrorLevel, Node, Parser, ParserOptions, ParserResult,
+};
+use serde_json::json;
+
+fn parse(src: &str) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(serde_test)".to_owned(),
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+#[test]
+fn test_node_shape() {
+    let ast = parse("foo(1)").ast.unwrap();
+
+    assert_eq!(
+        serde_json::to_value(&ast).unwrap(),
+        json!({
+            "type": "Send",
+            "recv": null,
+            "method_name": "foo",
+            "args": [
+                {
+                    "type": "Int",
+                    "value": "1",
+                    "operator_l": null,
+                    "expression_l": { "begin_pos": 4, "end_pos": 5 }
+                }
+            ],
+            "dot_l": null,
+            "selector_l": { "begin_pos": 0, "end_pos": 3 },
+            "begin_l": { "begin_pos": 3, "end_pos": 4 },
+            "end_l": { "begin_pos": 5, "end_pos": 6 },
+            "operator_l": null,
+            "expression_l": { "begin_pos": 0, "end_pos": 6 }
+        })
+    );
+}
+
+#[test]
+fn test_token_shape() {
+    let tokens = parse("foo").tokens;
+
+    assert_eq!(
+        serde_json::to_value(&tokens[0]).unwrap(),
+        json!({
+            "token_type": tokens[0].token_type,
+            "token_name": "tIDENTIFIER",
+            "token_value": { "String": "foo" },
+            "loc": { "begin": 0, "end": 3 }
+        })
+    );
+}
+
+#[test]
+fn test_diagnostic_shape() {
+    let diagnostic = Diagnostic::new(
+        ErrorLevel::Error,
+        DiagnosticMessage::UnterminatedHeredoc("HERE".to_owned()),
+        Range::new(4, 10),
+    );
+
+    assert_eq!(
+        serde_json::to_value(&diagnostic).unwrap(),
+        json!({
+            "level": "Error",
+            "message": { "UnterminatedHeredoc": "HERE" },
+            "range": { "begin_pos": 4, "end_pos": 10 }
+        })
+    );
+}
+
+#[test]
+fn test_node_roundtrip() {
+    let src = "
+        class Foo < Bar
+          def foo(a, *b, c: 1, **d, &e)
+            a.b&.c += [1, 2.0, 3r, 4i, :sym, \"str#{a}\", /re/mix]
+          end
+        end
+
+        case foo
+        in [1, *rest] | { key: } then bar
+        end
+    ";
+    let ast = parse(src).ast.unwrap();
+
+    let json = serde_json::to_string(&ast).unwrap();
+    let deserialized: Node = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, ast);
+}
+
+#[test]
+fn test_parser_result_roundtrip() {
+    let result = parse("# frozen_string_literal: true\nfoo(<<~HERE) # comment\n  x\nHERE\n");
+
+    let json = serde_json::to_string(&result).unwrap();
+    let deserialized: ParserResult = serde_json::from_str(&json).unwrap();
+    assert_eq!(format!("{:?}", deserialized), format!("{:?}", result));
+}
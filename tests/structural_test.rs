@@ -0,0 +1,71 @@
+use lib_ruby_parser::nodes::NodeFactory;
+use lib_ruby_parser::traverse::Find;
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> Node {
+    let options = ParserOptions {
+        buffer_name: "(structural_test)".to_owned(),
+        ..Default::default()
+    };
+    let ParserResult { ast, .. } = Parser::new(src.as_bytes(), options).do_parse();
+    ast.unwrap()
+}
+
+#[test]
+fn test_structurally_eq_ignores_locations() {
+    let lhs = parse("foo(1, 2) { |a| a + 1 }");
+    let rhs = parse("foo 1,\n  2 do |a|\n  a+1\nend");
+
+    assert_ne!(lhs, rhs);
+    assert!(lhs.structurally_eq(&rhs));
+    assert_eq!(lhs.structural_hash(), rhs.structural_hash());
+    assert_eq!(lhs.structural_diff(&rhs), None);
+}
+
+#[test]
+fn test_structurally_eq_compares_values() {
+    let cases: &[(&str, &str, &[&str])] = &[
+        ("foo(1)", "foo(2)", &["arg[0]"]),
+        ("foo(1)", "bar(1)", &[]),
+        ("foo(1)", "foo(1, 2)", &[]),
+        ("\"a\"", ":a", &[]),
+        ("a = 1", "a = 1.0", &["value"]),
+        ("def foo; end", "def foo(a); end", &[]),
+    ];
+    for (lhs, rhs, path) in cases.iter() {
+        let lhs = parse(lhs);
+        let rhs = parse(rhs);
+        assert!(!lhs.structurally_eq(&rhs));
+        assert_ne!(lhs.structural_hash(), rhs.structural_hash());
+        assert_eq!(
+            lhs.structural_diff(&rhs),
+            Some(path.iter().map(|s| s.to_string()).collect())
+        );
+    }
+}
+
+#[test]
+fn test_structural_diff_path() {
+    let lhs = parse("[1,2,3].each { |a| puts a + 1; 42 }");
+    let rhs = parse("[1, 2, 3].each do |a|\n  puts(a + 2)\n  42\nend");
+
+    let path = lhs.structural_diff(&rhs).unwrap();
+    assert_eq!(path, vec!["body", "stmt[0]", "arg[0]", "arg[0]"]);
+
+    let lhs = Find::run(&path, &lhs).unwrap().unwrap();
+    let rhs = Find::run(&path, &rhs).unwrap().unwrap();
+    assert!(lhs.structurally_eq(&parse("1")));
+    assert!(rhs.structurally_eq(&parse("2")));
+}
+
+#[test]
+fn test_synthetic_nodes() {
+    let f = NodeFactory::new();
+    let node = f.send(None, "foo", vec![f.str("bar")]);
+
+    assert!(node.structurally_eq(&parse("foo 'bar'")));
+    assert_eq!(
+        node.structural_hash(),
+        parse("foo(\"bar\")").structural_hash()
+    );
+}
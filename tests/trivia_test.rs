@@ -0,0 +1,88 @@
+use lib_ruby_parser::{token_name, Lexer, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(trivia_test)".to_owned(),
+        lossless_tokens: true,
+        ..Default::default()
+    };
+    Parser::new(src.as_bytes(), options).do_parse()
+}
+
+fn assert_lossless(src: &str) {
+    let ParserResult { tokens, input, .. } = parse(src);
+
+    let mut pos = 0;
+    for token in tokens.iter() {
+        assert_eq!(token.loc.begin, pos, "gap or overlap before {:?}", token);
+        pos = token.loc.end;
+    }
+    assert_eq!(pos, src.len());
+
+    let concatenated = tokens
+        .iter()
+        .flat_map(|token| input.bytes[token.loc.begin..token.loc.end].to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(concatenated, src.as_bytes());
+}
+
+fn trivia(src: &str) -> Vec<(&'static str, String)> {
+    parse(src)
+        .tokens
+        .into_iter()
+        .filter(|token| Lexer::is_trivia(token.token_type))
+        .map(|token| (token_name(token.token_type), token.into_string_lossy()))
+        .collect()
+}
+
+#[test]
+fn test_trivia_tokens() {
+    let src = "foo  1 # one\n\nbar \\\n  2\n__END__\ndata\n";
+    assert_eq!(
+        trivia(src),
+        vec![
+            ("tWHITESPACE", "  ".to_owned()),
+            ("tWHITESPACE", " ".to_owned()),
+            ("tCOMMENT", "# one".to_owned()),
+            ("tIGNORED_NL", "\n".to_owned()),
+            ("tWHITESPACE", " ".to_owned()),
+            ("tLINE_CONTINUATION", "\\\n".to_owned()),
+            ("tWHITESPACE", "  ".to_owned()),
+            ("tEND_DATA", "__END__\ndata\n".to_owned()),
+        ]
+    );
+    assert_lossless(src);
+}
+
+#[test]
+fn test_embedded_document() {
+    let src = "foo\n=begin\ndoc\n=end\nbar\n";
+    let trivia = trivia(src);
+    assert_eq!(trivia[0].0, "tEMBDOC");
+    assert!(trivia[0].1.starts_with("=begin\ndoc\n=end"));
+    assert_lossless(src);
+}
+
+#[test]
+fn test_heredocs() {
+    assert_lossless("foo(<<~A, <<-B) # c\n  a\n  A\n  b\n  B\nbar\n");
+}
+
+#[test]
+fn test_strings_and_interpolation() {
+    assert_lossless("a = \"x #{ 1 + 2 } y\"\nb = %w[ p  q ]\nc = /r #{a}/ix\n");
+}
+
+#[test]
+fn test_syntax_error() {
+    assert_lossless("foo(\n  1,\n");
+}
+
+#[test]
+fn test_default_mode() {
+    let options = ParserOptions::default();
+    let ParserResult { tokens, .. } = Parser::new(b"foo # c\n", options).do_parse();
+    assert!(tokens
+        .iter()
+        .all(|token| !Lexer::is_trivia(token.token_type)));
+}
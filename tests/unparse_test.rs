@@ -0,0 +1,159 @@
+use lib_ruby_parser::unparse::unparse;
+use lib_ruby_parser::{Parser, ParserOptions, ParserResult};
+use std::fs;
+
+mod files_under_dir;
+use files_under_dir::files_under_dir;
+
+fn parse(src: &[u8]) -> ParserResult {
+    let options = ParserOptions {
+        buffer_name: "(unparse_test)".to_owned(),
+        ..Default::default()
+    };
+    let parser = Parser::new(src, options);
+
+    // the same locals as in parser_test
+    parser.static_env.declare("foo");
+    parser.static_env.declare("bar");
+    parser.static_env.declare("baz");
+
+    parser.do_parse()
+}
+
+fn fixture_input(path: &str) -> String {
+    let content =
+        fs::read_to_string(path).unwrap_or_else(|_| panic!("failed to read file {:?}", path));
+
+    content
+        .lines()
+        .skip_while(|line| *line != "--INPUT")
+        .skip(1)
+        .take_while(|line| !line.starts_with("--"))
+        .filter(|line| !line.starts_with("// "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn assert_round_trip(src: &str) -> Result<(), String> {
+    let result = parse(src.as_bytes());
+    let ast = match result.ast {
+        Some(ast) if !result.diagnostics.iter().any(|d| d.is_error()) => ast,
+        // invalid input, nothing to compare
+        _ => return Ok(()),
+    };
+
+    let output = unparse(&ast);
+    let reparsed = parse(output.as_bytes());
+
+    if let Some(diagnostic) = reparsed.diagnostics.iter().find(|d| d.is_error()) {
+        return Err(format!(
+            "input:\n{}\noutput:\n{}\nerror: {}\n",
+            src,
+            output,
+            diagnostic.render_message()
+        ));
+    }
+
+    let reparsed = match reparsed.ast {
+        Some(reparsed) => reparsed,
+        None => return Err(format!("input:\n{}\noutput:\n{}\nno AST\n", src, output)),
+    };
+
+    if let Some(path) = ast.structural_diff(&reparsed) {
+        return Err(format!(
+            "input:\n{}\noutput:\n{}\nAST diff at {:?}:\nactual:\n{}\nexpected:\n{}\n",
+            src,
+            output,
+            path,
+            reparsed.inspect(0),
+            ast.inspect(0)
+        ));
+    }
+
+    Ok(())
+}
+
+fn test_dir(dir: &str) {
+    let mut failures = vec![];
+    for filename in files_under_dir(dir) {
+        if let Err(output) = assert_round_trip(&fixture_input(&filename)) {
+            failures.push(format!("{}:\n{}", filename, output));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} fixtures don't round-trip:\n\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn test_round_trip_gen() {
+    test_dir("tests/fixtures/parser/gen")
+}
+
+#[test]
+fn test_round_trip_manual() {
+    test_dir("tests/fixtures/parser/manual")
+}
+
+fn unparsed(src: &str) -> String {
+    unparse(&parse(src.as_bytes()).ast.unwrap())
+}
+
+#[test]
+fn test_precedence() {
+    assert_eq!(unparsed("(1 + 2) * 3"), "(1 + 2) * 3");
+    assert_eq!(unparsed("1 + 2 * 3"), "1 + 2 * 3");
+    assert_eq!(unparsed("1 - (2 - 3)"), "1 - (2 - 3)");
+    assert_eq!(unparsed("2 ** 3 ** 4"), "2 ** 3 ** 4");
+    assert_eq!(unparsed("(-2) ** 2"), "(-2) ** 2");
+    assert_eq!(unparsed("-2 ** 2"), "-2 ** 2");
+    assert_eq!(unparsed("not foo == bar"), "not foo == bar");
+    assert_eq!(unparsed("foo and not bar"), "foo && !bar");
+    assert_eq!(unparsed("foo.bar baz"), "foo.bar(baz)");
+    assert_eq!(
+        unparsed("qux 1 +  2*3 rescue nil"),
+        "qux(1 + 2 * 3) rescue nil"
+    );
+}
+
+#[test]
+fn test_heredoc() {
+    assert_eq!(
+        unparsed("foo(<<~A, 1)\n  a\n  #{b}\nA\n"),
+        "foo(<<~HEREDOC, 1)\na\n#{b()}\nHEREDOC\n"
+    );
+}
+
+#[test]
+fn test_pattern() {
+    assert_eq!(
+        unparsed("case foo; in [1, *] | {b: 2} if bar; end"),
+        "case foo\nin [1, *] | { b: 2 } if bar\nend"
+    );
+}
+
+#[test]
+fn test_round_trip_snippets() {
+    for src in &[
+        "a = b = 1 rescue 2",
+        "\"a#{1}b\" \"c\"",
+        ":\"foo bar\"",
+        "/a\\/b#{c}/im",
+        "x = <<-A\n  a\n  b\nA\n",
+        "foo.bar += 1",
+        "a, (b, *c), = 1, 2",
+        "->(a, b = 1, *c, d:, &e) { a }",
+        "foo { |a, (b, c); d| }",
+        "begin; 1; rescue A, B => e; 2; else; 3; ensure; 4; end",
+        "1 if 2 while 3",
+        "def self.foo(...) = bar(...)",
+    ] {
+        if let Err(output) = assert_round_trip(src) {
+            panic!("{}", output)
+        }
+    }
+}
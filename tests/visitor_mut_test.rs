@@ -0,0 +1,71 @@
+use lib_ruby_parser::nodes::{Int, Lvar};
+use lib_ruby_parser::traverse::{Visitor, VisitorMut};
+use lib_ruby_parser::{Node, Parser, ParserOptions, ParserResult};
+
+fn parse(src: &str) -> Node {
+    let options = ParserOptions {
+        buffer_name: "(visitor_mut_test)".to_owned(),
+        debug: false,
+        ..Default::default()
+    };
+    let parser = Parser::new(src.as_bytes(), options);
+    let ParserResult { ast, .. } = parser.do_parse();
+    ast.expect("expected AST")
+}
+
+struct IntIncrementer;
+
+impl VisitorMut for IntIncrementer {
+    fn on_int(&mut self, node: &mut Int) {
+        let value = node.value.parse::<i32>().unwrap();
+        node.value = (value + 1).to_string();
+    }
+}
+
+struct LvarRenamer;
+
+impl VisitorMut for LvarRenamer {
+    fn on_lvar(&mut self, node: &mut Lvar) {
+        node.name = format!("renamed_{}", node.name);
+    }
+}
+
+#[derive(Default)]
+struct Collector {
+    values: Vec<String>,
+}
+
+impl Visitor for Collector {
+    fn on_int(&mut self, node: &Int) {
+        self.values.push(node.value.clone())
+    }
+
+    fn on_lvar(&mut self, node: &Lvar) {
+        self.values.push(node.name.clone())
+    }
+}
+
+fn collect(node: &Node) -> Vec<String> {
+    let mut collector = Collector::default();
+    collector.visit(node);
+    collector.values
+}
+
+#[test]
+fn it_mutates_nested_nodes() {
+    let mut ast = parse("[1, [2, { 3 => 4 }]].each { |a| a + 5 }");
+    IntIncrementer.visit(&mut ast);
+
+    assert_eq!(collect(&ast), vec!["2", "3", "4", "5", "a", "6"]);
+}
+
+#[test]
+fn it_mutates_optional_children() {
+    let mut ast = parse("a = 1; a if a; def m(b = a); b; end");
+    LvarRenamer.visit(&mut ast);
+
+    assert_eq!(
+        collect(&ast),
+        vec!["1", "renamed_a", "renamed_a", "renamed_b"]
+    );
+}